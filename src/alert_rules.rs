@@ -0,0 +1,502 @@
+// User-defined alert rules evaluated against SystemData
+//
+// A rule addresses any field of `SystemData` through a metric path such as
+// `battery_info.percentage`, `thermal_info.fan_speeds[0]` or
+// `temperature_info[label=~"CPU.*"].temperature`. Paths are resolved against
+// the serde_json form of the sample, so new fields become addressable without
+// touching this module.
+
+use crate::{config::ThresholdConfig, notification::AlertLevel};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlertRule {
+    /// Rules sharing a name form tiers; only the most severe matching one fires
+    pub name: String,
+    pub metric: MetricPath,
+    pub op: Comparator,
+    pub threshold: RuleValue,
+    pub level: AlertLevel,
+    /// How long the condition must hold before the alert fires, e.g. "30s"
+    #[serde(default, rename = "for", with = "duration_str")]
+    pub for_duration: Duration,
+    /// Threshold the value must cross back over before a firing alert resolves;
+    /// defaults to `threshold`
+    #[serde(default)]
+    pub recovery: Option<RuleValue>,
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Supports `{value}`, `{threshold}`, `{instance}` and `{metric}` placeholders
+    #[serde(default)]
+    pub message: Option<String>,
+    /// Sent when the alert clears; same placeholders as `message`
+    #[serde(default)]
+    pub resolved_message: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Comparator {
+    #[serde(rename = ">")]
+    Greater,
+    #[serde(rename = ">=")]
+    GreaterOrEqual,
+    #[serde(rename = "<")]
+    Less,
+    #[serde(rename = "<=")]
+    LessOrEqual,
+    #[serde(rename = "==")]
+    Equal,
+    #[serde(rename = "!=")]
+    NotEqual,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RuleValue {
+    Bool(bool),
+    Number(f64),
+    Text(String),
+}
+
+/// One value a rule's metric path resolved to
+#[derive(Debug, Clone)]
+pub struct ResolvedMetric<'a> {
+    /// Identifies the array element(s) selected, e.g. "CPU die"; empty for scalar paths
+    pub instance: String,
+    pub value: &'a Value,
+}
+
+impl AlertRule {
+    pub fn matches(&self, value: &Value) -> bool {
+        self.op.compare(value, &self.threshold)
+    }
+
+    /// Whether a firing alert should keep firing at this value
+    pub fn still_active(&self, value: &Value) -> bool {
+        self.op.compare(value, self.recovery.as_ref().unwrap_or(&self.threshold))
+    }
+
+    pub fn title(&self) -> String {
+        self.title.clone().unwrap_or_else(|| format!("Alert: {}", self.name))
+    }
+
+    pub fn render_message(&self, instance: &str, value: &Value) -> String {
+        let template = self.message.clone().unwrap_or_else(|| {
+            if instance.is_empty() {
+                "{metric} is {value} ({op} {threshold})".to_string()
+            } else {
+                "{metric} for {instance} is {value} ({op} {threshold})".to_string()
+            }
+        });
+        self.fill_template(&template, instance, value)
+    }
+
+    pub fn render_resolved_message(&self, instance: &str, value: Option<&Value>) -> String {
+        let template = self.resolved_message.clone().unwrap_or_else(|| {
+            match (instance.is_empty(), value.is_some()) {
+                (true, true) => "{metric} is back to {value}",
+                (false, true) => "{metric} for {instance} is back to {value}",
+                (true, false) => "{metric} is no longer reported",
+                (false, false) => "{metric} for {instance} is no longer reported",
+            }
+            .to_string()
+        });
+        self.fill_template(&template, instance, value.unwrap_or(&Value::Null))
+    }
+
+    fn fill_template(&self, template: &str, instance: &str, value: &Value) -> String {
+        template
+            .replace("{value}", &format_value(value))
+            .replace("{threshold}", &self.threshold.to_string())
+            .replace("{instance}", instance)
+            .replace("{metric}", &self.metric.to_string())
+            .replace("{op}", self.op.symbol())
+    }
+}
+
+impl Comparator {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Self::Greater => ">",
+            Self::GreaterOrEqual => ">=",
+            Self::Less => "<",
+            Self::LessOrEqual => "<=",
+            Self::Equal => "==",
+            Self::NotEqual => "!=",
+        }
+    }
+
+    pub fn compare(&self, value: &Value, threshold: &RuleValue) -> bool {
+        if let (Value::String(actual), RuleValue::Text(expected)) = (value, threshold) {
+            return match self {
+                Self::Equal => actual == expected,
+                Self::NotEqual => actual != expected,
+                _ => false,
+            };
+        }
+
+        let (Some(actual), Some(expected)) = (as_number(value), threshold.as_number()) else {
+            return false;
+        };
+        match self {
+            Self::Greater => actual > expected,
+            Self::GreaterOrEqual => actual >= expected,
+            Self::Less => actual < expected,
+            Self::LessOrEqual => actual <= expected,
+            Self::Equal => actual == expected,
+            Self::NotEqual => actual != expected,
+        }
+    }
+}
+
+impl RuleValue {
+    pub fn as_number(&self) -> Option<f64> {
+        match self {
+            Self::Bool(b) => Some(if *b { 1.0 } else { 0.0 }),
+            Self::Number(n) => Some(*n),
+            Self::Text(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for RuleValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bool(b) => write!(f, "{}", b),
+            Self::Number(n) => write!(f, "{}", n),
+            Self::Text(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+/// Numeric view of a JSON value; booleans count as 1/0
+pub fn as_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::Bool(b) => Some(if *b { 1.0 } else { 0.0 }),
+        _ => None,
+    }
+}
+
+fn format_value(value: &Value) -> String {
+    match value {
+        Value::Number(n) if n.is_f64() => format!("{:.1}", n.as_f64().unwrap_or_default()),
+        Value::String(s) => s.clone(),
+        Value::Null => "n/a".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Parsed metric path: dotted field names with optional `[...]` selectors
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct MetricPath {
+    source: String,
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone)]
+enum Segment {
+    Field(String),
+    Index(usize),
+    Wildcard,
+    Filter {
+        field: String,
+        op: FilterOp,
+        value: String,
+        regex: Option<Regex>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FilterOp {
+    Equal,
+    NotEqual,
+    Matches,
+    NotMatches,
+}
+
+impl MetricPath {
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let mut segments = Vec::new();
+        let mut rest = source.trim();
+        if rest.is_empty() {
+            return Err("empty metric path".to_string());
+        }
+
+        while !rest.is_empty() {
+            if let Some(after_bracket) = rest.strip_prefix('[') {
+                let end = find_closing_bracket(after_bracket)
+                    .ok_or_else(|| format!("unclosed '[' in metric path '{}'", source))?;
+                segments.push(parse_selector(after_bracket[..end].trim(), source)?);
+                rest = &after_bracket[end + 1..];
+            } else {
+                let rest_without_dot = if segments.is_empty() {
+                    rest
+                } else {
+                    rest.strip_prefix('.')
+                        .ok_or_else(|| format!("expected '.' or '[' in metric path '{}' at '{}'", source, rest))?
+                };
+                let end = rest_without_dot
+                    .find(['.', '['])
+                    .unwrap_or(rest_without_dot.len());
+                let field = &rest_without_dot[..end];
+                if !is_identifier(field) {
+                    return Err(format!("invalid field name '{}' in metric path '{}'", field, source));
+                }
+                segments.push(Segment::Field(field.to_string()));
+                rest = &rest_without_dot[end..];
+            }
+        }
+
+        Ok(Self {
+            source: source.trim().to_string(),
+            segments,
+        })
+    }
+
+    /// Resolve the path, fanning out over wildcards and filters
+    pub fn resolve<'a>(&self, root: &'a Value) -> Vec<ResolvedMetric<'a>> {
+        let mut current = vec![ResolvedMetric {
+            instance: String::new(),
+            value: root,
+        }];
+
+        for segment in &self.segments {
+            let mut next = Vec::new();
+            for item in current {
+                match segment {
+                    Segment::Field(name) => {
+                        if let Some(value) = item.value.get(name) {
+                            next.push(ResolvedMetric {
+                                instance: item.instance,
+                                value,
+                            });
+                        }
+                    }
+                    Segment::Index(index) => {
+                        if let Some(value) = item.value.get(index) {
+                            next.push(ResolvedMetric {
+                                instance: join_instance(&item.instance, &element_label(value, *index)),
+                                value,
+                            });
+                        }
+                    }
+                    Segment::Wildcard | Segment::Filter { .. } => {
+                        let Some(elements) = item.value.as_array() else {
+                            continue;
+                        };
+                        for (index, value) in elements.iter().enumerate() {
+                            if segment.accepts(value) {
+                                next.push(ResolvedMetric {
+                                    instance: join_instance(&item.instance, &element_label(value, index)),
+                                    value,
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+            current = next;
+        }
+
+        current
+    }
+}
+
+impl Segment {
+    fn accepts(&self, element: &Value) -> bool {
+        let Segment::Filter { field, op, value, regex } = self else {
+            return true;
+        };
+        let actual = match element.get(field) {
+            Some(Value::String(s)) => s.clone(),
+            Some(other) => other.to_string(),
+            None => return false,
+        };
+        match op {
+            FilterOp::Equal => actual == *value,
+            FilterOp::NotEqual => actual != *value,
+            FilterOp::Matches => regex.as_ref().is_some_and(|r| r.is_match(&actual)),
+            FilterOp::NotMatches => regex.as_ref().is_some_and(|r| !r.is_match(&actual)),
+        }
+    }
+}
+
+// The segments are parsed from the source, so equal sources mean equal paths
+impl PartialEq for MetricPath {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl fmt::Display for MetricPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl TryFrom<String> for MetricPath {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::parse(&value)
+    }
+}
+
+impl From<MetricPath> for String {
+    fn from(path: MetricPath) -> Self {
+        path.source
+    }
+}
+
+fn parse_selector(selector: &str, source: &str) -> Result<Segment, String> {
+    if selector == "*" {
+        return Ok(Segment::Wildcard);
+    }
+    if let Ok(index) = selector.parse::<usize>() {
+        return Ok(Segment::Index(index));
+    }
+
+    // Longest operators first so "=~" isn't read as "="
+    let (position, op, op_len) = [("!~", FilterOp::NotMatches), ("=~", FilterOp::Matches), ("!=", FilterOp::NotEqual), ("=", FilterOp::Equal)]
+        .iter()
+        .find_map(|(symbol, op)| selector.find(symbol).map(|pos| (pos, *op, symbol.len())))
+        .ok_or_else(|| format!("invalid selector '[{}]' in metric path '{}'", selector, source))?;
+
+    let field = selector[..position].trim();
+    if !is_identifier(field) {
+        return Err(format!("invalid selector field '{}' in metric path '{}'", field, source));
+    }
+    let raw_value = selector[position + op_len..].trim();
+    let value = raw_value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(raw_value)
+        .to_string();
+
+    let regex = match op {
+        FilterOp::Matches | FilterOp::NotMatches => Some(
+            Regex::new(&format!("^(?:{})$", value))
+                .map_err(|e| format!("invalid regex in metric path '{}': {}", source, e))?,
+        ),
+        _ => None,
+    };
+
+    Ok(Segment::Filter {
+        field: field.to_string(),
+        op,
+        value,
+        regex,
+    })
+}
+
+/// Find the `]` closing a selector, skipping over quoted strings
+fn find_closing_bracket(input: &str) -> Option<usize> {
+    let mut in_quotes = false;
+    for (i, c) in input.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            ']' if !in_quotes => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn is_identifier(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Human-friendly name for an array element: its label/name/pid if it has one
+fn element_label(element: &Value, index: usize) -> String {
+    // 磁盘按挂载点区分：同一设备可能挂载在多处
+    ["label", "mount_point", "name", "device", "pid"]
+        .iter()
+        .find_map(|key| match element.get(key) {
+            Some(Value::String(s)) => Some(s.clone()),
+            Some(Value::Number(n)) => Some(n.to_string()),
+            _ => None,
+        })
+        .unwrap_or_else(|| index.to_string())
+}
+
+fn join_instance(parent: &str, child: &str) -> String {
+    if parent.is_empty() {
+        child.to_string()
+    } else {
+        format!("{}/{}", parent, child)
+    }
+}
+
+/// The CPU, memory, temperature and disk space alerts driven by `[thresholds]`
+pub fn threshold_rules(thresholds: &ThresholdConfig) -> Vec<AlertRule> {
+    let rule = |name: &str, metric: &str, threshold: f64, level: AlertLevel, title: &str, message: &str, resolved: &str| AlertRule {
+        name: name.to_string(),
+        metric: MetricPath::parse(metric).expect("built-in metric path"),
+        op: Comparator::Greater,
+        threshold: RuleValue::Number(threshold),
+        level,
+        for_duration: Duration::from_secs(thresholds.sustain_seconds),
+        recovery: Some(RuleValue::Number(threshold - thresholds.recovery_margin as f64)),
+        title: Some(title.to_string()),
+        message: Some(message.to_string()),
+        resolved_message: Some(resolved.to_string()),
+    };
+
+    vec![
+        rule("cpu", "cpu_info.average_usage", thresholds.cpu_critical as f64, AlertLevel::Critical,
+            "CPU Alert", "CPU usage is critically high: {value}%", "CPU usage is back to {value}%"),
+        rule("cpu", "cpu_info.average_usage", thresholds.cpu_warning as f64, AlertLevel::Warning,
+            "CPU Alert", "CPU usage is high: {value}%", "CPU usage is back to {value}%"),
+        rule("memory", "memory_info.usage_percentage", thresholds.memory_critical as f64, AlertLevel::Critical,
+            "Memory Alert", "Memory usage is critically high: {value}%", "Memory usage is back to {value}%"),
+        rule("memory", "memory_info.usage_percentage", thresholds.memory_warning as f64, AlertLevel::Warning,
+            "Memory Alert", "Memory usage is high: {value}%", "Memory usage is back to {value}%"),
+        rule("temperature", "temperature_info[*].temperature", thresholds.temperature_critical as f64, AlertLevel::Critical,
+            "Temperature Alert", "{instance} temperature is critically high: {value}°C", "{instance} temperature is back to {value}°C"),
+        rule("temperature", "temperature_info[*].temperature", thresholds.temperature_warning as f64, AlertLevel::Warning,
+            "Temperature Alert", "{instance} temperature is high: {value}°C", "{instance} temperature is back to {value}°C"),
+        // 只读镜像（squashfs、ISO）总是显示 100% 已用
+        rule("disk", "disk_info[is_read_only=false].usage_percentage", thresholds.disk_critical as f64, AlertLevel::Critical,
+            "Disk Alert", "{instance} is almost full: {value}% used", "{instance} is back to {value}% used"),
+        rule("disk", "disk_info[is_read_only=false].usage_percentage", thresholds.disk_warning as f64, AlertLevel::Warning,
+            "Disk Alert", "{instance} is filling up: {value}% used", "{instance} is back to {value}% used"),
+    ]
+}
+
+/// Parse durations like "500ms", "30s", "5m" or "1h"; a bare number means seconds
+pub fn parse_duration(input: &str) -> Result<Duration, String> {
+    let input = input.trim();
+    let split = input.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(input.len());
+    let (number, unit) = input.split_at(split);
+    let number: f64 = number
+        .parse()
+        .map_err(|_| format!("invalid duration '{}'", input))?;
+    let seconds = match unit.trim() {
+        "ms" => number / 1000.0,
+        "" | "s" => number,
+        "m" => number * 60.0,
+        "h" => number * 3600.0,
+        other => return Err(format!("unknown duration unit '{}' in '{}'", other, input)),
+    };
+    Duration::try_from_secs_f64(seconds).map_err(|_| format!("duration '{}' is out of range", input))
+}
+
+mod duration_str {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("{}s", duration.as_secs_f64()))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        super::parse_duration(&value).map_err(serde::de::Error::custom)
+    }
+}
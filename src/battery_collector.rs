@@ -1,7 +1,7 @@
 // Fast battery data collector - optimized version
+use crate::error::{run_command, CollectorError, CollectorResult};
 use crate::types::BatteryInfo;
-use std::time::{Duration, Instant};
-use tokio::time::timeout;
+use std::time::Duration;
 use regex::Regex;
 
 #[derive(Debug, Clone)]
@@ -18,142 +18,115 @@ struct BatteryDetailedInfo {
     power_adapter_wattage: f32,
 }
 
-pub struct FastBatteryCollector {
-    last_update: Option<Instant>,
-    cached_data: Option<BatteryInfo>,
-    cache_duration: Duration,
-}
+/// Reads pmset, system_profiler and ioreg on every call. Nothing is cached here:
+/// when every method fails the error is returned, and the collector keeps the
+/// previous reading marked as cached.
+pub struct FastBatteryCollector;
 
 impl FastBatteryCollector {
     pub fn new() -> Self {
-        Self {
-            last_update: None,
-            cached_data: None,
-            cache_duration: Duration::from_secs(5), // 5 second cache
-        }
+        Self
     }
 
-    pub async fn get_battery_info(&mut self) -> BatteryInfo {
-        // Check if cache is valid
-        if let (Some(last_update), Some(cached_data)) = (self.last_update, &self.cached_data) {
-            if last_update.elapsed() < self.cache_duration {
-                return cached_data.clone();
-            }
-        }
-
-        // Try to quickly get real data
-        match self.collect_real_battery_data().await {
-            Ok(battery_info) => {
-                self.cached_data = Some(battery_info.clone());
-                self.last_update = Some(Instant::now());
-                battery_info
-            }
-            Err(_) => {
-                // If failed, return cached data or default data
-                self.cached_data.clone().unwrap_or_else(|| self.get_fallback_data())
-            }
-        }
+    pub async fn get_battery_info(&self) -> CollectorResult<BatteryInfo> {
+        self.collect_real_battery_data().await
     }
 
-    async fn collect_real_battery_data(&self) -> Result<BatteryInfo, Box<dyn std::error::Error>> {
+    async fn collect_real_battery_data(&self) -> CollectorResult<BatteryInfo> {
         let mut battery_info = BatteryInfo::default();
+        // Only fails when every method did; the first error is the one reported
+        let mut first_error: Option<CollectorError> = None;
+        let mut any_succeeded = false;
 
         // Method 1: Use pmset to get basic status (fastest and most reliable)
-        if let Ok(basic_info) = self.get_battery_basic_from_pmset().await {
-            battery_info.percentage = basic_info.percentage;
-            battery_info.is_charging = basic_info.is_charging;
-            battery_info.is_plugged = basic_info.is_plugged;
-            battery_info.time_remaining = basic_info.time_remaining;
+        match self.get_battery_basic_from_pmset().await {
+            Ok(basic_info) => {
+                any_succeeded = true;
+                battery_info.percentage = basic_info.percentage;
+                battery_info.is_charging = basic_info.is_charging;
+                battery_info.is_plugged = basic_info.is_plugged;
+                battery_info.time_remaining = basic_info.time_remaining;
+            }
+            Err(e) => {
+                first_error.get_or_insert(e);
+            }
         }
 
         // Method 2: Use system_profiler to get accurate health information (highest priority)
-        if let Ok(detailed_info) = self.get_battery_detailed_from_profiler().await {
-            battery_info.health_percentage = detailed_info.health_percentage;
-            battery_info.cycle_count = detailed_info.cycle_count;
-            battery_info.power_adapter_wattage = detailed_info.power_adapter_wattage;
+        match self.get_battery_detailed_from_profiler().await {
+            Ok(detailed_info) => {
+                any_succeeded = true;
+                battery_info.health_percentage = detailed_info.health_percentage;
+                battery_info.cycle_count = detailed_info.cycle_count;
+                battery_info.power_adapter_wattage = detailed_info.power_adapter_wattage;
+            }
+            Err(e) => {
+                first_error.get_or_insert(e);
+            }
         }
 
         // Method 3: Use ioreg to get capacity information (supplementary data)
-        if let Ok(capacity_info) = self.get_battery_capacity_from_ioreg().await {
-            battery_info.current_capacity = capacity_info.current_capacity;
-            battery_info.design_capacity = capacity_info.design_capacity;
-            
-            // If system_profiler didn't get cycle count, use ioreg data
-            if battery_info.cycle_count == 0 {
-                battery_info.cycle_count = capacity_info.cycle_count;
+        match self.get_battery_capacity_from_ioreg().await {
+            Ok(capacity_info) => {
+                any_succeeded = true;
+                battery_info.current_capacity = capacity_info.current_capacity;
+                battery_info.design_capacity = capacity_info.design_capacity;
+                
+                // If system_profiler didn't get cycle count, use ioreg data
+                if battery_info.cycle_count == 0 {
+                    battery_info.cycle_count = capacity_info.cycle_count;
+                }
+                
+                // Only use ioreg to calculate health when system_profiler completely fails
+                if battery_info.health_percentage == 0.0 && capacity_info.design_capacity > 0 {
+                    battery_info.health_percentage = 
+                        (capacity_info.current_capacity as f32 / capacity_info.design_capacity as f32) * 100.0;
+                }
             }
-            
-            // Only use ioreg to calculate health when system_profiler completely fails
-            if battery_info.health_percentage == 0.0 && capacity_info.design_capacity > 0 {
-                battery_info.health_percentage = 
-                    (capacity_info.current_capacity as f32 / capacity_info.design_capacity as f32) * 100.0;
+            Err(e) => {
+                first_error.get_or_insert(e);
             }
         }
 
-        Ok(battery_info)
+        match first_error {
+            Some(e) if !any_succeeded => Err(e),
+            _ => Ok(battery_info),
+        }
     }
 
-    async fn get_battery_basic_from_pmset(&self) -> Result<BatteryInfo, Box<dyn std::error::Error>> {
+    async fn get_battery_basic_from_pmset(&self) -> CollectorResult<BatteryInfo> {
         // pmset -g batt - quickly get basic battery status
-        let pmset_future = tokio::process::Command::new("pmset")
-            .arg("-g")
-            .arg("batt")
-            .output();
-
-        let output = timeout(Duration::from_secs(1), pmset_future).await??;
-        
-        if !output.status.success() {
-            return Err("pmset command failed".into());
-        }
-
-        let output_str = String::from_utf8_lossy(&output.stdout);
-        self.parse_pmset_basic(&output_str)
+        let output = run_command("pmset", &["-g", "batt"], Duration::from_secs(1)).await?;
+        self.parse_pmset_basic(&output)
     }
 
-    async fn get_battery_capacity_from_ioreg(&self) -> Result<BatteryCapacityInfo, Box<dyn std::error::Error>> {
-        // ioreg -l | grep -i "Capacity" - get capacity information
-        let ioreg_future = tokio::process::Command::new("sh")
-            .arg("-c")
-            .arg("ioreg -l | grep -i 'Capacity\\|CycleCount'")
-            .output();
-
-        let output = timeout(Duration::from_secs(2), ioreg_future).await??;
-        
-        if !output.status.success() {
-            return Err("ioreg capacity command failed".into());
-        }
+    async fn get_battery_capacity_from_ioreg(&self) -> CollectorResult<BatteryCapacityInfo> {
+        // ioreg -rn AppleSmartBattery - only the battery's properties, not the whole registry
+        let output = run_command("ioreg", &["-rn", "AppleSmartBattery"], Duration::from_secs(2)).await?;
 
-        let output_str = String::from_utf8_lossy(&output.stdout);
-        self.parse_ioreg_capacity(&output_str)
+        // parse_ioreg_capacity only looks at the capacity and cycle count keys
+        self.parse_ioreg_capacity(&output)
     }
 
-    async fn get_battery_detailed_from_profiler(&self) -> Result<BatteryDetailedInfo, Box<dyn std::error::Error>> {
+    async fn get_battery_detailed_from_profiler(&self) -> CollectorResult<BatteryDetailedInfo> {
         // system_profiler SPPowerDataType - get detailed information
-        let profiler_future = tokio::process::Command::new("system_profiler")
-            .arg("SPPowerDataType")
-            .output();
-
-        let output = timeout(Duration::from_secs(3), profiler_future).await??;
-        
-        if !output.status.success() {
-            return Err("system_profiler command failed".into());
-        }
-
-        let output_str = String::from_utf8_lossy(&output.stdout);
-        self.parse_profiler_detailed(&output_str)
+        let output = run_command("system_profiler", &["SPPowerDataType"], Duration::from_secs(3)).await?;
+        self.parse_profiler_detailed(&output)
     }
 
-    fn parse_pmset_basic(&self, output: &str) -> Result<BatteryInfo, Box<dyn std::error::Error>> {
+    fn parse_pmset_basic(&self, output: &str) -> CollectorResult<BatteryInfo> {
         let mut battery_info = BatteryInfo::default();
         
         // Optimized regex based on actual output
         // Actual output: "-InternalBattery-0 (id=20775011)	98%; charging; 0:13 remaining present: true"
         let percentage_regex = Regex::new(r"(\d+)%").unwrap();
         let time_regex = Regex::new(r"(\d+):(\d+) remaining").unwrap();
+        let mut recognized = false;
         
         for line in output.lines() {
             // Check if this is a battery status line
             if line.contains("InternalBattery") {
+                recognized = true;
                 // Extract battery percentage
                 if let Some(caps) = percentage_regex.captures(line) {
                     if let Ok(percentage) = caps[1].parse::<f32>() {
@@ -180,14 +153,18 @@ impl FastBatteryCollector {
             
             // Check power status line
             if line.contains("Now drawing from") {
+                recognized = true;
                 battery_info.is_plugged = line.contains("AC Power");
             }
         }
-        
+
+        if !recognized {
+            return Err(CollectorError::parse("pmset", output.lines().next().unwrap_or("")));
+        }
         Ok(battery_info)
     }
 
-    fn parse_ioreg_capacity(&self, output: &str) -> Result<BatteryCapacityInfo, Box<dyn std::error::Error>> {
+    fn parse_ioreg_capacity(&self, output: &str) -> CollectorResult<BatteryCapacityInfo> {
         let mut capacity_info = BatteryCapacityInfo {
             current_capacity: 0,
             design_capacity: 0,
@@ -235,11 +212,15 @@ impl FastBatteryCollector {
                 }
             }
         }
-        
+
+        // 台式机的 IORegistry 里没有电池
+        if capacity_info.design_capacity == 0 && capacity_info.current_capacity == 0 {
+            return Err(CollectorError::NotFound("battery in the IORegistry".to_string()));
+        }
         Ok(capacity_info)
     }
 
-    fn parse_profiler_detailed(&self, output: &str) -> Result<BatteryDetailedInfo, Box<dyn std::error::Error>> {
+    fn parse_profiler_detailed(&self, output: &str) -> CollectorResult<BatteryDetailedInfo> {
         let mut detailed_info = BatteryDetailedInfo {
             health_percentage: 0.0,
             cycle_count: 0,
@@ -300,7 +281,7 @@ impl FastBatteryCollector {
     }
 
     #[allow(dead_code)]
-    fn parse_ioreg_output(&self, output: &str) -> Result<BatteryInfo, Box<dyn std::error::Error>> {
+    fn parse_ioreg_output(&self, output: &str) -> CollectorResult<BatteryInfo> {
         let mut battery_info = BatteryInfo::default();
 
         for line in output.lines() {
@@ -403,17 +384,10 @@ impl FastBatteryCollector {
         }
         None
     }
+}
 
-    #[allow(dead_code)]
-    async fn get_battery_from_sysfs(&self) -> Result<BatteryInfo, Box<dyn std::error::Error>> {
-        // macOS 没有 /sys/class/power_supply，但可以尝试其他系统文件
-        // 这里返回一个基本的电池信息
-        Err("sysfs not available on macOS".into())
-    }
-
-    fn get_fallback_data(&self) -> BatteryInfo {
-        // Only return fallback when all real data collection fails
-        // This should rarely be used - prefer real data collection
-        BatteryInfo::default()
+impl Default for FastBatteryCollector {
+    fn default() -> Self {
+        Self::new()
     }
 }
\ No newline at end of file
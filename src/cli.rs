@@ -4,13 +4,24 @@ use termion::{
     event::{Event, Key},
     input::TermRead,
 };
-use tokio::{process::Command as tokio_comm, sync::mpsc as tokio_mpsc, time::{timeout, Duration}};
+use tokio::sync::mpsc as tokio_mpsc;
+use crate::error::{CollectorError, CollectorResult};
 
 #[derive(Debug)]
 pub struct CliArgs {
     pub refresh_rate: Option<u64>,
     pub minimal_mode: bool,
     pub config_file: Option<String>,
+    pub headless: bool,
+    pub metrics_address: Option<String>,
+    pub mode: RunMode,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RunMode {
+    Live,
+    Record { output: String },
+    Replay { input: String, speed: f64 },
 }
 
 pub fn parse_args() -> CliArgs {
@@ -25,6 +36,7 @@ pub fn parse_args() -> CliArgs {
                 .long("refresh")
                 .value_name("SECONDS")
                 .help("Set refresh rate in seconds (default: 1)")
+                .global(true)
                 .value_parser(clap::value_parser!(u64)),
         )
         .arg(
@@ -32,6 +44,7 @@ pub fn parse_args() -> CliArgs {
                 .short('m')
                 .long("minimal")
                 .help("Use minimal display mode")
+                .global(true)
                 .action(clap::ArgAction::SetTrue),
         )
         .arg(
@@ -39,44 +52,92 @@ pub fn parse_args() -> CliArgs {
                 .short('c')
                 .long("config")
                 .value_name("FILE")
-                .help("Specify a custom configuration file"),
+                .help("Specify a custom configuration file")
+                .global(true),
+        )
+        .arg(
+            Arg::new("headless")
+                .long("headless")
+                .visible_alias("daemon")
+                .help("Run without the terminal UI (alerts and exporter only); SIGHUP reloads the config")
+                .action(clap::ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("metrics-addr")
+                .long("metrics-addr")
+                .value_name("ADDR")
+                .help("Serve Prometheus metrics on ADDR, e.g. 0.0.0.0:9184")
+                .global(true),
+        )
+        .subcommand(
+            Command::new("record")
+                .about("Monitor live and save every sample to a JSON Lines session file")
+                .arg(
+                    Arg::new("out")
+                        .short('o')
+                        .long("out")
+                        .value_name("FILE")
+                        .help("Session file to write")
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            Command::new("replay")
+                .about("Run the monitor from a recorded session file instead of live data")
+                .arg(
+                    Arg::new("file")
+                        .value_name("FILE")
+                        .help("Session file to replay")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("speed")
+                        .short('s')
+                        .long("speed")
+                        .value_name("FACTOR")
+                        .help("Playback speed, e.g. 4x")
+                        .default_value("1x")
+                        .value_parser(parse_speed),
+                ),
         )
         .get_matches();
 
+    let mode = match matches.subcommand() {
+        Some(("record", sub)) => RunMode::Record {
+            output: sub.get_one::<String>("out").cloned().unwrap_or_default(),
+        },
+        Some(("replay", sub)) => RunMode::Replay {
+            input: sub.get_one::<String>("file").cloned().unwrap_or_default(),
+            speed: sub.get_one::<f64>("speed").copied().unwrap_or(1.0),
+        },
+        _ => RunMode::Live,
+    };
+
     CliArgs {
         refresh_rate: matches.get_one::<u64>("refresh").copied(),
         minimal_mode: matches.get_flag("minimal"),
         config_file: matches.get_one::<String>("config").cloned(),
+        headless: matches.get_flag("headless"),
+        metrics_address: matches.get_one::<String>("metrics-addr").cloned(),
+        mode,
     }
 }
 
-pub async fn check_root() -> Result<(), Box<dyn std::error::Error>> {
-    if unsafe { getuid() != 0 } {
-        return Err("This program requires root privileges to access system metrics.".into());
+/// Parse a replay speed such as "4x", "0.5x" or "2"
+pub fn parse_speed(value: &str) -> Result<f64, String> {
+    let number = value.trim().trim_end_matches(['x', 'X']);
+    match number.parse::<f64>() {
+        Ok(speed) if speed.is_finite() && speed > 0.0 => Ok(speed),
+        _ => Err(format!("invalid speed '{}', expected something like 4x", value)),
     }
-    Ok(())
 }
 
-pub async fn get_powermetrics_output() -> Result<String, Box<dyn std::error::Error>> {
-    let powermetrics_future = tokio_comm::new("powermetrics")
-        .arg("-n")
-        .arg("1")
-        .arg("--samplers")
-        .arg("cpu_power,gpu_power")
-        .output();
-    
-    // Add 5 second timeout to prevent infinite waiting - this fixes potential deadlock
-    match timeout(Duration::from_secs(5), powermetrics_future).await {
-        Ok(Ok(output)) => {
-            if !output.status.success() {
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                return Err(format!("Failed to run powermetrics: {}", stderr).into());
-            }
-            Ok(String::from_utf8_lossy(&output.stdout).into_owned())
-        }
-        Ok(Err(e)) => Err(format!("Failed to execute powermetrics: {}", e).into()),
-        Err(_) => Err("Powermetrics command timed out after 5 seconds - this may indicate a system issue".into()),
+pub async fn check_root() -> CollectorResult<()> {
+    if unsafe { getuid() != 0 } {
+        return Err(CollectorError::PermissionDenied("system metrics (powermetrics, RAPL counters)".to_string()));
     }
+    Ok(())
 }
 
 #[derive(Debug, Clone)]
@@ -84,37 +145,50 @@ pub enum InputEvent {
     Quit,
     NextTab,
     PreviousTab,
+    /// Jump straight to a tab by its position in the tab bar
+    SelectTab(usize),
     ToggleNotifications,
     Refresh,
+    /// Any other key, interpreted by the active view
+    Key(Key),
 }
 
-pub async fn handle_input() -> tokio_mpsc::Receiver<InputEvent> {
+/// Map a key press to an event. While `text_entry` is set (e.g. typing a filter),
+/// every key except Ctrl+C goes to the view untouched.
+pub fn map_key(key: Key, text_entry: bool) -> InputEvent {
+    match key {
+        Key::Ctrl('c') => InputEvent::Quit,
+        _ if text_entry => InputEvent::Key(key),
+        Key::Char('q') => InputEvent::Quit,
+        Key::Right | Key::Char('\t') => InputEvent::NextTab,
+        Key::Left | Key::BackTab => InputEvent::PreviousTab,
+        Key::Char(c @ '1'..='9') => InputEvent::SelectTab(c as usize - '1' as usize),
+        Key::Char('n') => InputEvent::ToggleNotifications,
+        Key::Char('r') => InputEvent::Refresh,
+        _ => InputEvent::Key(key),
+    }
+}
+
+/// Read keys from stdin. They are passed on raw and mapped with `map_key` by the
+/// main loop, which knows whether a text field currently has focus.
+pub async fn handle_input() -> tokio_mpsc::Receiver<Key> {
     let (tx, rx) = tokio_mpsc::channel(32);
 
-    tokio::spawn(async move {
+    // A plain thread: blocked on stdin it must not hold up the runtime's shutdown
+    std::thread::spawn(move || {
         let stdin = std::io::stdin();
 
         for event in stdin.events() {
-            if let Ok(event) = event {
-                let input_event = match event {
-                    Event::Key(Key::Char('q')) | Event::Key(Key::Ctrl('c')) => Some(InputEvent::Quit),
-                    Event::Key(Key::Right) | Event::Key(Key::Char('\t')) => Some(InputEvent::NextTab),
-                    Event::Key(Key::Left) | Event::Key(Key::BackTab) => Some(InputEvent::PreviousTab),
-                    Event::Key(Key::Char('n')) => Some(InputEvent::ToggleNotifications),
-                    Event::Key(Key::Char('r')) => Some(InputEvent::Refresh),
-                    _ => None,
-                };
-
-                if let Some(event) = input_event {
-                    let should_quit = matches!(event, InputEvent::Quit);
-                    if tx.send(event).await.is_err() {
-                        break;
-                    }
+            if let Ok(Event::Key(key)) = event {
+                // Ctrl+C quits even while typing into a text field
+                let should_quit = key == Key::Ctrl('c');
+                if tx.blocking_send(key).is_err() {
+                    break;
+                }
                     
-                    // Exit on quit
-                    if should_quit {
-                        break;
-                    }
+                // Exit on quit
+                if should_quit {
+                    break;
                 }
             }
         }
@@ -1,6 +1,13 @@
+use crate::alert_rules::{threshold_rules, AlertRule};
+use crate::data_collector::CollectorSchedule;
+use crate::history::DEFAULT_TRACKED_PROCESSES;
+use crate::notification::AlertLevel;
+use crate::ui::Tab;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::Path;
+use std::time::Duration;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -9,6 +16,12 @@ pub struct Config {
     pub thresholds: ThresholdConfig,
     pub display: DisplayConfig,
     pub notifications: NotificationConfig,
+    #[serde(default)]
+    pub exporter: ExporterConfig,
+    #[serde(default)]
+    pub alerts: Vec<AlertRule>,
+    #[serde(default)]
+    pub schedule: ScheduleConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,6 +32,33 @@ pub struct ThresholdConfig {
     pub memory_critical: u16,
     pub temperature_warning: f32,
     pub temperature_critical: f32,
+    /// Seconds a threshold must be exceeded before its alert fires
+    #[serde(default = "default_sustain_seconds")]
+    pub sustain_seconds: u64,
+    /// How far below a threshold the value must drop before the alert resolves
+    #[serde(default = "default_recovery_margin")]
+    pub recovery_margin: f32,
+    /// Used space of a mounted filesystem, %
+    #[serde(default = "default_disk_warning")]
+    pub disk_warning: f32,
+    #[serde(default = "default_disk_critical")]
+    pub disk_critical: f32,
+}
+
+fn default_sustain_seconds() -> u64 {
+    10
+}
+
+fn default_recovery_margin() -> f32 {
+    5.0
+}
+
+fn default_disk_warning() -> f32 {
+    85.0
+}
+
+fn default_disk_critical() -> f32 {
+    95.0
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,12 +68,158 @@ pub struct DisplayConfig {
     pub show_processes: bool,
     pub show_history: bool,
     pub history_size: usize,
+    /// Tab shown when the full layout starts
+    #[serde(default)]
+    pub active_tab: Tab,
+    /// Number of busiest processes that get their own CPU/memory/disk history
+    #[serde(default = "default_tracked_processes")]
+    pub tracked_processes: usize,
+    /// Interfaces shown and counted in network totals; `*` and `?` wildcards, empty means all
+    #[serde(default)]
+    pub interface_include: Vec<String>,
+    /// Interfaces hidden even when they match `interface_include`
+    #[serde(default = "default_interface_exclude")]
+    pub interface_exclude: Vec<String>,
+}
+
+fn default_tracked_processes() -> usize {
+    DEFAULT_TRACKED_PROCESSES
+}
+
+// 默认隐藏回环、VPN 隧道和虚拟网桥
+fn default_interface_exclude() -> Vec<String> {
+    ["lo", "lo0", "utun*", "bridge*"].iter().map(|p| p.to_string()).collect()
+}
+
+impl DisplayConfig {
+    pub fn interface_filter(&self) -> InterfaceFilter {
+        InterfaceFilter {
+            include: self.interface_include.clone(),
+            exclude: self.interface_exclude.clone(),
+        }
+    }
+}
+
+/// Include/exclude wildcard patterns selecting network interfaces
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InterfaceFilter {
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+}
+
+impl InterfaceFilter {
+    pub fn matches(&self, name: &str) -> bool {
+        (self.include.is_empty() || self.include.iter().any(|p| wildcard_match(p, name)))
+            && !self.exclude.iter().any(|p| wildcard_match(p, name))
+    }
+}
+
+/// Shell-style match where `*` is any run of characters and `?` a single one
+pub fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // 最近一个 `*` 的位置，以及它当前吞掉的文本终点，用于回溯
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NotificationConfig {
     pub enabled: bool,
     pub cooldown_seconds: u64,
+    /// Where notifications go; empty means the platform's desktop notifications
+    #[serde(default)]
+    pub sinks: Vec<SinkConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SinkConfig {
+    #[serde(flatten)]
+    pub kind: SinkKind,
+    /// Alert levels routed to this sink; empty means all levels
+    #[serde(default)]
+    pub levels: Vec<AlertLevel>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum SinkKind {
+    /// macOS Notification Center via osascript
+    Osascript,
+    /// freedesktop notifications via notify-send
+    Freedesktop,
+    /// HTTP POST with a JSON body
+    Webhook {
+        url: String,
+        #[serde(default)]
+        headers: BTreeMap<String, String>,
+    },
+    /// Run a program with the alert in SYSALERT_* environment variables
+    Command {
+        command: String,
+        #[serde(default)]
+        args: Vec<String>,
+    },
+    /// Append one JSON line per notification
+    Log { path: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExporterConfig {
+    pub enabled: bool,
+    pub listen_address: String,
+    /// Leave out metric groups that are estimated, cached or stale
+    #[serde(default)]
+    pub measured_only: bool,
+}
+
+impl Default for ExporterConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            listen_address: "127.0.0.1:9184".to_string(),
+            measured_only: false,
+        }
+    }
+}
+
+/// Seconds between runs of the slower collectors; CPU, memory, network and
+/// processes are collected every `refresh_rate`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleConfig {
+    pub disk_seconds: u64,
+    pub thermal_seconds: u64,
+    pub health_seconds: u64,
+    pub battery_seconds: u64,
+}
+
+impl Default for ScheduleConfig {
+    fn default() -> Self {
+        let schedule = CollectorSchedule::default();
+        Self {
+            disk_seconds: schedule.disk.as_secs(),
+            thermal_seconds: schedule.thermal.as_secs(),
+            health_seconds: schedule.health.as_secs(),
+            battery_seconds: schedule.battery.as_secs(),
+        }
+    }
 }
 
 impl Default for Config {
@@ -48,6 +234,10 @@ impl Default for Config {
                 memory_critical: 90,
                 temperature_warning: 70.0,
                 temperature_critical: 85.0,
+                sustain_seconds: default_sustain_seconds(),
+                recovery_margin: default_recovery_margin(),
+                disk_warning: default_disk_warning(),
+                disk_critical: default_disk_critical(),
             },
             display: DisplayConfig {
                 show_temperatures: true,
@@ -55,11 +245,19 @@ impl Default for Config {
                 show_processes: true,
                 show_history: true,
                 history_size: 60,
+                active_tab: Tab::default(),
+                tracked_processes: DEFAULT_TRACKED_PROCESSES,
+                interface_include: Vec::new(),
+                interface_exclude: default_interface_exclude(),
             },
             notifications: NotificationConfig {
                 enabled: true,
                 cooldown_seconds: 30,
+                sinks: Vec::new(),
             },
+            exporter: ExporterConfig::default(),
+            alerts: Vec::new(),
+            schedule: ScheduleConfig::default(),
         }
     }
 }
@@ -77,6 +275,25 @@ impl Config {
         Ok(())
     }
 
+    /// Built-in threshold alerts followed by the user-defined `[[alerts]]` rules
+    pub fn alert_rules(&self) -> Vec<AlertRule> {
+        let mut rules = threshold_rules(&self.thresholds);
+        rules.extend(self.alerts.iter().cloned());
+        rules
+    }
+
+    /// Collector intervals; zero is treated as one second so no task spins
+    pub fn collector_schedule(&self) -> CollectorSchedule {
+        let seconds = |s: u64| Duration::from_secs(s.max(1));
+        CollectorSchedule {
+            system: seconds(self.refresh_rate),
+            disk: seconds(self.schedule.disk_seconds),
+            thermal: seconds(self.schedule.thermal_seconds),
+            health: seconds(self.schedule.health_seconds),
+            battery: seconds(self.schedule.battery_seconds),
+        }
+    }
+
     pub fn merge_with_cli(&mut self, refresh_rate: Option<u64>, minimal_mode: bool) {
         if let Some(rate) = refresh_rate {
             self.refresh_rate = rate;
@@ -1,77 +1,384 @@
-use crate::{cli::get_powermetrics_output, battery_collector::FastBatteryCollector, types::*};
-use sysinfo::{Components, Networks, System};
-use std::time::{Duration, Instant};
+// Data collection on independent schedules
+//
+// Each group of collectors runs as its own Tokio task with its own interval and
+// writes into a shared latest-value store, so a slow `system_profiler` call only
+// delays the battery figures. `SystemData` snapshots are assembled from the
+// store whenever the UI refreshes, and record for each metric group whether it
+// was measured, estimated or cached, by which source and when.
+
+use crate::{
+    error::{CollectorError, CollectorResult},
+    rates::CounterRates,
+    sources::{unsupported, BatterySource, CpuPowerSource, DiskIoSource, HealthSource, MetricSources, ThermalSource},
+    types::*,
+};
+use sysinfo::{Components, Disks, Networks, System, Users};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+/// How often each collector group runs
+#[derive(Debug, Clone, PartialEq)]
+pub struct CollectorSchedule {
+    /// CPU, power, memory, network, temperatures and processes
+    pub system: Duration,
+    /// Filesystems and disk I/O
+    pub disk: Duration,
+    pub thermal: Duration,
+    /// Uptime and load averages
+    pub health: Duration,
+    /// pmset, ioreg and `system_profiler` on macOS
+    pub battery: Duration,
+}
+
+impl Default for CollectorSchedule {
+    fn default() -> Self {
+        Self {
+            system: Duration::from_secs(1),
+            disk: Duration::from_secs(5),
+            thermal: Duration::from_secs(2),
+            health: Duration::from_secs(10),
+            battery: Duration::from_secs(30),
+        }
+    }
+}
+
+// `SystemData` fields read straight from sysinfo, used as keys of `SystemData::provenance`
+const SYSINFO_FIELDS: &[&str] = &[
+    "system_info",
+    "cpu_info",
+    "memory_info",
+    "network_info",
+    "temperature_info",
+    "process_info",
+];
+
+/// Values older than this many collection intervals are stale
+const STALE_AFTER_INTERVALS: u32 = 3;
+
+/// Source name recorded for CPU power derived from CPU usage
+const USAGE_ESTIMATE: &str = "usage-estimate";
 
 pub struct DataCollector {
-    system: System,
-    networks: Networks,
-    components: Components,
-    last_powermetrics: Option<Instant>,
-    cached_cpu_metrics: Option<CPUMetrics>,
-    powermetrics_cache_duration: Duration,
-    battery_collector: FastBatteryCollector,
+    latest: Arc<Mutex<LatestValues>>,
+    schedule: watch::Sender<CollectorSchedule>,
+    tasks: Vec<JoinHandle<()>>,
 }
 
 impl DataCollector {
-    pub fn new() -> Self {
-        Self {
-            system: System::new_all(),
-            networks: Networks::new_with_refreshed_list(),
-            components: Components::new_with_refreshed_list(),
-            last_powermetrics: None,
-            cached_cpu_metrics: None,
-            powermetrics_cache_duration: Duration::from_secs(2),
-            battery_collector: FastBatteryCollector::new(),
+    /// Start one task per collector group; must be called from inside a Tokio runtime
+    pub fn start(sources: MetricSources, schedule: CollectorSchedule) -> Self {
+        let latest = Arc::new(Mutex::new(LatestValues::default()));
+        let (sender, receiver) = watch::channel(schedule);
+        let MetricSources { cpu_power, thermal, battery, health, disk_io, cpu_topology } = sources;
+
+        let system = SystemCollector::new(cpu_power, cpu_topology);
+        let tasks = vec![
+            tokio::spawn(run_system(system, latest.clone(), receiver.clone())),
+            tokio::spawn(run_disk(DiskCollector::new(disk_io), latest.clone(), receiver.clone())),
+            tokio::spawn(run_thermal(thermal, latest.clone(), receiver.clone())),
+            tokio::spawn(run_health(health, latest.clone(), receiver.clone())),
+            tokio::spawn(run_battery(battery, latest.clone(), receiver)),
+        ];
+
+        Self { latest, schedule: sender, tasks }
+    }
+
+    /// The newest value of every field; `None` until CPU and memory have been collected once
+    pub fn snapshot(&self) -> Option<SystemData> {
+        lock(&self.latest).snapshot()
+    }
+
+    /// Apply new intervals; each task picks its own up without waiting out the old one
+    pub fn set_schedule(&self, schedule: CollectorSchedule) {
+        self.schedule.send_replace(schedule);
+    }
+}
+
+impl Drop for DataCollector {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+// 锁只在写入/读取时短暂持有，从不跨 await
+fn lock(latest: &Mutex<LatestValues>) -> MutexGuard<'_, LatestValues> {
+    latest.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Sleep for the group's interval, starting over whenever the schedule changes
+async fn wait_for_next(schedule: &mut watch::Receiver<CollectorSchedule>, interval: fn(&CollectorSchedule) -> Duration) {
+    loop {
+        let period = interval(&schedule.borrow_and_update());
+        tokio::select! {
+            _ = tokio::time::sleep(period) => return,
+            changed = schedule.changed() => {
+                if changed.is_err() {
+                    tokio::time::sleep(period).await;
+                    return;
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+struct SystemValues {
+    system_info: SystemInfo,
+    cpu_info: CpuInfo,
+    memory_info: MemoryInfo,
+    network_info: Vec<NetworkInterface>,
+    temperature_info: Vec<TemperatureInfo>,
+    process_info: Vec<ProcessInfo>,
+    performance_metrics: PerformanceMetrics,
+}
+
+/// The latest value written by each collector task
+#[derive(Default)]
+struct LatestValues {
+    system: Option<SystemValues>,
+    disk_info: Vec<DiskInfo>,
+    disk_io: Vec<DiskIoInfo>,
+    battery_info: BatteryInfo,
+    thermal_info: ThermalInfo,
+    system_health: SystemHealthInfo,
+    provenance: BTreeMap<&'static str, Provenance>,
+    // 每个子系统最近一次失败的原因，成功后清除
+    issues: BTreeMap<&'static str, CollectorIssue>,
+}
+
+impl LatestValues {
+    fn snapshot(&self) -> Option<SystemData> {
+        let system = self.system.clone()?;
+        Some(SystemData {
+            system_info: system.system_info,
+            cpu_info: system.cpu_info,
+            memory_info: system.memory_info,
+            network_info: system.network_info,
+            disk_info: self.disk_info.clone(),
+            disk_io: self.disk_io.clone(),
+            temperature_info: system.temperature_info,
+            process_info: system.process_info,
+            battery_info: self.battery_info.clone(),
+            thermal_info: self.thermal_info.clone(),
+            performance_metrics: system.performance_metrics,
+            system_health: self.system_health.clone(),
+            timestamp: SystemTime::now(),
+            collector_issues: self.issues.values().cloned().collect(),
+            provenance: self.provenance.iter().map(|(group, p)| (group.to_string(), p.clone())).collect(),
+        })
+    }
+
+    /// Note that `groups` were just produced by `source`
+    fn record(&mut self, groups: &[&'static str], origin: DataOrigin, source: &str, interval: Duration) {
+        let provenance = Provenance {
+            origin,
+            source: source.to_string(),
+            measured_at: SystemTime::now(),
+            max_age: interval * STALE_AFTER_INTERVALS,
+        };
+        for group in groups {
+            self.provenance.insert(group, provenance.clone());
+        }
+    }
+
+    /// Keep the values of `groups`, which are now older than their failing source's last attempt
+    fn mark_cached(&mut self, groups: &[&'static str]) {
+        for group in groups {
+            if let Some(provenance) = self.provenance.get_mut(group) {
+                provenance.origin = DataOrigin::Cached;
+            }
+        }
+    }
+
+    /// Remember why `subsystem` failed, or forget it once it works again.
+    /// Logs only changes so a permanently missing source doesn't flood the log.
+    fn track<T>(&mut self, subsystem: &'static str, source: &str, result: CollectorResult<T>) -> Option<T> {
+        match result {
+            Ok(value) => {
+                if self.issues.remove(subsystem).is_some() {
+                    log::info!("{} data is available again from {}", subsystem, source);
+                }
+                Some(value)
+            }
+            Err(e) => {
+                let issue = CollectorIssue {
+                    subsystem: subsystem.to_string(),
+                    source: source.to_string(),
+                    kind: e.kind().to_string(),
+                    message: e.to_string(),
+                };
+                if self.issues.get(subsystem) != Some(&issue) {
+                    log::warn!("No {} data from {} ({}): {}", subsystem, source, issue.kind, issue.message);
+                    self.issues.insert(subsystem, issue);
+                }
+                None
+            }
+        }
+    }
+}
+
+async fn run_system(mut collector: SystemCollector, latest: Arc<Mutex<LatestValues>>, mut schedule: watch::Receiver<CollectorSchedule>) {
+    loop {
+        collector.refresh();
+        let cpu_usages = collector.cpu_usages();
+        let power_result = collector.cpu_power.sample().await;
+        let power_source = collector.cpu_power.name();
+
+        // If the power source fails, use fallback metrics
+        let power_metrics = lock(&latest).track("cpu_power", power_source, power_result);
+        let (power_metrics, power_origin, power_source) = match power_metrics {
+            Some(metrics) => (metrics, DataOrigin::Measured, power_source),
+            None => (collector.get_fallback_cpu_metrics(), DataOrigin::Estimated, USAGE_ESTIMATE),
+        };
+        let values = collector.collect(cpu_usages, power_metrics);
+
+        {
+            let interval = schedule.borrow().system;
+            let mut latest_values = lock(&latest);
+            latest_values.system = Some(values);
+            latest_values.record(SYSINFO_FIELDS, DataOrigin::Measured, "sysinfo", interval);
+            // 性能指标由功耗推算，功耗是估算值时它也是
+            latest_values.record(&["cpu_info.power_metrics", "performance_metrics"], power_origin, power_source, interval);
+        }
+
+        wait_for_next(&mut schedule, |s| s.system).await;
+    }
+}
+
+async fn run_disk(mut collector: DiskCollector, latest: Arc<Mutex<LatestValues>>, mut schedule: watch::Receiver<CollectorSchedule>) {
+    loop {
+        collector.disks.refresh(true);
+        let (disk_io, disk_io_source) = collector.collect_disk_io().await;
+        let disk_info = collector.collect_disk_info();
+
+        {
+            let interval = schedule.borrow().disk;
+            let mut latest_values = lock(&latest);
+            latest_values.disk_info = disk_info;
+            latest_values.disk_io = disk_io;
+            latest_values.record(&["disk_info"], DataOrigin::Measured, "sysinfo", interval);
+            latest_values.record(&["disk_io"], DataOrigin::Measured, disk_io_source, interval);
+        }
+
+        wait_for_next(&mut schedule, |s| s.disk).await;
+    }
+}
+
+async fn run_thermal(mut source: Box<dyn ThermalSource>, latest: Arc<Mutex<LatestValues>>, mut schedule: watch::Receiver<CollectorSchedule>) {
+    loop {
+        let thermal_info = source.sample().await;
+        {
+            let interval = schedule.borrow().thermal;
+            let mut latest_values = lock(&latest);
+            latest_values.thermal_info = thermal_info;
+            // 不支持的平台只给出空值，不算测量结果
+            if source.name() != unsupported::NAME {
+                latest_values.record(&["thermal_info"], DataOrigin::Measured, source.name(), interval);
+            }
         }
+
+        wait_for_next(&mut schedule, |s| s.thermal).await;
     }
+}
 
-    // 快速启动版本 - 延迟初始化
-    pub fn new_fast() -> Self {
+async fn run_health(mut source: Box<dyn HealthSource>, latest: Arc<Mutex<LatestValues>>, mut schedule: watch::Receiver<CollectorSchedule>) {
+    loop {
+        let system_health = source.sample().await;
+        {
+            let interval = schedule.borrow().health;
+            let mut latest_values = lock(&latest);
+            latest_values.system_health = system_health;
+            if source.name() != unsupported::NAME {
+                latest_values.record(&["system_health"], DataOrigin::Measured, source.name(), interval);
+            }
+        }
+
+        wait_for_next(&mut schedule, |s| s.health).await;
+    }
+}
+
+async fn run_battery(mut source: Box<dyn BatterySource>, latest: Arc<Mutex<LatestValues>>, mut schedule: watch::Receiver<CollectorSchedule>) {
+    loop {
+        let result = source.sample().await;
+        {
+            let interval = schedule.borrow().battery;
+            let mut latest_values = lock(&latest);
+            // 失败时保留上一次的电池数据并标记为缓存值，原因记在 issues 里
+            match latest_values.track("battery", source.name(), result) {
+                Some(battery_info) => {
+                    latest_values.battery_info = battery_info;
+                    latest_values.record(&["battery_info"], DataOrigin::Measured, source.name(), interval);
+                }
+                None => latest_values.mark_cached(&["battery_info"]),
+            }
+        }
+
+        wait_for_next(&mut schedule, |s| s.battery).await;
+    }
+}
+
+/// CPU, power, memory, network, temperature and process data, all from one sysinfo refresh
+struct SystemCollector {
+    system: System,
+    networks: Networks,
+    components: Components,
+    users: Users,
+    network_rates: CounterRates<String>,
+    cpu_power: Box<dyn CpuPowerSource>,
+    /// CPU clusters found at startup, see `MetricSources::cpu_topology`
+    cpu_topology: Vec<ClusterMetrics>,
+}
+
+impl SystemCollector {
+    fn new(cpu_power: Box<dyn CpuPowerSource>, cpu_topology: Vec<ClusterMetrics>) -> Self {
         Self {
             system: System::new(),
             networks: Networks::new(),
             components: Components::new(),
-            last_powermetrics: None,
-            cached_cpu_metrics: None,
-            powermetrics_cache_duration: Duration::from_secs(1),
-            battery_collector: FastBatteryCollector::new(),
+            users: Users::new_with_refreshed_list(),
+            network_rates: CounterRates::new(),
+            cpu_power,
+            cpu_topology,
         }
     }
 
-    pub async fn collect_all_data(&mut self) -> Result<SystemData, Box<dyn std::error::Error>> {
+    fn refresh(&mut self) {
         // 智能刷新 - 只刷新必要的数据
         self.system.refresh_cpu_all();
         self.system.refresh_memory();
-        self.system.refresh_processes_specifics(sysinfo::ProcessesToUpdate::All, true, sysinfo::ProcessRefreshKind::everything());
+        // 只取进程表和详情页用到的字段；environ、cwd、exe 等每个进程都要读 /proc，代价不小
+        let process_kind = sysinfo::ProcessRefreshKind::nothing()
+            .with_cpu()
+            .with_memory()
+            .with_disk_usage()
+            .with_user(sysinfo::UpdateKind::OnlyIfNotSet)
+            .with_cmd(sysinfo::UpdateKind::OnlyIfNotSet);
+        self.system.refresh_processes_specifics(sysinfo::ProcessesToUpdate::All, true, process_kind);
         self.networks.refresh(true);
         self.components.refresh(true);
+    }
 
-        let system_info = self.collect_system_info();
-        let cpu_info = self.collect_cpu_info().await?;
-        let memory_info = self.collect_memory_info();
-        let network_info = self.collect_network_info();
-        let temperature_info = self.collect_temperature_info();
-        let process_info = self.collect_process_info();
-        let total_power = cpu_info.power_metrics.package_w;
-
-        let battery_info = self.battery_collector.get_battery_info().await;
-        let thermal_info = self.collect_thermal_info().await;
-        let performance_metrics = self.collect_performance_metrics(&cpu_info, total_power).await;
-        let system_health = self.collect_system_health().await;
+    fn cpu_usages(&self) -> Vec<f32> {
+        self.system.cpus().iter().map(|cpu| cpu.cpu_usage()).collect()
+    }
 
-        Ok(SystemData {
-            system_info,
+    fn collect(&mut self, cpu_usages: Vec<f32>, power_metrics: CPUMetrics) -> SystemValues {
+        let cpu_info = self.collect_cpu_info(cpu_usages, power_metrics);
+        let total_power = cpu_info.power_metrics.package_w;
+        SystemValues {
+            system_info: self.collect_system_info(),
+            performance_metrics: collect_performance_metrics(&cpu_info, total_power),
             cpu_info,
-            memory_info,
-            network_info,
-            temperature_info,
-            process_info,
-            battery_info,
-            thermal_info,
-            performance_metrics,
-            system_health,
-            timestamp: Instant::now(),
-        })
+            memory_info: self.collect_memory_info(),
+            network_info: self.collect_network_info(),
+            temperature_info: self.collect_temperature_info(),
+            process_info: self.collect_process_info(),
+        }
     }
 
     fn collect_system_info(&self) -> SystemInfo {
@@ -87,67 +394,34 @@ impl DataCollector {
         }
     }
 
-    async fn collect_cpu_info(&mut self) -> Result<CpuInfo, Box<dyn std::error::Error>> {
-        let cpu_usages: Vec<f32> = self.system
-            .cpus()
-            .iter()
-            .map(|cpu| cpu.cpu_usage())
-            .collect();
-
+    fn collect_cpu_info(&self, cpu_usages: Vec<f32>, mut power_metrics: CPUMetrics) -> CpuInfo {
         let average_usage = if !cpu_usages.is_empty() {
             cpu_usages.iter().sum::<f32>() / cpu_usages.len() as f32
         } else {
             0.0
         };
 
-        let power_metrics = if let Some(last_time) = self.last_powermetrics {
-            if last_time.elapsed() < self.powermetrics_cache_duration {
-                self.cached_cpu_metrics.clone().unwrap_or_default()
-            } else {
-                self.fetch_fresh_powermetrics().await?
-            }
-        } else {
-            self.fetch_fresh_powermetrics().await?
-        };
+        // RAPL 和估算值不带集群信息，按拓扑用 sysinfo 的每核数据补上
+        if power_metrics.clusters.is_empty() {
+            power_metrics.clusters = self.usage_clusters(&cpu_usages);
+        }
 
-        Ok(CpuInfo {
+        CpuInfo {
             core_usages: cpu_usages,
             average_usage,
             power_metrics,
-        })
-    }
-
-    async fn fetch_fresh_powermetrics(&mut self) -> Result<CPUMetrics, Box<dyn std::error::Error>> {
-        match get_powermetrics_output().await {
-            Ok(output) => {
-                let metrics = parse_cpu_metrics(output).await?;
-                self.cached_cpu_metrics = Some(metrics.clone());
-                self.last_powermetrics = Some(Instant::now());
-                Ok(metrics)
-            }
-            Err(e) => {
-                // If powermetrics fails, use fallback metrics
-                log::warn!("Powermetrics failed, using fallback: {}", e);
-                let fallback_metrics = self.get_fallback_cpu_metrics();
-                self.cached_cpu_metrics = Some(fallback_metrics.clone());
-                self.last_powermetrics = Some(Instant::now());
-                Ok(fallback_metrics)
-            }
         }
     }
 
     fn get_fallback_cpu_metrics(&self) -> CPUMetrics {
         // Use real CPU usage as basis for fallback metrics
         let avg_usage = self.system.cpus().iter().map(|cpu| cpu.cpu_usage()).sum::<f32>() / self.system.cpus().len() as f32;
-        
+
         // Estimate based on actual CPU usage
         let estimated_power = (avg_usage / 100.0) * 15.0; // Scale with usage
-        
+
         CPUMetrics {
-            e_cluster_active: (avg_usage * 0.6) as i32, // E-cores typically more active
-            p_cluster_active: (avg_usage * 0.4) as i32, // P-cores less active
-            e_cluster_freq_mhz: if avg_usage > 50.0 { 2400 } else { 1800 },
-            p_cluster_freq_mhz: if avg_usage > 70.0 { 3200 } else { 2400 },
+            clusters: Vec::new(),
             ane_w: (estimated_power * 0.05) as f64,
             cpu_w: (estimated_power * 0.6) as f64,
             gpu_w: (estimated_power * 0.2) as f64,
@@ -155,6 +429,30 @@ impl DataCollector {
         }
     }
 
+    /// Clusters from the startup topology with each one's average core usage and frequency;
+    /// a single cluster of every core when the topology is unknown
+    fn usage_clusters(&self, cpu_usages: &[f32]) -> Vec<ClusterMetrics> {
+        let frequencies: Vec<u64> = self.system.cpus().iter().map(|cpu| cpu.frequency()).collect();
+        let mut clusters = self.cpu_topology.clone();
+        if clusters.is_empty() {
+            clusters.push(ClusterMetrics {
+                name: "CPU".to_string(),
+                cores: (0..cpu_usages.len()).collect(),
+                ..ClusterMetrics::default()
+            });
+        }
+        for cluster in &mut clusters {
+            let cores: Vec<usize> = cluster.cores.iter().copied().filter(|&c| c < cpu_usages.len()).collect();
+            if cores.is_empty() {
+                continue;
+            }
+            cluster.active_percent = cores.iter().map(|&c| cpu_usages[c]).sum::<f32>() / cores.len() as f32;
+            let freq: u64 = cores.iter().filter_map(|&c| frequencies.get(c)).sum();
+            cluster.freq_mhz = (freq / cores.len() as u64) as u32;
+        }
+        clusters
+    }
+
     fn collect_memory_info(&self) -> MemoryInfo {
         let total_memory = self.system.total_memory();
         let used_memory = self.system.used_memory();
@@ -178,15 +476,41 @@ impl DataCollector {
         }
     }
 
-    fn collect_network_info(&self) -> Vec<NetworkInterface> {
-        self.networks
+    fn collect_network_info(&mut self) -> Vec<NetworkInterface> {
+        let now = Instant::now();
+        let networks = &self.networks;
+        self.network_rates.retain(|name| networks.contains_key(name));
+
+        networks
             .iter()
-            .map(|(name, data)| NetworkInterface {
-                name: name.to_string(),
-                bytes_received: data.total_received(),
-                bytes_transmitted: data.total_transmitted(),
-                packets_received: data.packets_received(),
-                packets_transmitted: data.packets_transmitted(),
+            .map(|(name, data)| {
+                let counters = [
+                    data.total_received(),
+                    data.total_transmitted(),
+                    data.total_packets_received(),
+                    data.total_packets_transmitted(),
+                ];
+                let rates = self
+                    .network_rates
+                    .update(name, &counters, now)
+                    .unwrap_or_else(|| vec![0.0; counters.len()]);
+                NetworkInterface {
+                    name: name.to_string(),
+                    bytes_received: counters[0],
+                    bytes_transmitted: counters[1],
+                    packets_received: counters[2],
+                    packets_transmitted: counters[3],
+                    rx_bytes_per_sec: rates[0],
+                    tx_bytes_per_sec: rates[1],
+                    rx_packets_per_sec: rates[2],
+                    tx_packets_per_sec: rates[3],
+                    errors_received: data.total_errors_on_received(),
+                    errors_transmitted: data.total_errors_on_transmitted(),
+                    mac_address: Some(data.mac_address())
+                        .filter(|mac| !mac.is_unspecified())
+                        .map(|mac| mac.to_string()),
+                    ip_addresses: data.ip_networks().iter().map(|ip| ip.to_string()).collect(),
+                }
             })
             .collect()
     }
@@ -208,6 +532,8 @@ impl DataCollector {
         self.system
             .processes()
             .iter()
+            // Linux lists threads as processes too; they are counted in `threads` instead
+            .filter(|(_, process)| process.thread_kind().is_none())
             .map(|(pid, process)| ProcessInfo {
                 pid: *pid,
                 name: process.name().to_string_lossy().to_string(),
@@ -215,254 +541,131 @@ impl DataCollector {
                 memory_usage: process.memory(),
                 disk_read_bytes: process.disk_usage().read_bytes,
                 disk_write_bytes: process.disk_usage().written_bytes,
+                parent_pid: process.parent().filter(|parent| parent != pid),
+                user: process.user_id().map(|uid| match self.users.get_user_by_id(uid) {
+                    Some(user) => user.name().to_string(),
+                    None => uid.to_string(),
+                }),
+                command: process.cmd().iter().map(|arg| arg.to_string_lossy().to_string()).collect(),
+                threads: process.tasks().map(|tasks| tasks.len().max(1) as u32),
+                start_time: process.start_time(),
+                status: process.status().to_string(),
             })
             .collect()
     }
+}
 
+fn collect_performance_metrics(cpu_info: &CpuInfo, total_power: f64) -> PerformanceMetrics {
+    let mut metrics = PerformanceMetrics::default();
 
+    // Calculate instructions per watt (estimated)
+    if total_power > 0.0 {
+        let estimated_instructions = cpu_info.average_usage as f64 * 1000000.0; // Simplified
+        metrics.instructions_per_watt = estimated_instructions / total_power;
+    }
 
-    async fn collect_thermal_info(&self) -> ThermalInfo {
-        let mut thermal_info = ThermalInfo::default();
-        
-        // Get real thermal pressure from system
-        if let Ok(output) = tokio::process::Command::new("sysctl")
-            .arg("-n")
-            .arg("machdep.xcpm.cpu_thermal_level")
-            .output()
-            .await
-        {
-            if let Ok(thermal_str) = String::from_utf8(output.stdout) {
-                if let Ok(thermal_level) = thermal_str.trim().parse::<u8>() {
-                    thermal_info.thermal_pressure = thermal_level * 10; // Convert to percentage
-                }
-            }
-        }
-        
-        // Check for thermal throttling via CPU frequency scaling
-        thermal_info.thermal_throttling = thermal_info.thermal_pressure > 50;
-        
-        // Get fan speeds from powermetrics if available
-        if let Ok(output) = tokio::process::Command::new("powermetrics")
-            .arg("--samplers")
-            .arg("smc")
-            .arg("-n")
-            .arg("1")
-            .arg("--show-initial-usage")
-            .output()
-            .await
-        {
-            if let Ok(power_str) = String::from_utf8(output.stdout) {
-                let mut fan_speeds = Vec::new();
-                for line in power_str.lines() {
-                    if line.contains("Fan") && line.contains("RPM") {
-                        if let Some(rpm_str) = line.split_whitespace().find(|s| s.ends_with("RPM")) {
-                            if let Ok(rpm) = rpm_str.trim_end_matches("RPM").parse::<u32>() {
-                                fan_speeds.push(rpm);
-                            }
-                        }
-                    }
-                }
-                if !fan_speeds.is_empty() {
-                    thermal_info.fan_speeds = fan_speeds;
-                }
-            }
-        }
-        
-        // Estimate heat dissipation based on thermal pressure
-        thermal_info.heat_dissipation_rate = match thermal_info.thermal_pressure {
-            0..=20 => 5.0,
-            21..=40 => 10.0,
-            41..=60 => 15.0,
-            61..=80 => 20.0,
-            _ => 25.0,
-        };
-        
-        thermal_info
+    // Calculate performance per watt
+    if total_power > 0.0 {
+        metrics.performance_per_watt = cpu_info.average_usage as f64 / total_power;
     }
 
-    async fn collect_performance_metrics(&self, cpu_info: &CpuInfo, total_power: f64) -> PerformanceMetrics {
-        let mut metrics = PerformanceMetrics::default();
+    // Calculate frequency efficiency
+    let avg_freq = cpu_info.power_metrics.average_freq_mhz();
+    if avg_freq > 0.0 {
+        metrics.frequency_efficiency = cpu_info.average_usage as f64 / avg_freq * 1000.0;
+    }
 
-        // Calculate instructions per watt (estimated)
-        if total_power > 0.0 {
-            let estimated_instructions = cpu_info.average_usage as f64 * 1000000.0; // Simplified
-            metrics.instructions_per_watt = estimated_instructions / total_power;
-        }
+    // Determine workload type
+    metrics.workload_type = if cpu_info.average_usage < 10.0 {
+        "idle".to_string()
+    } else if cpu_info.power_metrics.gpu_w > cpu_info.power_metrics.cpu_w {
+        "graphics".to_string()
+    } else if cpu_info.average_usage > 70.0 {
+        "compute".to_string()
+    } else {
+        "mixed".to_string()
+    };
+
+    metrics
+}
 
-        // Calculate performance per watt
-        if total_power > 0.0 {
-            metrics.performance_per_watt = cpu_info.average_usage as f64 / total_power;
-        }
+/// Filesystems and per-device I/O rates
+struct DiskCollector {
+    disks: Disks,
+    disk_io: Box<dyn DiskIoSource>,
+    disk_rates: CounterRates<String>,
+}
 
-        // Calculate frequency efficiency
-        let avg_freq = (cpu_info.power_metrics.e_cluster_freq_mhz + cpu_info.power_metrics.p_cluster_freq_mhz) as f64 / 2.0;
-        if avg_freq > 0.0 {
-            metrics.frequency_efficiency = cpu_info.average_usage as f64 / avg_freq * 1000.0;
+impl DiskCollector {
+    fn new(disk_io: Box<dyn DiskIoSource>) -> Self {
+        Self {
+            disks: Disks::new(),
+            disk_io,
+            disk_rates: CounterRates::new(),
         }
-
-        // Determine workload type
-        metrics.workload_type = if cpu_info.average_usage < 10.0 {
-            "idle".to_string()
-        } else if cpu_info.power_metrics.gpu_w > cpu_info.power_metrics.cpu_w {
-            "graphics".to_string()
-        } else if cpu_info.average_usage > 70.0 {
-            "compute".to_string()
-        } else {
-            "mixed".to_string()
-        };
-
-        metrics
     }
 
-    async fn collect_system_health(&self) -> SystemHealthInfo {
-        // Get real system health data
-        let mut health_info = SystemHealthInfo::default();
-        
-        // Get real uptime
-        if let Ok(output) = tokio::process::Command::new("sysctl")
-            .arg("-n")
-            .arg("kern.boottime")
-            .output()
-            .await
-        {
-            if let Ok(boottime_str) = String::from_utf8(output.stdout) {
-                // Parse boottime and calculate uptime
-                if let Some(timestamp_str) = boottime_str.split_whitespace().nth(3) {
-                    if let Ok(boot_timestamp) = timestamp_str.trim_end_matches(',').parse::<i64>() {
-                        let now = std::time::SystemTime::now()
-                            .duration_since(std::time::UNIX_EPOCH)
-                            .unwrap_or_default()
-                            .as_secs() as i64;
-                        health_info.uptime_seconds = (now - boot_timestamp) as u64;
-                    }
-                }
-            }
-        }
-        
-        // Get real load averages
-        if let Ok(output) = tokio::process::Command::new("sysctl")
-            .arg("-n")
-            .arg("vm.loadavg")
-            .output()
-            .await
-        {
-            if let Ok(loadavg_str) = String::from_utf8(output.stdout) {
-                // Parse "{ 2.66 2.75 2.97 }" format
-                let loads: Vec<f64> = loadavg_str
-                    .trim()
-                    .trim_start_matches('{')
-                    .trim_end_matches('}')
-                    .split_whitespace()
-                    .filter_map(|s| s.parse().ok())
-                    .collect();
-                
-                if loads.len() >= 3 {
-                    health_info.system_load_1min = loads[0];
-                    health_info.system_load_5min = loads[1];
-                    health_info.system_load_15min = loads[2];
+    fn collect_disk_info(&self) -> Vec<DiskInfo> {
+        self.disks
+            .iter()
+            .map(|disk| {
+                let total_space = disk.total_space();
+                let available_space = disk.available_space();
+                let usage_percentage = if total_space > 0 {
+                    (total_space - available_space.min(total_space)) as f32 / total_space as f32 * 100.0
+                } else {
+                    0.0
+                };
+                DiskInfo {
+                    name: disk.name().to_string_lossy().into_owned(),
+                    mount_point: disk.mount_point().to_string_lossy().into_owned(),
+                    file_system: disk.file_system().to_string_lossy().into_owned(),
+                    total_space,
+                    available_space,
+                    usage_percentage,
+                    is_removable: disk.is_removable(),
+                    is_read_only: disk.is_read_only(),
                 }
-            }
-        }
-        
-        // Calculate power quality score based on load and other factors
-        let avg_load = (health_info.system_load_1min + health_info.system_load_5min + health_info.system_load_15min) / 3.0;
-        health_info.power_quality_score = if avg_load < 1.0 {
-            95
-        } else if avg_load < 2.0 {
-            85
-        } else if avg_load < 3.0 {
-            75
-        } else {
-            65
-        };
-        
-        // Estimate sleep/wake efficiency (simplified)
-        health_info.sleep_wake_efficiency = if avg_load < 1.5 { 95.0 } else { 85.0 };
-        
-        health_info
+            })
+            .collect()
     }
-}
 
-async fn parse_cpu_metrics(
-    powermetrics_output: String,
-) -> Result<CPUMetrics, Box<dyn std::error::Error>> {
-    use regex::Regex;
-    use lazy_static::lazy_static;
-
-    lazy_static! {
-        static ref ACTIVE_RESIDENCY_REGEX: Regex = Regex::new(r"CPU (\d+) active residency:\s+(\d+\.\d+)%").unwrap();
-        static ref FREQUENCY_REGEX: Regex = Regex::new(r"^CPU\s+(\d+)\s+frequency:\s+(\d+)\s+MHz$").unwrap();
-    }
-
-    let lines: Vec<&str> = powermetrics_output.lines().collect();
-    let mut cpu_metrics = CPUMetrics::default();
-
-    let mut e_cluster_active_sum = 0.0;
-    let mut p_cluster_active_sum = 0.0;
-    let mut e_cluster_freq_sum = 0.0;
-    let mut p_cluster_freq_sum = 0.0;
-    let mut e_cluster_count = 0;
-    let mut p_cluster_count = 0;
-
-    for line in &lines {
-        if let Some(caps) = ACTIVE_RESIDENCY_REGEX.captures(line) {
-            if let (Ok(core_id), Ok(active_residency)) = (caps[1].parse::<usize>(), caps[2].parse::<f64>()) {
-                if core_id <= 3 {
-                    e_cluster_active_sum += active_residency;
-                    e_cluster_count += 1;
-                } else {
-                    p_cluster_active_sum += active_residency;
-                    p_cluster_count += 1;
+    /// Per-device counters and the name of the source they came from
+    async fn collect_disk_io(&mut self) -> (Vec<DiskIoInfo>, &'static str) {
+        let (mut devices, source) = match self.disk_io.sample().await {
+            Ok(devices) => (devices, self.disk_io.name()),
+            // 没有专用数据源时退回 sysinfo 的按磁盘计数（按挂载的磁盘名区分）
+            Err(e) => {
+                if !matches!(e, CollectorError::Unsupported(_)) {
+                    log::debug!("{} failed, using sysinfo disk counters: {}", self.disk_io.name(), e);
                 }
-            }
-        }
-
-        if let Some(caps) = FREQUENCY_REGEX.captures(line) {
-            if let (Ok(core_id), Ok(active_freq)) = (caps[1].parse::<usize>(), caps[2].parse::<f64>()) {
-                if core_id <= 3 {
-                    e_cluster_freq_sum += active_freq;
-                } else {
-                    p_cluster_freq_sum += active_freq;
+                let mut devices: Vec<DiskIoInfo> = Vec::new();
+                for disk in self.disks.iter() {
+                    let device = disk.name().to_string_lossy().into_owned();
+                    if devices.iter().any(|d| d.device == device) {
+                        continue;
+                    }
+                    let usage = disk.usage();
+                    devices.push(DiskIoInfo {
+                        device,
+                        read_bytes: usage.total_read_bytes,
+                        written_bytes: usage.total_written_bytes,
+                        read_bytes_per_sec: 0.0,
+                        write_bytes_per_sec: 0.0,
+                    });
                 }
+                (devices, "sysinfo")
             }
-        }
+        };
 
-        if line.contains("ANE Power") {
-            if let Some(power_str) = line.split_whitespace().nth(2) {
-                if let Ok(power) = power_str.trim_end_matches("mW").parse::<f64>() {
-                    cpu_metrics.ane_w = power / 1000.0;
-                }
-            }
-        } else if line.contains("CPU Power") {
-            if let Some(power_str) = line.split_whitespace().nth(2) {
-                if let Ok(power) = power_str.trim_end_matches("mW").parse::<f64>() {
-                    cpu_metrics.cpu_w = power / 1000.0;
-                }
-            }
-        } else if line.contains("GPU Power") {
-            if let Some(power_str) = line.split_whitespace().nth(2) {
-                if let Ok(power) = power_str.trim_end_matches("mW").parse::<f64>() {
-                    cpu_metrics.gpu_w = power / 1000.0;
-                }
-            }
-        } else if line.contains("Combined Power (CPU + GPU + ANE)") {
-            if let Some(power_str) = line.split_whitespace().nth(7) {
-                if let Ok(power) = power_str.trim_end_matches("mW").parse::<f64>() {
-                    cpu_metrics.package_w = power / 1000.0;
-                }
+        let now = Instant::now();
+        self.disk_rates.retain(|device| devices.iter().any(|d| &d.device == device));
+        for device in &mut devices {
+            if let Some(rates) = self.disk_rates.update(&device.device, &[device.read_bytes, device.written_bytes], now) {
+                device.read_bytes_per_sec = rates[0];
+                device.write_bytes_per_sec = rates[1];
             }
         }
+        (devices, source)
     }
-
-    if e_cluster_count > 0 {
-        cpu_metrics.e_cluster_active = (e_cluster_active_sum / e_cluster_count as f64) as i32;
-        cpu_metrics.e_cluster_freq_mhz = (e_cluster_freq_sum / e_cluster_count as f64) as i32;
-    }
-
-    if p_cluster_count > 0 {
-        cpu_metrics.p_cluster_active = (p_cluster_active_sum / p_cluster_count as f64) as i32;
-        cpu_metrics.p_cluster_freq_mhz = (p_cluster_freq_sum / p_cluster_count as f64) as i32;
-    }
-
-    Ok(cpu_metrics)
-}
\ No newline at end of file
+}
@@ -0,0 +1,106 @@
+// Typed errors for the collectors and metric sources
+//
+// Every failure says which tool or file was involved and why, so logs and the UI
+// can tell a missing binary from a permission problem from unexpected output.
+
+use std::fmt;
+use std::io;
+use std::process::{ExitStatus, Output};
+use std::time::Duration;
+use tokio::process::Command;
+
+#[derive(Debug, Clone)]
+pub enum CollectorError {
+    /// A command, file or device that isn't there
+    NotFound(String),
+    /// The OS refused access, usually because we are not running as root
+    PermissionDenied(String),
+    /// A command that didn't answer in time
+    Timeout { what: String, after: Duration },
+    /// Output we couldn't make sense of; `line` is the offending input, if any
+    Parse { source: String, line: String },
+    /// Not available on this platform or hardware
+    Unsupported(String),
+    /// Any other failure: a non-zero exit, an unexpected I/O error, a source still warming up
+    Failed { what: String, reason: String },
+}
+
+pub type CollectorResult<T> = Result<T, CollectorError>;
+
+impl CollectorError {
+    /// Classify an I/O error hit while running or reading `what`
+    pub fn from_io(what: &str, error: io::Error) -> Self {
+        match error.kind() {
+            io::ErrorKind::NotFound => CollectorError::NotFound(what.to_string()),
+            io::ErrorKind::PermissionDenied => CollectorError::PermissionDenied(what.to_string()),
+            io::ErrorKind::TimedOut => CollectorError::Timeout { what: what.to_string(), after: Duration::ZERO },
+            _ => CollectorError::Failed { what: what.to_string(), reason: error.to_string() },
+        }
+    }
+
+    pub fn parse(source: &str, line: &str) -> Self {
+        CollectorError::Parse { source: source.to_string(), line: line.trim().to_string() }
+    }
+
+    pub fn failed(what: &str, reason: impl Into<String>) -> Self {
+        CollectorError::Failed { what: what.to_string(), reason: reason.into() }
+    }
+
+    /// Short category name for logs and status lines
+    pub fn kind(&self) -> &'static str {
+        match self {
+            CollectorError::NotFound(_) => "not found",
+            CollectorError::PermissionDenied(_) => "permission denied",
+            CollectorError::Timeout { .. } => "timeout",
+            CollectorError::Parse { .. } => "parse error",
+            CollectorError::Unsupported(_) => "unsupported",
+            CollectorError::Failed { .. } => "failed",
+        }
+    }
+}
+
+impl fmt::Display for CollectorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CollectorError::NotFound(what) => write!(f, "{} not found", what),
+            CollectorError::PermissionDenied(what) => write!(f, "permission denied for {} (try running as root)", what),
+            CollectorError::Timeout { what, after } if after.is_zero() => write!(f, "{} timed out", what),
+            CollectorError::Timeout { what, after } => write!(f, "{} timed out after {}s", what, after.as_secs_f64()),
+            CollectorError::Parse { source, line } if line.is_empty() => write!(f, "no usable data in {} output", source),
+            CollectorError::Parse { source, line } => write!(f, "cannot parse {} output: {:?}", source, line),
+            CollectorError::Unsupported(what) => write!(f, "{} not supported on this system", what),
+            CollectorError::Failed { what, reason } => write!(f, "{} failed: {}", what, reason),
+        }
+    }
+}
+
+impl std::error::Error for CollectorError {}
+
+/// Run `program` and return its stdout, giving up after `limit`
+pub async fn run_command(program: &str, args: &[&str], limit: Duration) -> CollectorResult<String> {
+    let output = Command::new(program).args(args).kill_on_drop(true).output();
+    match tokio::time::timeout(limit, output).await {
+        Ok(Ok(output)) => command_stdout(program, output),
+        Ok(Err(e)) => Err(CollectorError::from_io(program, e)),
+        Err(_) => Err(CollectorError::Timeout { what: program.to_string(), after: limit }),
+    }
+}
+
+fn command_stdout(program: &str, output: Output) -> CollectorResult<String> {
+    if output.status.success() {
+        return Ok(String::from_utf8_lossy(&output.stdout).into_owned());
+    }
+    Err(exit_error(program, output.status, &String::from_utf8_lossy(&output.stderr)))
+}
+
+/// Why `program` exited unsuccessfully with `status`
+pub fn exit_error(program: &str, status: ExitStatus, stderr: &str) -> CollectorError {
+    // 根据 stderr 判断是否是权限问题（powermetrics 会提示需要 superuser）
+    let stderr = stderr.trim().to_string();
+    let lower = stderr.to_lowercase();
+    if lower.contains("superuser") || lower.contains("permission denied") || lower.contains("not permitted") {
+        return CollectorError::PermissionDenied(program.to_string());
+    }
+    let reason = if stderr.is_empty() { status.to_string() } else { stderr };
+    CollectorError::failed(program, reason)
+}
@@ -0,0 +1,324 @@
+// Prometheus exporter - serves the latest SystemData as text exposition format
+
+use crate::config::ExporterConfig;
+use crate::types::{NetworkInterface, SystemData};
+use std::fmt::Write as _;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::watch;
+
+/// A client that hasn't sent its request by then is dropped
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub struct MetricsExporter {
+    latest: watch::Sender<Option<SystemData>>,
+}
+
+/// Which metric groups make it into the exposition
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RenderOptions {
+    /// Leave out estimated, cached and stale groups instead of exporting them as if measured
+    pub measured_only: bool,
+}
+
+impl MetricsExporter {
+    /// Bind the listener and serve `/metrics` in a background task.
+    pub async fn start(config: &ExporterConfig) -> Result<Self, Box<dyn std::error::Error>> {
+        let listener = TcpListener::bind(&config.listen_address).await?;
+        log::info!("Prometheus exporter listening on http://{}/metrics", listener.local_addr()?);
+
+        let (latest, receiver) = watch::channel(None);
+        let options = RenderOptions { measured_only: config.measured_only };
+        tokio::spawn(serve(listener, receiver, options));
+
+        Ok(Self { latest })
+    }
+
+    pub fn publish(&self, data: &SystemData) {
+        self.latest.send_replace(Some(data.clone()));
+    }
+}
+
+async fn serve(listener: TcpListener, receiver: watch::Receiver<Option<SystemData>>, options: RenderOptions) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                let receiver = receiver.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = handle_connection(stream, receiver, options).await {
+                        log::debug!("Exporter connection error: {}", e);
+                    }
+                });
+            }
+            Err(e) => log::warn!("Exporter accept failed: {}", e),
+        }
+    }
+}
+
+async fn handle_connection(
+    mut stream: TcpStream,
+    receiver: watch::Receiver<Option<SystemData>>,
+    options: RenderOptions,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    // Only the request line matters; cap how much a client can make us buffer, and for how long
+    let mut buffer = vec![0u8; 4096];
+    let reading = async {
+        let mut read = 0;
+        while read < buffer.len() {
+            let n = stream.read(&mut buffer[read..]).await?;
+            if n == 0 {
+                break;
+            }
+            read += n;
+            if buffer[..read].windows(4).any(|w| w == b"\r\n\r\n") {
+                break;
+            }
+        }
+        Ok::<_, std::io::Error>(read)
+    };
+    let read = tokio::time::timeout(REQUEST_TIMEOUT, reading)
+        .await
+        .map_err(|_| format!("no request within {} seconds", REQUEST_TIMEOUT.as_secs()))??;
+
+    let request = String::from_utf8_lossy(&buffer[..read]);
+    let mut parts = request.lines().next().unwrap_or_default().split_whitespace();
+    let (method, path) = (parts.next().unwrap_or_default(), parts.next().unwrap_or_default());
+
+    let (status, content_type, body) = if method != "GET" {
+        ("405 Method Not Allowed", "text/plain", "Method not allowed\n".to_string())
+    } else if path == "/metrics" || path.starts_with("/metrics?") {
+        let body = receiver.borrow().as_ref().map(|data| render_metrics(data, options)).unwrap_or_default();
+        ("200 OK", "text/plain; version=0.0.4; charset=utf-8", body)
+    } else {
+        ("404 Not Found", "text/plain", "Try /metrics\n".to_string())
+    };
+
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+/// Accumulates metric families, writing HELP/TYPE once per family.
+struct Exposition {
+    out: String,
+}
+
+impl Exposition {
+    fn family(&mut self, name: &str, kind: &str, help: &str) -> &mut Self {
+        let _ = writeln!(self.out, "# HELP {} {}", name, help);
+        let _ = writeln!(self.out, "# TYPE {} {}", name, kind);
+        self
+    }
+
+    fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: f64) -> &mut Self {
+        self.out.push_str(name);
+        if !labels.is_empty() {
+            self.out.push('{');
+            for (i, (key, val)) in labels.iter().enumerate() {
+                if i > 0 {
+                    self.out.push(',');
+                }
+                let _ = write!(self.out, "{}=\"{}\"", key, escape_label(val));
+            }
+            self.out.push('}');
+        }
+        let _ = writeln!(self.out, " {}", value);
+        self
+    }
+
+    fn gauge(&mut self, name: &str, help: &str, value: f64) -> &mut Self {
+        self.family(name, "gauge", help).sample(name, &[], value)
+    }
+}
+
+type InterfaceCounter = fn(&NetworkInterface) -> u64;
+
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn flag(value: bool) -> f64 {
+    if value {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+/// Render a sample in the Prometheus text exposition format
+pub fn render_metrics(data: &SystemData, options: RenderOptions) -> String {
+    let mut m = Exposition { out: String::new() };
+    let keep = |group: &str| !options.measured_only || data.is_measured(group);
+
+    // Provenance of every group, including the ones left out below
+    m.family("sysalert_data_age_seconds", "gauge", "Age of each metric group's values, labelled with their source and origin");
+    for (group, provenance) in &data.provenance {
+        let labels = [("group", group.as_str()), ("source", provenance.source.as_str()), ("origin", provenance.origin.label())];
+        m.sample("sysalert_data_age_seconds", &labels, provenance.age(data.timestamp).as_secs_f64());
+    }
+
+    // CPU
+    if keep("cpu_info") {
+        m.family("sysalert_cpu_core_usage_percent", "gauge", "CPU usage per logical core");
+        for (core, usage) in data.cpu_info.core_usages.iter().enumerate() {
+            m.sample("sysalert_cpu_core_usage_percent", &[("core", &core.to_string())], *usage as f64);
+        }
+        m.gauge("sysalert_cpu_usage_percent", "Average CPU usage across all cores", data.cpu_info.average_usage as f64);
+    }
+
+    if keep("cpu_info.power_metrics") {
+        render_power(&mut m, data);
+    }
+    if keep("memory_info") {
+        render_memory(&mut m, data);
+    }
+    if keep("network_info") {
+        render_network(&mut m, data);
+    }
+    if keep("disk_info") {
+        render_filesystems(&mut m, data);
+    }
+    if keep("disk_io") {
+        render_disk_io(&mut m, data);
+    }
+    if keep("temperature_info") {
+        render_temperatures(&mut m, data);
+    }
+    if keep("thermal_info") {
+        render_thermal(&mut m, data);
+    }
+    if keep("battery_info") {
+        render_battery(&mut m, data);
+    }
+    if keep("system_health") {
+        render_health(&mut m, data);
+    }
+
+    m.out
+}
+
+fn render_power(m: &mut Exposition, data: &SystemData) {
+    let power = &data.cpu_info.power_metrics;
+    m.family("sysalert_power_watts", "gauge", "Power draw by domain");
+    for (domain, watts) in [("package", power.package_w), ("cpu", power.cpu_w), ("gpu", power.gpu_w), ("ane", power.ane_w)] {
+        m.sample("sysalert_power_watts", &[("domain", domain)], watts);
+    }
+    m.family("sysalert_cpu_cluster_active_percent", "gauge", "Active residency per CPU cluster");
+    for cluster in &power.clusters {
+        let labels = [("cluster", cluster.name.as_str()), ("kind", cluster.kind.label())];
+        m.sample("sysalert_cpu_cluster_active_percent", &labels, cluster.active_percent as f64);
+    }
+    m.family("sysalert_cpu_cluster_frequency_mhz", "gauge", "Average frequency per CPU cluster");
+    for cluster in &power.clusters {
+        let labels = [("cluster", cluster.name.as_str()), ("kind", cluster.kind.label())];
+        m.sample("sysalert_cpu_cluster_frequency_mhz", &labels, cluster.freq_mhz as f64);
+    }
+}
+
+fn render_memory(m: &mut Exposition, data: &SystemData) {
+    let memory = &data.memory_info;
+    m.gauge("sysalert_memory_total_bytes", "Total physical memory", memory.total_memory as f64);
+    m.gauge("sysalert_memory_used_bytes", "Used physical memory", memory.used_memory as f64);
+    m.gauge("sysalert_memory_available_bytes", "Available physical memory", memory.available_memory as f64);
+    m.gauge("sysalert_memory_usage_percent", "Used share of physical memory", memory.usage_percentage as f64);
+    m.gauge("sysalert_swap_total_bytes", "Total swap space", memory.total_swap as f64);
+    m.gauge("sysalert_swap_used_bytes", "Used swap space", memory.used_swap as f64);
+}
+
+// The interface counters are cumulative
+fn render_network(m: &mut Exposition, data: &SystemData) {
+    let counters: [(&str, &str, InterfaceCounter); 4] = [
+        ("sysalert_network_received_bytes_total", "Bytes received per interface", |n| n.bytes_received),
+        ("sysalert_network_transmitted_bytes_total", "Bytes transmitted per interface", |n| n.bytes_transmitted),
+        ("sysalert_network_received_packets_total", "Packets received per interface", |n| n.packets_received),
+        ("sysalert_network_transmitted_packets_total", "Packets transmitted per interface", |n| n.packets_transmitted),
+    ];
+    for (name, help, value) in counters {
+        m.family(name, "counter", help);
+        for interface in &data.network_info {
+            m.sample(name, &[("interface", &interface.name)], value(interface) as f64);
+        }
+    }
+}
+
+// Filesystems by mount point
+fn render_filesystems(m: &mut Exposition, data: &SystemData) {
+    m.family("sysalert_filesystem_size_bytes", "gauge", "Filesystem size");
+    for disk in &data.disk_info {
+        m.sample("sysalert_filesystem_size_bytes", &[("mountpoint", &disk.mount_point), ("device", &disk.name), ("fstype", &disk.file_system)], disk.total_space as f64);
+    }
+    m.family("sysalert_filesystem_available_bytes", "gauge", "Filesystem space available");
+    for disk in &data.disk_info {
+        m.sample("sysalert_filesystem_available_bytes", &[("mountpoint", &disk.mount_point), ("device", &disk.name), ("fstype", &disk.file_system)], disk.available_space as f64);
+    }
+}
+
+// I/O counters by block device
+fn render_disk_io(m: &mut Exposition, data: &SystemData) {
+    m.family("sysalert_disk_read_bytes_total", "counter", "Bytes read per block device");
+    for device in &data.disk_io {
+        m.sample("sysalert_disk_read_bytes_total", &[("device", &device.device)], device.read_bytes as f64);
+    }
+    m.family("sysalert_disk_written_bytes_total", "counter", "Bytes written per block device");
+    for device in &data.disk_io {
+        m.sample("sysalert_disk_written_bytes_total", &[("device", &device.device)], device.written_bytes as f64);
+    }
+}
+
+fn render_temperatures(m: &mut Exposition, data: &SystemData) {
+    m.family("sysalert_temperature_celsius", "gauge", "Component temperature");
+    for temp in &data.temperature_info {
+        m.sample("sysalert_temperature_celsius", &[("sensor", &temp.label)], temp.temperature as f64);
+    }
+    m.family("sysalert_temperature_critical_celsius", "gauge", "Component critical temperature");
+    for temp in &data.temperature_info {
+        m.sample("sysalert_temperature_critical_celsius", &[("sensor", &temp.label)], temp.critical_temperature as f64);
+    }
+}
+
+// Thermal management
+fn render_thermal(m: &mut Exposition, data: &SystemData) {
+    let thermal = &data.thermal_info;
+    m.family("sysalert_fan_speed_rpm", "gauge", "Fan speed");
+    for (fan, rpm) in thermal.fan_speeds.iter().enumerate() {
+        m.sample("sysalert_fan_speed_rpm", &[("fan", &fan.to_string())], *rpm as f64);
+    }
+    m.gauge("sysalert_thermal_throttling", "1 if the system is thermally throttled", flag(thermal.thermal_throttling));
+    m.gauge("sysalert_thermal_pressure_percent", "Thermal pressure", thermal.thermal_pressure as f64);
+}
+
+fn render_battery(m: &mut Exposition, data: &SystemData) {
+    let battery = &data.battery_info;
+    m.gauge("sysalert_battery_charge_percent", "Battery state of charge", battery.percentage as f64);
+    m.gauge("sysalert_battery_charging", "1 while the battery is charging", flag(battery.is_charging));
+    m.gauge("sysalert_battery_plugged", "1 while on external power", flag(battery.is_plugged));
+    m.gauge("sysalert_battery_health_percent", "Battery maximum capacity relative to design", battery.health_percentage as f64);
+    m.family("sysalert_battery_cycles_total", "counter", "Battery charge cycles")
+        .sample("sysalert_battery_cycles_total", &[], battery.cycle_count as f64);
+    if let Some(remaining) = battery.time_remaining {
+        m.gauge("sysalert_battery_time_remaining_seconds", "Estimated battery time remaining", remaining as f64);
+    }
+    m.gauge("sysalert_battery_capacity_mah", "Current full charge capacity", battery.current_capacity as f64);
+    m.gauge("sysalert_battery_design_capacity_mah", "Design capacity", battery.design_capacity as f64);
+    m.gauge("sysalert_battery_voltage_volts", "Battery voltage", battery.voltage as f64);
+    m.gauge("sysalert_battery_current_amperes", "Battery current, negative while discharging", battery.amperage as f64);
+    m.gauge("sysalert_battery_temperature_celsius", "Battery temperature", battery.temperature as f64);
+    m.gauge("sysalert_power_adapter_watts", "Power adapter rating", battery.power_adapter_wattage as f64);
+}
+
+fn render_health(m: &mut Exposition, data: &SystemData) {
+    let health = &data.system_health;
+    m.family("sysalert_load_average", "gauge", "System load average");
+    m.sample("sysalert_load_average", &[("period", "1m")], health.system_load_1min);
+    m.sample("sysalert_load_average", &[("period", "5m")], health.system_load_5min);
+    m.sample("sysalert_load_average", &[("period", "15m")], health.system_load_15min);
+    m.gauge("sysalert_uptime_seconds", "Time since boot", health.uptime_seconds as f64);
+}
@@ -1,16 +1,81 @@
-use crate::types::SystemData;
-use std::collections::VecDeque;
+use crate::config::InterfaceFilter;
+use crate::types::{NetworkInterface, ProcessInfo, SystemData};
+use std::collections::{HashMap, HashSet, VecDeque};
+use sysinfo::Pid;
+
+/// Processes tracked per PID unless configured otherwise
+pub const DEFAULT_TRACKED_PROCESSES: usize = 10;
+
+/// Samples a process keeps being recorded after it leaves the tracked set
+pub const PROCESS_GRACE_SAMPLES: usize = 3;
 
 #[derive(Clone)]
 pub struct HistoryData {
     pub cpu_history: VecDeque<f32>,
     pub memory_history: VecDeque<u16>,
-    pub network_rx_history: VecDeque<u64>,
-    pub network_tx_history: VecDeque<u64>,
+    pub network_rx_history: VecDeque<f64>, // bytes/s, interfaces passing the filter
+    pub network_tx_history: VecDeque<f64>,
     pub temperature_history: VecDeque<f32>,
+    pub disk_read_history: VecDeque<f64>, // bytes/s, all devices
+    pub disk_write_history: VecDeque<f64>,
+    pub power_history: VecDeque<f64>, // W, CPU package
+    pub battery_power_history: VecDeque<f64>, // W into (+) or out of (-) the battery
+    interface_history: HashMap<String, InterfaceHistory>,
+    interface_filter: InterfaceFilter,
+    process_history: HashMap<Pid, ProcessHistory>,
+    pinned_pids: HashSet<Pid>,
+    tracked_processes: usize,
     max_size: usize,
 }
 
+/// Recent samples of one process. The start time tells a reused PID apart from the original process.
+#[derive(Clone)]
+pub struct ProcessHistory {
+    pub name: String,
+    pub start_time: u64,
+    pub cpu_history: VecDeque<f32>,
+    pub memory_history: VecDeque<u64>,
+    pub disk_read_history: VecDeque<u64>,
+    pub disk_write_history: VecDeque<u64>,
+    /// Consecutive samples spent outside the top processes and the pinned set
+    untracked_samples: usize,
+}
+
+/// Recent throughput of one network interface, in bytes/s
+#[derive(Clone, Default)]
+pub struct InterfaceHistory {
+    pub rx_history: VecDeque<f64>,
+    pub tx_history: VecDeque<f64>,
+}
+
+impl ProcessHistory {
+    fn new(process: &ProcessInfo, max_size: usize) -> Self {
+        Self {
+            name: process.name.clone(),
+            start_time: process.start_time,
+            cpu_history: VecDeque::with_capacity(max_size),
+            memory_history: VecDeque::with_capacity(max_size),
+            disk_read_history: VecDeque::with_capacity(max_size),
+            disk_write_history: VecDeque::with_capacity(max_size),
+            untracked_samples: 0,
+        }
+    }
+
+    fn push(&mut self, process: &ProcessInfo, max_size: usize) {
+        push_bounded(&mut self.cpu_history, process.cpu_usage, max_size);
+        push_bounded(&mut self.memory_history, process.memory_usage, max_size);
+        push_bounded(&mut self.disk_read_history, process.disk_read_bytes, max_size);
+        push_bounded(&mut self.disk_write_history, process.disk_write_bytes, max_size);
+    }
+
+    fn trim(&mut self, max_size: usize) {
+        trim_front(&mut self.cpu_history, max_size);
+        trim_front(&mut self.memory_history, max_size);
+        trim_front(&mut self.disk_read_history, max_size);
+        trim_front(&mut self.disk_write_history, max_size);
+    }
+}
+
 impl HistoryData {
     pub fn new(max_size: usize) -> Self {
         Self {
@@ -19,6 +84,15 @@ impl HistoryData {
             network_rx_history: VecDeque::with_capacity(max_size),
             network_tx_history: VecDeque::with_capacity(max_size),
             temperature_history: VecDeque::with_capacity(max_size),
+            disk_read_history: VecDeque::with_capacity(max_size),
+            disk_write_history: VecDeque::with_capacity(max_size),
+            power_history: VecDeque::with_capacity(max_size),
+            battery_power_history: VecDeque::with_capacity(max_size),
+            interface_history: HashMap::new(),
+            interface_filter: InterfaceFilter::default(),
+            process_history: HashMap::new(),
+            pinned_pids: HashSet::new(),
+            tracked_processes: DEFAULT_TRACKED_PROCESSES,
             max_size,
         }
     }
@@ -27,11 +101,23 @@ impl HistoryData {
         self.add_cpu_usage(data.cpu_info.average_usage);
         self.add_memory_usage(data.memory_info.usage_percentage);
         
-        // Sum up all network interfaces
-        let total_rx = data.network_info.iter().map(|ni| ni.bytes_received).sum();
-        let total_tx = data.network_info.iter().map(|ni| ni.bytes_transmitted).sum();
+        // Sum up the interfaces that pass the filter
+        let shown = data.network_info.iter().filter(|ni| self.interface_filter.matches(&ni.name));
+        let (total_rx, total_tx) = shown.fold((0.0, 0.0), |(rx, tx), ni| {
+            (rx + ni.rx_bytes_per_sec, tx + ni.tx_bytes_per_sec)
+        });
         self.add_network_rx(total_rx);
         self.add_network_tx(total_tx);
+        self.update_interfaces(&data.network_info);
+
+        let disk_read = data.disk_io.iter().map(|d| d.read_bytes_per_sec).sum();
+        let disk_write = data.disk_io.iter().map(|d| d.write_bytes_per_sec).sum();
+        push_bounded(&mut self.disk_read_history, disk_read, self.max_size);
+        push_bounded(&mut self.disk_write_history, disk_write, self.max_size);
+
+        let battery = &data.battery_info;
+        push_bounded(&mut self.power_history, data.cpu_info.power_metrics.package_w, self.max_size);
+        push_bounded(&mut self.battery_power_history, (battery.voltage * battery.amperage) as f64, self.max_size);
 
         // Average temperature
         if !data.temperature_info.is_empty() {
@@ -40,49 +126,141 @@ impl HistoryData {
                 .sum::<f32>() / data.temperature_info.len() as f32;
             self.add_temperature(avg_temp);
         }
+
+        self.update_processes(&data.process_info);
+    }
+
+    /// Record the top processes by CPU plus any pinned ones. A process that leaves
+    /// that set keeps being recorded for `PROCESS_GRACE_SAMPLES` more samples before it is dropped,
+    /// so one that flickers in and out of the top N keeps its graph. Entries are
+    /// dropped right away when their process exits, and restarted when the PID is reused.
+    pub fn update_processes(&mut self, processes: &[ProcessInfo]) {
+        let mut by_cpu: Vec<&ProcessInfo> = processes.iter().collect();
+        by_cpu.sort_by(|a, b| b.cpu_usage.partial_cmp(&a.cpu_usage).unwrap_or(std::cmp::Ordering::Equal));
+
+        let alive: HashSet<Pid> = processes.iter().map(|p| p.pid).collect();
+        self.pinned_pids.retain(|pid| alive.contains(pid));
+
+        let tracked_pids: HashSet<Pid> = by_cpu
+            .iter()
+            .take(self.tracked_processes)
+            .map(|p| p.pid)
+            .chain(self.pinned_pids.iter().copied())
+            .collect();
+        self.process_history.retain(|pid, _| alive.contains(pid));
+
+        let max_size = self.max_size;
+        for process in processes {
+            let is_tracked = tracked_pids.contains(&process.pid);
+            if !is_tracked && !self.process_history.contains_key(&process.pid) {
+                continue;
+            }
+            let entry = self
+                .process_history
+                .entry(process.pid)
+                .or_insert_with(|| ProcessHistory::new(process, max_size));
+            if entry.start_time != process.start_time {
+                if !is_tracked {
+                    // PID 被复用，新进程不在前 N 名里，不继承宽限期
+                    self.process_history.remove(&process.pid);
+                    continue;
+                }
+                *entry = ProcessHistory::new(process, max_size);
+            }
+            if is_tracked {
+                entry.untracked_samples = 0;
+            } else {
+                entry.untracked_samples += 1;
+                if entry.untracked_samples > PROCESS_GRACE_SAMPLES {
+                    self.process_history.remove(&process.pid);
+                    continue;
+                }
+            }
+            entry.push(process, max_size);
+        }
+    }
+
+    /// Record every interface; ones that disappeared are forgotten
+    pub fn update_interfaces(&mut self, interfaces: &[NetworkInterface]) {
+        self.interface_history
+            .retain(|name, _| interfaces.iter().any(|ni| &ni.name == name));
+        let max_size = self.max_size;
+        for interface in interfaces {
+            let entry = self.interface_history.entry(interface.name.clone()).or_default();
+            push_bounded(&mut entry.rx_history, interface.rx_bytes_per_sec, max_size);
+            push_bounded(&mut entry.tx_history, interface.tx_bytes_per_sec, max_size);
+        }
+    }
+
+    /// History of one network interface
+    pub fn interface(&self, name: &str) -> Option<&InterfaceHistory> {
+        self.interface_history.get(name)
+    }
+
+    /// Which interfaces count towards the network totals
+    pub fn set_interface_filter(&mut self, filter: InterfaceFilter) {
+        self.interface_filter = filter;
+    }
+
+    /// History of one process, if it is currently tracked
+    pub fn process(&self, pid: Pid) -> Option<&ProcessHistory> {
+        self.process_history.get(&pid)
+    }
+
+    /// Keep history for these processes even when they are not among the top ones
+    pub fn set_pinned(&mut self, pids: HashSet<Pid>) {
+        self.pinned_pids = pids;
+    }
+
+    /// How many of the busiest processes get per-process history
+    pub fn set_tracked_processes(&mut self, count: usize) {
+        self.tracked_processes = count;
     }
     
     pub fn add_cpu_usage(&mut self, usage: f32) {
-        if self.cpu_history.len() >= self.max_size {
-            self.cpu_history.pop_front();
-        }
-        self.cpu_history.push_back(usage);
+        push_bounded(&mut self.cpu_history, usage, self.max_size);
     }
     
     pub fn add_memory_usage(&mut self, usage: u16) {
-        if self.memory_history.len() >= self.max_size {
-            self.memory_history.pop_front();
-        }
-        self.memory_history.push_back(usage);
+        push_bounded(&mut self.memory_history, usage, self.max_size);
     }
     
-    pub fn add_network_rx(&mut self, bytes: u64) {
-        if self.network_rx_history.len() >= self.max_size {
-            self.network_rx_history.pop_front();
-        }
-        self.network_rx_history.push_back(bytes);
+    pub fn add_network_rx(&mut self, bytes_per_sec: f64) {
+        push_bounded(&mut self.network_rx_history, bytes_per_sec, self.max_size);
     }
     
-    pub fn add_network_tx(&mut self, bytes: u64) {
-        if self.network_tx_history.len() >= self.max_size {
-            self.network_tx_history.pop_front();
-        }
-        self.network_tx_history.push_back(bytes);
+    pub fn add_network_tx(&mut self, bytes_per_sec: f64) {
+        push_bounded(&mut self.network_tx_history, bytes_per_sec, self.max_size);
     }
 
     pub fn add_temperature(&mut self, temp: f32) {
-        if self.temperature_history.len() >= self.max_size {
-            self.temperature_history.pop_front();
-        }
-        self.temperature_history.push_back(temp);
+        push_bounded(&mut self.temperature_history, temp, self.max_size);
     }
 
-    #[allow(dead_code)]
-    fn add_to_deque<T>(&mut self, deque: &mut VecDeque<T>, value: T) {
-        if deque.len() >= self.max_size {
-            deque.pop_front();
+    /// Change how many points are kept, dropping the oldest ones if shrinking
+    pub fn set_max_size(&mut self, max_size: usize) {
+        self.max_size = max_size;
+        trim_front(&mut self.cpu_history, max_size);
+        trim_front(&mut self.memory_history, max_size);
+        trim_front(&mut self.network_rx_history, max_size);
+        trim_front(&mut self.network_tx_history, max_size);
+        trim_front(&mut self.temperature_history, max_size);
+        trim_front(&mut self.disk_read_history, max_size);
+        trim_front(&mut self.disk_write_history, max_size);
+        trim_front(&mut self.power_history, max_size);
+        trim_front(&mut self.battery_power_history, max_size);
+        for history in self.interface_history.values_mut() {
+            trim_front(&mut history.rx_history, max_size);
+            trim_front(&mut history.tx_history, max_size);
         }
-        deque.push_back(value);
+        for history in self.process_history.values_mut() {
+            history.trim(max_size);
+        }
+    }
+
+    /// Maximum number of points kept per series
+    pub fn capacity(&self) -> usize {
+        self.max_size
     }
 
     pub fn get_cpu_trend(&self) -> Option<f32> {
@@ -102,4 +280,16 @@ impl HistoryData {
         let older = self.memory_history.iter().take(5).sum::<u16>() as f32 / 5.0;
         Some(recent - older)
     }
-}
\ No newline at end of file
+}
+
+fn push_bounded<T>(deque: &mut VecDeque<T>, value: T, max_size: usize) {
+    if deque.len() >= max_size {
+        deque.pop_front();
+    }
+    deque.push_back(value);
+}
+
+fn trim_front<T>(deque: &mut VecDeque<T>, max_size: usize) {
+    let excess = deque.len().saturating_sub(max_size);
+    deque.drain(..excess);
+}
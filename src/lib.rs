@@ -1,9 +1,17 @@
+pub mod alert_rules;
 pub mod battery_collector;
 pub mod cli;
 pub mod config;
 pub mod data_collector;
+pub mod error;
+pub mod exporter;
 pub mod history;
 pub mod notification;
+pub mod process_control;
+pub mod rates;
+pub mod session;
+pub mod sinks;
+pub mod sources;
 pub mod system_info;
 pub mod types;
 pub mod ui;
\ No newline at end of file
@@ -1,73 +1,99 @@
 use system_alert::{
-    cli::{check_root, handle_input, parse_args, InputEvent},
+    cli::{check_root, handle_input, map_key, parse_args, InputEvent, RunMode},
     config::Config,
     data_collector::DataCollector,
+    exporter::MetricsExporter,
     history::HistoryData,
     notification::NotificationManager,
-    ui::UI,
+    session::{SampleFeed, SessionRecorder, SessionReplayer},
+    sinks::build_routes,
+    sources::MetricSources,
+    ui::{Tab, UI},
     types::*,
 };
 
 use log::{error, info, warn};
-use std::time::Duration;
-use tokio::time::interval;
+use system_alert::cli::CliArgs;
+use tokio::signal::unix::{signal, SignalKind};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    env_logger::init();
+    // Parse command line arguments
+    let cli_args = parse_args();
+    
+    // The TUI draws on stderr, so only log by default when there is no UI
+    let default_filter = if cli_args.headless { "info" } else { "error" };
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(default_filter)).init();
     
     info!("Advanced System Monitor starting...");
     
     // Check root privileges (but don't exit if not root, just warn)
-    if let Err(e) = check_root().await {
-        warn!("Running without root privileges: {}", e);
-        warn!("Some features (like powermetrics) may not work properly.");
-        warn!("For full functionality, run with: sudo cargo run");
+    if !matches!(cli_args.mode, RunMode::Replay { .. }) {
+        if let Err(e) = check_root().await {
+            warn!("Running without root privileges: {}", e);
+            warn!("Some features (like powermetrics) may not work properly.");
+            warn!("For full functionality, run with: sudo cargo run");
+        }
     }
     
-    // Parse command line arguments
-    let cli_args = parse_args();
-    
     // Load configuration
-    let mut config = if let Some(config_file) = &cli_args.config_file {
-        match Config::load_from_file(config_file) {
-            Ok(config) => {
-                info!("Loaded configuration from: {}", config_file);
-                config
-            }
-            Err(e) => {
-                warn!("Failed to load config file {}: {}. Using defaults.", config_file, e);
-                Config::default()
-            }
+    let mut config = load_config(&cli_args).unwrap_or_else(|e| {
+        warn!("{}. Using defaults.", e);
+        let mut config = Config::default();
+        apply_cli_overrides(&mut config, &cli_args);
+        config
+    });
+    
+    // Open the session file before touching the terminal so errors stay readable
+    let mut feed = match &cli_args.mode {
+        RunMode::Live => SampleFeed::live(start_collector(&config), config.refresh_rate, None),
+        RunMode::Record { output } => {
+            info!("Recording session to: {}", output);
+            let recorder = SessionRecorder::create(output)?;
+            SampleFeed::live(start_collector(&config), config.refresh_rate, Some(recorder))
         }
+        RunMode::Replay { input, speed } => {
+            info!("Replaying session {} at {}x", input, speed);
+            SampleFeed::Replay(Box::new(SessionReplayer::open(input, *speed)?))
+        }
+    };
+    
+    let exporter = if config.exporter.enabled {
+        Some(MetricsExporter::start(&config.exporter).await?)
     } else {
-        Config::default()
+        None
     };
     
-    // Apply CLI overrides
-    config.merge_with_cli(cli_args.refresh_rate, cli_args.minimal_mode);
+    if cli_args.headless {
+        return run_headless(feed, config, exporter, &cli_args).await;
+    }
     
     // Initialize UI first - immediate startup
     let mut ui = UI::new()?;
+    ui.set_tab(config.display.active_tab);
+    // 回放中的 PID 指向录制时的进程，不能对当前系统上的同号进程发信号
+    if matches!(cli_args.mode, RunMode::Replay { .. }) {
+        ui.disable_process_actions("replaying a recorded session");
+    }
     info!("UI initialized - starting data collection in background...");
     
     // Show loading screen immediately
     ui.show_loading_screen()?;
     
     // Initialize other components in background
-    let mut data_collector = DataCollector::new_fast();
     let mut history = HistoryData::new(config.display.history_size);
+    history.set_tracked_processes(config.display.tracked_processes);
+    history.set_interface_filter(config.display.interface_filter());
     let mut notification_manager = NotificationManager::new(
         config.notifications.enabled,
         config.notifications.cooldown_seconds,
     );
+    notification_manager.set_rules(config.alert_rules());
+    notification_manager.set_sinks(build_routes(&config.notifications));
     
     // Set up input handling
     let mut input_receiver = handle_input().await;
     
-    // Set up refresh timer
-    let mut refresh_interval = interval(Duration::from_secs(config.refresh_rate));
-    
     // Create initial empty data for immediate display
     let mut system_data = create_placeholder_data();
     
@@ -77,17 +103,24 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     loop {
         tokio::select! {
             // Handle input events
-            input_event = input_receiver.recv() => {
-                match input_event {
+            key = input_receiver.recv() => {
+                // 按键在这里按当前界面状态解释，输入框里的 q 或数字不会被当成命令
+                match key.map(|key| map_key(key, ui.captures_keys())) {
                     Some(InputEvent::Quit) => {
                         info!("Quit signal received");
                         break;
                     }
                     Some(InputEvent::NextTab) => {
                         ui.next_tab();
+                        redraw(&mut ui, &system_data, &history, &config, &notification_manager);
                     }
                     Some(InputEvent::PreviousTab) => {
                         ui.previous_tab();
+                        redraw(&mut ui, &system_data, &history, &config, &notification_manager);
+                    }
+                    Some(InputEvent::SelectTab(index)) => {
+                        ui.set_tab(Tab::from_index(index));
+                        redraw(&mut ui, &system_data, &history, &config, &notification_manager);
                     }
                     Some(InputEvent::ToggleNotifications) => {
                         let new_state = !config.notifications.enabled;
@@ -98,6 +131,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     Some(InputEvent::Refresh) => {
                         // Force immediate refresh by continuing to the refresh logic
                     }
+                    Some(InputEvent::Key(key)) => {
+                        if ui.handle_key(key) {
+                            history.set_pinned(ui.pinned_processes());
+                            redraw(&mut ui, &system_data, &history, &config, &notification_manager);
+                        }
+                    }
                     None => {
                         warn!("Input channel closed");
                         break;
@@ -106,22 +145,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
             
             // Handle periodic refresh
-            _ = refresh_interval.tick() => {
+            _ = feed.tick() => {
                 // Collect system data asynchronously
-                match data_collector.collect_all_data().await {
-                    Ok(new_data) => {
+                match feed.next_sample().await {
+                    Ok(Some(new_data)) => {
                         system_data = new_data;
-                        // Update history
-                        history.update_from_system_data(&system_data);
-                        
-                        // Check for notifications
-                        if let Err(e) = notification_manager
-                            .check_and_send_notifications(&system_data, &config.thresholds)
-                            .await
-                        {
-                            error!("Notification error: {}", e);
-                        }
+                        process_sample(&system_data, &mut history, &mut notification_manager, exporter.as_ref());
                     }
+                    Ok(None) => {}
                     Err(e) => {
                         error!("Data collection error: {}", e);
                         // Keep using previous data, don't crash
@@ -129,9 +160,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 }
                 
                 // Always update UI (even with old data)
-                if let Err(e) = ui.draw(&system_data, &history, &config) {
-                    error!("UI draw error: {}", e);
-                }
+                redraw(&mut ui, &system_data, &history, &config, &notification_manager);
             }
         }
     }
@@ -143,8 +172,163 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// Draw the current frame, logging rather than propagating draw failures
+fn redraw(
+    ui: &mut UI,
+    data: &SystemData,
+    history: &HistoryData,
+    config: &Config,
+    notification_manager: &NotificationManager,
+) {
+    if let Err(e) = ui.draw(data, history, config, &notification_manager.active_alerts()) {
+        error!("UI draw error: {}", e);
+    }
+}
+
+fn start_collector(config: &Config) -> DataCollector {
+    DataCollector::start(MetricSources::detect(), config.collector_schedule())
+}
+
+/// Load the config file (or defaults) and apply command line overrides
+fn load_config(cli_args: &CliArgs) -> Result<Config, String> {
+    let mut config = match &cli_args.config_file {
+        Some(config_file) => {
+            let config = Config::load_from_file(config_file)
+                .map_err(|e| format!("Failed to load config file {}: {}", config_file, e))?;
+            info!("Loaded configuration from: {}", config_file);
+            config
+        }
+        None => Config::default(),
+    };
+    apply_cli_overrides(&mut config, cli_args);
+    Ok(config)
+}
+
+fn apply_cli_overrides(config: &mut Config, cli_args: &CliArgs) {
+    config.merge_with_cli(cli_args.refresh_rate, cli_args.minimal_mode);
+    if let Some(address) = &cli_args.metrics_address {
+        config.exporter.enabled = true;
+        config.exporter.listen_address = address.clone();
+    }
+}
+
+/// Collect → history → notifications loop without a terminal, for launchd/systemd
+async fn run_headless(
+    mut feed: SampleFeed,
+    mut config: Config,
+    exporter: Option<MetricsExporter>,
+    cli_args: &CliArgs,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut history = HistoryData::new(config.display.history_size);
+    history.set_tracked_processes(config.display.tracked_processes);
+    history.set_interface_filter(config.display.interface_filter());
+    let mut notification_manager = NotificationManager::new(
+        config.notifications.enabled,
+        config.notifications.cooldown_seconds,
+    );
+    notification_manager.set_rules(config.alert_rules());
+    notification_manager.set_sinks(build_routes(&config.notifications));
+    
+    let mut sigterm = signal(SignalKind::terminate())?;
+    let mut sighup = signal(SignalKind::hangup())?;
+    
+    info!("Running headless (pid {}). SIGTERM/SIGINT to stop, SIGHUP to reload configuration.", std::process::id());
+    
+    loop {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {
+                info!("SIGINT received, shutting down");
+                break;
+            }
+            
+            _ = sigterm.recv() => {
+                info!("SIGTERM received, shutting down");
+                break;
+            }
+            
+            _ = sighup.recv() => {
+                info!("SIGHUP received, reloading configuration");
+                match load_config(cli_args) {
+                    Ok(new_config) => {
+                        reload_config(&config, &new_config, &mut feed, &mut history, &mut notification_manager);
+                        config = new_config;
+                    }
+                    Err(e) => error!("{}. Keeping current configuration.", e),
+                }
+            }
+            
+            _ = feed.tick() => {
+                match feed.next_sample().await {
+                    Ok(Some(data)) => {
+                        process_sample(&data, &mut history, &mut notification_manager, exporter.as_ref());
+                    }
+                    Ok(None) => {}
+                    Err(e) => error!("Data collection error: {}", e),
+                }
+            }
+        }
+    }
+    
+    info!("System monitor exited normally");
+    Ok(())
+}
+
+/// Push changed settings into the running components
+fn reload_config(
+    old: &Config,
+    new: &Config,
+    feed: &mut SampleFeed,
+    history: &mut HistoryData,
+    notification_manager: &mut NotificationManager,
+) {
+    notification_manager.set_enabled(new.notifications.enabled);
+    notification_manager.set_cooldown(new.notifications.cooldown_seconds);
+    notification_manager.set_rules(new.alert_rules());
+    notification_manager.set_sinks(build_routes(&new.notifications));
+    
+    if new.refresh_rate != old.refresh_rate {
+        feed.set_refresh_rate(new.refresh_rate);
+        info!("Refresh rate changed to {}s", new.refresh_rate);
+    }
+    if new.collector_schedule() != old.collector_schedule() {
+        feed.set_schedule(new.collector_schedule());
+        info!("Collector schedule changed: {:?}", new.collector_schedule());
+    }
+    if new.display.history_size != old.display.history_size {
+        history.set_max_size(new.display.history_size);
+    }
+    history.set_tracked_processes(new.display.tracked_processes);
+    history.set_interface_filter(new.display.interface_filter());
+    if new.exporter.enabled != old.exporter.enabled
+        || new.exporter.listen_address != old.exporter.listen_address
+        || new.exporter.measured_only != old.exporter.measured_only
+    {
+        warn!("Exporter settings changed; restart to apply them");
+    }
+}
+
+/// Feed a fresh sample to history, notifications and the exporter
+fn process_sample(
+    data: &SystemData,
+    history: &mut HistoryData,
+    notification_manager: &mut NotificationManager,
+    exporter: Option<&MetricsExporter>,
+) {
+    // Update history
+    history.update_from_system_data(data);
+    
+    // Check for notifications; delivery runs on its own task
+    if let Err(e) = notification_manager.check_and_send_notifications(data) {
+        error!("Notification error: {}", e);
+    }
+    
+    if let Some(exporter) = exporter {
+        exporter.publish(data);
+    }
+}
+
 fn create_placeholder_data() -> SystemData {
-    use std::time::Instant;
+    use std::time::SystemTime;
     
     SystemData {
         system_info: SystemInfo {
@@ -169,12 +353,16 @@ fn create_placeholder_data() -> SystemData {
             usage_percentage: 0,
         },
         network_info: vec![],
+        disk_info: vec![],
+        disk_io: vec![],
         temperature_info: vec![],
         process_info: vec![],
         battery_info: BatteryInfo::default(),
         thermal_info: ThermalInfo::default(),
         performance_metrics: PerformanceMetrics::default(),
         system_health: SystemHealthInfo::default(),
-        timestamp: Instant::now(),
+        timestamp: SystemTime::now(),
+        collector_issues: vec![],
+        provenance: Default::default(),
     }
 }
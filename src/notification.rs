@@ -1,8 +1,17 @@
-use crate::{config::ThresholdConfig, types::SystemData};
-use std::collections::HashMap;
-use std::time::{Duration, Instant};
+use crate::{
+    alert_rules::AlertRule,
+    sinks::{desktop_sink, SinkRoute},
+    types::SystemData,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::sync::mpsc;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum AlertLevel {
     Info,
     Warning,
@@ -14,159 +23,362 @@ pub struct Notification {
     title: String,
     message: String,
     level: AlertLevel,
-    #[allow(dead_code)]
-    timestamp: Instant,
+    resolved: bool,
+    timestamp: SystemTime,
 }
 
 impl Notification {
     pub fn new(title: &str, message: &str, level: AlertLevel) -> Self {
         Self {
-            title: title.to_string(),
-            message: message.to_string(),
+            title: sanitize_text(title),
+            message: sanitize_text(message),
             level,
-            timestamp: Instant::now(),
+            resolved: false,
+            timestamp: SystemTime::now(),
         }
     }
-    
-    pub async fn send(&self) -> Result<(), Box<dyn std::error::Error>> {
-        let subtitle = match self.level {
-            AlertLevel::Info => "Information",
-            AlertLevel::Warning => "Warning",
-            AlertLevel::Critical => "Critical Alert",
-        };
-        
-        let script = format!(
-            r#"display notification "{}" with title "{}" subtitle "{}""#,
-            self.message, self.title, subtitle
-        );
-        
-        tokio::process::Command::new("osascript")
-            .arg("-e")
-            .arg(script)
-            .output()
-            .await?;
-            
-        Ok(())
+
+    /// Notification that an alert at `level` has cleared
+    pub fn resolved(title: &str, message: &str, level: AlertLevel) -> Self {
+        Self {
+            resolved: true,
+            ..Self::new(title, message, level)
+        }
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn level(&self) -> AlertLevel {
+        self.level
+    }
+
+    pub fn is_resolved(&self) -> bool {
+        self.resolved
+    }
+
+    pub fn timestamp(&self) -> SystemTime {
+        self.timestamp
+    }
+}
+
+const MAX_TEXT_CHARS: usize = 256;
+
+/// Make externally sourced text (sensor labels, process names) safe to hand to
+/// notification backends: control characters become spaces and overly long
+/// text is truncated on a character boundary.
+pub fn sanitize_text(text: &str) -> String {
+    let mut clean: String = text
+        .chars()
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .take(MAX_TEXT_CHARS)
+        .collect();
+    if text.chars().count() > MAX_TEXT_CHARS {
+        clean.pop();
+        clean.push('…');
     }
+    clean.trim().to_string()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertPhase {
+    /// Condition holds but has not yet lasted the rule's `for` duration
+    Pending,
+    Firing,
+}
+
+/// An alert that is pending or firing, keyed by rule name and instance
+#[derive(Debug, Clone)]
+pub struct AlertState {
+    pub name: String,
+    pub instance: String,
+    pub phase: AlertPhase,
+    pub level: AlertLevel,
+    /// When the alert entered its current phase (sample time)
+    pub since: SystemTime,
+    pub message: String,
+    rule: usize,
+    last_notified: Option<SystemTime>,
+}
+
+type AlertKey = (String, String);
+
+/// Notifications waiting for delivery before new ones are dropped
+const DELIVERY_QUEUE: usize = 64;
+
+/// A notification and the routes that were configured when it was raised
+struct Delivery {
+    routes: Arc<Vec<SinkRoute>>,
+    notification: Notification,
 }
 
 pub struct NotificationManager {
-    last_notifications: HashMap<String, Instant>,
     cooldown_duration: Duration,
     enabled: bool,
+    rules: Vec<AlertRule>,
+    sinks: Arc<Vec<SinkRoute>>,
+    // Queue to the task that talks to the sinks, started on first use
+    delivery: Option<mpsc::Sender<Delivery>>,
+    // When each (rule, instance) condition started holding, for `for` durations
+    pending_since: HashMap<(usize, String), SystemTime>,
+    alerts: BTreeMap<AlertKey, AlertState>,
 }
 
 impl NotificationManager {
     pub fn new(enabled: bool, cooldown_seconds: u64) -> Self {
         Self {
-            last_notifications: HashMap::new(),
             cooldown_duration: Duration::from_secs(cooldown_seconds),
             enabled,
+            rules: Vec::new(),
+            sinks: Arc::new(vec![SinkRoute::new(desktop_sink(), Vec::new())]),
+            delivery: None,
+            pending_since: HashMap::new(),
+            alerts: BTreeMap::new(),
+        }
+    }
+
+    /// Replace the active rule set (see `Config::alert_rules`). Alerts of unchanged
+    /// rules keep their state; firing alerts of rules that were removed or changed
+    /// resolve, and those notifications are sent and returned.
+    pub fn set_rules(&mut self, rules: Vec<AlertRule>) -> Vec<Notification> {
+        // 旧规则下标 → 新规则中完全相同的那条
+        let remap: Vec<Option<usize>> = self
+            .rules
+            .iter()
+            .map(|old| rules.iter().position(|new| new == old))
+            .collect();
+
+        self.pending_since = std::mem::take(&mut self.pending_since)
+            .into_iter()
+            .filter_map(|((index, instance), since)| Some(((remap[index]?, instance), since)))
+            .collect();
+
+        let mut notifications = Vec::new();
+        for (group, mut state) in std::mem::take(&mut self.alerts) {
+            match remap[state.rule] {
+                Some(index) => {
+                    state.rule = index;
+                    self.alerts.insert(group, state);
+                }
+                None if state.phase == AlertPhase::Firing => {
+                    let rule = &self.rules[state.rule];
+                    let message = format!("Rule '{}' was removed or changed in the configuration", rule.name);
+                    notifications.push(Notification::resolved(&rule.title(), &message, state.level));
+                }
+                None => {}
+            }
         }
+
+        self.rules = rules;
+        self.dispatch(&notifications);
+        notifications
+    }
+
+    /// Replace where notifications are delivered (see `sinks::build_routes`)
+    pub fn set_sinks(&mut self, sinks: Vec<SinkRoute>) {
+        self.sinks = Arc::new(sinks);
+    }
+
+    /// Pending and firing alerts, most severe first
+    pub fn active_alerts(&self) -> Vec<AlertState> {
+        let mut alerts: Vec<AlertState> = self.alerts.values().cloned().collect();
+        alerts.sort_by(|a, b| {
+            (b.phase == AlertPhase::Firing, b.level).cmp(&(a.phase == AlertPhase::Firing, a.level))
+        });
+        alerts
     }
 
-    pub async fn check_and_send_notifications(
+    /// Evaluate a sample and queue the due notifications for delivery. Sinks run on
+    /// their own task, so a slow webhook or command never holds up the caller.
+    pub fn check_and_send_notifications(
         &mut self,
         data: &SystemData,
-        thresholds: &ThresholdConfig,
     ) -> Result<Vec<Notification>, Box<dyn std::error::Error>> {
+        let notifications = self.evaluate(data)?;
         if !self.enabled {
             return Ok(vec![]);
         }
 
-        let mut notifications = Vec::new();
+        self.dispatch(&notifications);
+        Ok(notifications)
+    }
 
-        // Check CPU usage
-        if let Some(notification) = self.check_cpu_threshold(data.cpu_info.average_usage, thresholds) {
-            if self.should_send_notification("cpu") {
-                notification.send().await?;
-                self.last_notifications.insert("cpu".to_string(), Instant::now());
-                notifications.push(notification);
-            }
+    /// Queue notifications for the delivery task
+    fn dispatch(&mut self, notifications: &[Notification]) {
+        if !self.enabled || notifications.is_empty() {
+            return;
         }
-
-        // Check memory usage
-        if let Some(notification) = self.check_memory_threshold(data.memory_info.usage_percentage, thresholds) {
-            if self.should_send_notification("memory") {
-                notification.send().await?;
-                self.last_notifications.insert("memory".to_string(), Instant::now());
-                notifications.push(notification);
+        let delivery = self.delivery.get_or_insert_with(|| {
+            let (sender, receiver) = mpsc::channel(DELIVERY_QUEUE);
+            tokio::spawn(deliver(receiver));
+            sender
+        });
+        for notification in notifications {
+            let queued = delivery.try_send(Delivery {
+                routes: self.sinks.clone(),
+                notification: notification.clone(),
+            });
+            if queued.is_err() {
+                log::warn!("Notification queue is full, dropping '{}'", notification.title());
             }
         }
+    }
+
+    /// Advance every alert's state machine with a sample and return the
+    /// notifications that are due, without sending them.
+    ///
+    /// Only sample timestamps are used for timing, so feeding a synthetic or
+    /// replayed sequence of `SystemData` gives deterministic results.
+    pub fn evaluate(&mut self, data: &SystemData) -> Result<Vec<Notification>, Box<dyn std::error::Error>> {
+        let sample = serde_json::to_value(data)?;
+        let now = data.timestamp;
+        let Self {
+            rules,
+            pending_since,
+            alerts,
+            cooldown_duration,
+            ..
+        } = self;
+
+        // Most severe rule per (name, instance) whose condition has held long enough,
+        // and the most severe one that holds but is still waiting out its `for`
+        let mut triggered: BTreeMap<AlertKey, usize> = BTreeMap::new();
+        let mut waiting: BTreeMap<AlertKey, usize> = BTreeMap::new();
+        let mut values: HashMap<(usize, String), Value> = HashMap::new();
+        let mut holding = HashSet::new();
 
-        // Check temperature
-        if let Some(notification) = self.check_temperature_threshold(&data.temperature_info, thresholds) {
-            if self.should_send_notification("temperature") {
-                notification.send().await?;
-                self.last_notifications.insert("temperature".to_string(), Instant::now());
-                notifications.push(notification);
+        for (index, rule) in rules.iter().enumerate() {
+            for metric in rule.metric.resolve(&sample) {
+                values.insert((index, metric.instance.clone()), metric.value.clone());
+                if !rule.matches(metric.value) {
+                    continue;
+                }
+
+                let key = (index, metric.instance.clone());
+                let since = *pending_since.entry(key.clone()).or_insert(now);
+                holding.insert(key);
+
+                let group = (rule.name.clone(), metric.instance);
+                let held_long_enough = now.duration_since(since).unwrap_or_default() >= rule.for_duration;
+                let slot = if held_long_enough { &mut triggered } else { &mut waiting };
+                if slot.get(&group).is_none_or(|existing| rules[*existing].level < rule.level) {
+                    slot.insert(group, index);
+                }
             }
         }
 
-        Ok(notifications)
-    }
+        // Conditions that stopped holding start their `for` timer over
+        pending_since.retain(|key, _| holding.contains(key));
 
-    fn should_send_notification(&self, key: &str) -> bool {
-        if let Some(last_time) = self.last_notifications.get(key) {
-            last_time.elapsed() >= self.cooldown_duration
-        } else {
-            true
-        }
-    }
+        let mut groups: Vec<AlertKey> = alerts.keys().cloned().collect();
+        groups.extend(triggered.keys().cloned());
+        groups.extend(waiting.keys().cloned());
+        groups.sort();
+        groups.dedup();
 
-    fn check_cpu_threshold(&self, cpu_usage: f32, thresholds: &ThresholdConfig) -> Option<Notification> {
-        if cpu_usage > thresholds.cpu_critical {
-            Some(Notification::new(
-                "CPU Alert",
-                &format!("CPU usage is critically high: {:.1}%", cpu_usage),
-                AlertLevel::Critical,
-            ))
-        } else if cpu_usage > thresholds.cpu_warning {
-            Some(Notification::new(
-                "CPU Alert",
-                &format!("CPU usage is high: {:.1}%", cpu_usage),
-                AlertLevel::Warning,
-            ))
-        } else {
-            None
-        }
-    }
+        let mut notifications = Vec::new();
+        for group in groups {
+            let (name, instance) = group.clone();
+            let firing = alerts
+                .get(&group)
+                .filter(|state| state.phase == AlertPhase::Firing)
+                .cloned();
+            let triggered_rule = triggered.get(&group).copied();
 
-    fn check_memory_threshold(&self, memory_percentage: u16, thresholds: &ThresholdConfig) -> Option<Notification> {
-        if memory_percentage > thresholds.memory_critical {
-            Some(Notification::new(
-                "Memory Alert",
-                &format!("Memory usage is critically high: {}%", memory_percentage),
-                AlertLevel::Critical,
-            ))
-        } else if memory_percentage > thresholds.memory_warning {
-            Some(Notification::new(
-                "Memory Alert",
-                &format!("Memory usage is high: {}%", memory_percentage),
-                AlertLevel::Warning,
-            ))
-        } else {
-            None
-        }
-    }
+            match (firing, triggered_rule) {
+                // Escalate, or start firing
+                (current, Some(index)) if current.as_ref().is_none_or(|c| rules[index].level > c.level) => {
+                    let rule = &rules[index];
+                    let value = &values[&(index, instance.clone())];
+                    let message = rule.render_message(&instance, value);
+                    notifications.push(Notification::new(&rule.title(), &message, rule.level));
+                    alerts.insert(group, AlertState {
+                        name,
+                        instance,
+                        phase: AlertPhase::Firing,
+                        level: rule.level,
+                        since: now,
+                        message,
+                        rule: index,
+                        last_notified: Some(now),
+                    });
+                }
+                (Some(mut state), _) => {
+                    let rule = &rules[state.rule];
+                    let value = values.get(&(state.rule, instance.clone()));
+
+                    // Most severe lower tier that is still above its own recovery threshold
+                    let lower_tier = rules
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, r)| r.name == name && r.level < state.level)
+                        .filter(|(i, r)| values.get(&(*i, instance.clone())).is_some_and(|v| r.still_active(v)))
+                        .max_by_key(|(_, r)| r.level)
+                        .map(|(i, _)| i);
 
-    fn check_temperature_threshold(&self, temperatures: &[crate::types::TemperatureInfo], thresholds: &ThresholdConfig) -> Option<Notification> {
-        for temp_info in temperatures {
-            if temp_info.temperature > thresholds.temperature_critical {
-                return Some(Notification::new(
-                    "Temperature Alert",
-                    &format!("{} temperature is critically high: {:.1}°C", temp_info.label, temp_info.temperature),
-                    AlertLevel::Critical,
-                ));
-            } else if temp_info.temperature > thresholds.temperature_warning {
-                return Some(Notification::new(
-                    "Temperature Alert",
-                    &format!("{} temperature is high: {:.1}°C", temp_info.label, temp_info.temperature),
-                    AlertLevel::Warning,
-                ));
+                    if value.is_some_and(|v| rule.still_active(v)) {
+                        // Still above the recovery threshold - remind every cooldown period
+                        let value = value.expect("checked above");
+                        state.message = rule.render_message(&instance, value);
+                        let due = state
+                            .last_notified
+                            .is_none_or(|last| now.duration_since(last).unwrap_or_default() >= *cooldown_duration);
+                        if due {
+                            notifications.push(Notification::new(&rule.title(), &state.message, state.level));
+                            state.last_notified = Some(now);
+                        }
+                        alerts.insert(group, state);
+                    } else if let Some(index) = lower_tier {
+                        // Dropped out of this tier but a lower one still holds
+                        let lower = &rules[index];
+                        let message = lower.render_message(&instance, &values[&(index, instance.clone())]);
+                        notifications.push(Notification::new(&lower.title(), &message, lower.level));
+                        alerts.insert(group, AlertState {
+                            level: lower.level,
+                            since: now,
+                            message,
+                            rule: index,
+                            last_notified: Some(now),
+                            ..state
+                        });
+                    } else {
+                        let message = rule.render_resolved_message(&instance, value);
+                        notifications.push(Notification::resolved(&rule.title(), &message, state.level));
+                        alerts.remove(&group);
+                    }
+                }
+                (None, _) => match waiting.get(&group) {
+                    Some(&index) => {
+                        let rule = &rules[index];
+                        let message = rule.render_message(&instance, &values[&(index, instance.clone())]);
+                        let since = alerts
+                            .get(&group)
+                            .map(|state| state.since)
+                            .unwrap_or(now);
+                        alerts.insert(group, AlertState {
+                            name,
+                            instance,
+                            phase: AlertPhase::Pending,
+                            level: rule.level,
+                            since,
+                            message,
+                            rule: index,
+                            last_notified: None,
+                        });
+                    }
+                    None => {
+                        alerts.remove(&group);
+                    }
+                },
             }
         }
-        None
+
+        Ok(notifications)
     }
 
     pub fn set_enabled(&mut self, enabled: bool) {
@@ -176,4 +388,15 @@ impl NotificationManager {
     pub fn set_cooldown(&mut self, cooldown_seconds: u64) {
         self.cooldown_duration = Duration::from_secs(cooldown_seconds);
     }
-}
\ No newline at end of file
+}
+
+/// Hand queued notifications to every sink routed for their level, one at a time
+async fn deliver(mut queue: mpsc::Receiver<Delivery>) {
+    while let Some(Delivery { routes, notification }) = queue.recv().await {
+        for route in routes.iter().filter(|route| route.accepts(notification.level())) {
+            if let Err(e) = route.sink.send(&notification).await {
+                log::error!("{} notification sink failed: {}", route.sink.name(), e);
+            }
+        }
+    }
+}
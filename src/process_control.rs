@@ -0,0 +1,83 @@
+use std::io;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+/// Lowest (highest priority) and highest (lowest priority) nice values
+pub const MIN_NICE: i32 = -20;
+pub const MAX_NICE: i32 = 19;
+
+/// Something that can be done to a running process from the UI
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessAction {
+    Terminate,
+    Kill,
+    Stop,
+    Continue,
+    Renice(i32),
+}
+
+impl ProcessAction {
+    pub fn label(&self) -> String {
+        match self {
+            ProcessAction::Terminate => "SIGTERM".to_string(),
+            ProcessAction::Kill => "SIGKILL".to_string(),
+            ProcessAction::Stop => "SIGSTOP".to_string(),
+            ProcessAction::Continue => "SIGCONT".to_string(),
+            ProcessAction::Renice(nice) => format!("renice {}", nice),
+        }
+    }
+
+    /// Apply the action; errors carry the OS reason (e.g. "Operation not permitted")
+    pub fn apply(self, pid: Pid) -> io::Result<()> {
+        let pid = raw_pid(pid)?;
+        match self {
+            ProcessAction::Terminate => send_signal(pid, libc::SIGTERM),
+            ProcessAction::Kill => send_signal(pid, libc::SIGKILL),
+            ProcessAction::Stop => send_signal(pid, libc::SIGSTOP),
+            ProcessAction::Continue => send_signal(pid, libc::SIGCONT),
+            ProcessAction::Renice(nice) => renice(pid, nice),
+        }
+    }
+}
+
+/// Start time of the process holding `pid` right now, in the same units as
+/// `ProcessInfo::start_time`; `None` once it has exited
+pub fn current_start_time(pid: Pid) -> Option<u64> {
+    let mut system = System::new();
+    system.refresh_processes_specifics(ProcessesToUpdate::Some(&[pid]), true, ProcessRefreshKind::nothing());
+    system.process(pid).map(|process| process.start_time())
+}
+
+// kill(2) treats 0 and negative PIDs as process groups, so never pass those through
+fn raw_pid(pid: Pid) -> io::Result<libc::pid_t> {
+    match libc::pid_t::try_from(pid.as_u32()) {
+        Ok(raw) if raw > 0 => Ok(raw),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("refusing to act on PID {}", pid),
+        )),
+    }
+}
+
+fn send_signal(pid: libc::pid_t, signal: libc::c_int) -> io::Result<()> {
+    // SAFETY: kill has no memory-safety preconditions
+    if unsafe { libc::kill(pid, signal) } == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+fn renice(pid: libc::pid_t, nice: i32) -> io::Result<()> {
+    if !(MIN_NICE..=MAX_NICE).contains(&nice) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("nice value must be between {} and {}", MIN_NICE, MAX_NICE),
+        ));
+    }
+    // SAFETY: setpriority has no memory-safety preconditions
+    if unsafe { libc::setpriority(libc::PRIO_PROCESS, pid as libc::id_t, nice) } == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
@@ -0,0 +1,55 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::time::Instant;
+
+/// Turns cumulative counters (bytes, packets, sectors...) into per-second rates,
+/// one set of counters per key such as an interface or device name.
+pub struct CounterRates<K> {
+    previous: HashMap<K, (Instant, Vec<u64>)>,
+}
+
+impl<K: Eq + Hash + Clone> CounterRates<K> {
+    pub fn new() -> Self {
+        Self {
+            previous: HashMap::new(),
+        }
+    }
+
+    /// Per-second rate of each counter since the last update for `key`.
+    /// Returns `None` the first time a key is seen. A counter that went backwards
+    /// was reset; how much of its value came before the reset is unknown, so it
+    /// reports 0 for that interval.
+    pub fn update(&mut self, key: &K, counters: &[u64], now: Instant) -> Option<Vec<f64>> {
+        let rates = match self.previous.get(key) {
+            Some((then, previous)) if previous.len() == counters.len() => {
+                let elapsed = now.saturating_duration_since(*then).as_secs_f64();
+                if elapsed <= 0.0 {
+                    return None;
+                }
+                Some(
+                    counters
+                        .iter()
+                        .zip(previous)
+                        .map(|(current, previous)| {
+                            current.saturating_sub(*previous) as f64 / elapsed
+                        })
+                        .collect(),
+                )
+            }
+            _ => None,
+        };
+        self.previous.insert(key.clone(), (now, counters.to_vec()));
+        rates
+    }
+
+    /// Forget keys that are gone, so one that reappears starts over
+    pub fn retain(&mut self, mut keep: impl FnMut(&K) -> bool) {
+        self.previous.retain(|key, _| keep(key));
+    }
+}
+
+impl<K: Eq + Hash + Clone> Default for CounterRates<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,190 @@
+// Session recording and replay
+//
+// A session file is JSON Lines: one serialized `SystemData` per line. During
+// replay the gaps between recorded timestamps are reproduced (scaled by the
+// replay speed) so history, notifications and the UI see the same cadence
+// they would have seen live.
+
+use crate::{
+    data_collector::{CollectorSchedule, DataCollector},
+    types::SystemData,
+};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Lines, Write};
+use std::iter::Peekable;
+use std::path::Path;
+use std::time::Duration;
+use tokio::time::{Instant, Interval};
+
+pub struct SessionRecorder {
+    writer: BufWriter<File>,
+}
+
+impl SessionRecorder {
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn std::error::Error>> {
+        let file = OpenOptions::new().create(true).write(true).truncate(true).open(path)?;
+        Ok(Self {
+            writer: BufWriter::new(file),
+        })
+    }
+
+    pub fn record(&mut self, data: &SystemData) -> Result<(), Box<dyn std::error::Error>> {
+        serde_json::to_writer(&mut self.writer, data)?;
+        self.writer.write_all(b"\n")?;
+        // Flush every sample so a killed session still leaves a usable file
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+pub struct SessionReplayer {
+    lines: Peekable<Lines<BufReader<File>>>,
+    line_number: usize,
+    pending: Option<SystemData>,
+    next_due: Instant,
+    speed: f64,
+}
+
+impl SessionReplayer {
+    pub fn open<P: AsRef<Path>>(path: P, speed: f64) -> Result<Self, Box<dyn std::error::Error>> {
+        if !(speed.is_finite() && speed > 0.0) {
+            return Err(format!("Invalid replay speed: {}", speed).into());
+        }
+
+        let mut replayer = Self {
+            lines: BufReader::new(File::open(path)?).lines().peekable(),
+            line_number: 0,
+            pending: None,
+            next_due: Instant::now(),
+            speed,
+        };
+        replayer.pending = replayer.read_next()?;
+        if replayer.pending.is_none() {
+            return Err("Session file contains no samples".into());
+        }
+        Ok(replayer)
+    }
+
+    /// Wait until the next recorded sample is due; never resolves once the session has ended.
+    pub async fn tick(&mut self) {
+        if self.pending.is_some() {
+            tokio::time::sleep_until(self.next_due).await;
+        } else {
+            std::future::pending::<()>().await;
+        }
+    }
+
+    /// Take the due sample and schedule the one after it.
+    pub fn next_sample(&mut self) -> Result<Option<SystemData>, Box<dyn std::error::Error>> {
+        let Some(current) = self.pending.take() else {
+            return Ok(None);
+        };
+
+        self.pending = self.read_next()?;
+        if let Some(next) = &self.pending {
+            let gap = next
+                .timestamp
+                .duration_since(current.timestamp)
+                .unwrap_or_default();
+            self.next_due = Instant::now() + gap.div_f64(self.speed);
+        } else {
+            log::info!("Replay finished after {} lines", self.line_number);
+        }
+
+        Ok(Some(current))
+    }
+
+    fn read_next(&mut self) -> Result<Option<SystemData>, Box<dyn std::error::Error>> {
+        while let Some(line) = self.lines.next() {
+            self.line_number += 1;
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str(&line) {
+                Ok(data) => return Ok(Some(data)),
+                // A recording that was killed mid-write ends in a partial line
+                Err(e) if self.lines.peek().is_none() => {
+                    log::warn!("Ignoring truncated sample on line {}: {}", self.line_number, e);
+                    return Ok(None);
+                }
+                Err(e) => return Err(format!("Invalid sample on line {}: {}", self.line_number, e).into()),
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// A zero period would make `tokio::time::interval` panic, so 0 counts as 1 second
+/// like it does in `Config::collector_schedule`
+fn refresh_interval(refresh_rate: u64) -> Interval {
+    tokio::time::interval(Duration::from_secs(refresh_rate.max(1)))
+}
+
+/// Where the main loop gets its samples from: snapshots of the live collectors
+/// (optionally recorded to disk) or a recorded session.
+pub enum SampleFeed {
+    Live {
+        collector: DataCollector,
+        interval: Interval,
+        recorder: Option<SessionRecorder>,
+    },
+    Replay(Box<SessionReplayer>),
+}
+
+impl SampleFeed {
+    pub fn live(collector: DataCollector, refresh_rate: u64, recorder: Option<SessionRecorder>) -> Self {
+        Self::Live {
+            collector,
+            interval: refresh_interval(refresh_rate),
+            recorder,
+        }
+    }
+
+    /// Change the live sampling interval; replays keep their recorded cadence.
+    pub fn set_refresh_rate(&mut self, refresh_rate: u64) {
+        if let Self::Live { interval, .. } = self {
+            *interval = refresh_interval(refresh_rate);
+        }
+    }
+
+    /// Change how often the live collectors run; replays have nothing to collect.
+    pub fn set_schedule(&mut self, schedule: CollectorSchedule) {
+        if let Self::Live { collector, .. } = self {
+            collector.set_schedule(schedule);
+        }
+    }
+
+    /// Wait for the next sample to be due. Cancel safe, so it can sit in `tokio::select!`.
+    pub async fn tick(&mut self) {
+        match self {
+            Self::Live { interval, .. } => {
+                interval.tick().await;
+            }
+            Self::Replay(replayer) => replayer.tick().await,
+        }
+    }
+
+    /// Produce the sample for the tick that just fired; `None` while the live
+    /// collectors haven't produced anything yet.
+    pub async fn next_sample(&mut self) -> Result<Option<SystemData>, Box<dyn std::error::Error>> {
+        match self {
+            Self::Live {
+                collector,
+                recorder,
+                ..
+            } => {
+                let Some(data) = collector.snapshot() else {
+                    return Ok(None);
+                };
+                if let Some(recorder) = recorder {
+                    if let Err(e) = recorder.record(&data) {
+                        log::error!("Failed to record sample: {}", e);
+                    }
+                }
+                Ok(Some(data))
+            }
+            Self::Replay(replayer) => replayer.next_sample(),
+        }
+    }
+}
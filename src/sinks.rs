@@ -0,0 +1,374 @@
+// Notification sinks - where alerts are delivered
+//
+// Each configured sink gets the notifications whose level it is routed for.
+// A failing sink is logged and never stops delivery to the others.
+
+use crate::config::{NotificationConfig, SinkConfig, SinkKind};
+use crate::notification::{AlertLevel, Notification};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::process::Stdio;
+use std::time::{Duration, UNIX_EPOCH};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::time::timeout;
+
+pub type SinkResult = Result<(), Box<dyn std::error::Error + Send + Sync>>;
+pub type SinkFuture<'a> = Pin<Box<dyn Future<Output = SinkResult> + Send + 'a>>;
+
+const SINK_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub trait NotificationSink: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn send<'a>(&'a self, notification: &'a Notification) -> SinkFuture<'a>;
+}
+
+pub struct SinkRoute {
+    pub sink: Box<dyn NotificationSink>,
+    levels: Vec<AlertLevel>,
+}
+
+impl SinkRoute {
+    pub fn new(sink: Box<dyn NotificationSink>, levels: Vec<AlertLevel>) -> Self {
+        Self { sink, levels }
+    }
+
+    pub fn accepts(&self, level: AlertLevel) -> bool {
+        self.levels.is_empty() || self.levels.contains(&level)
+    }
+}
+
+/// Build the configured routes, defaulting to desktop notifications for every level
+pub fn build_routes(config: &NotificationConfig) -> Vec<SinkRoute> {
+    if config.sinks.is_empty() {
+        return vec![SinkRoute::new(desktop_sink(), Vec::new())];
+    }
+    config.sinks.iter().map(build_route).collect()
+}
+
+fn build_route(config: &SinkConfig) -> SinkRoute {
+    let sink: Box<dyn NotificationSink> = match &config.kind {
+        SinkKind::Osascript => Box::new(OsascriptSink),
+        SinkKind::Freedesktop => Box::new(FreedesktopSink),
+        SinkKind::Webhook { url, headers } => Box::new(WebhookSink::new(url, headers.clone())),
+        SinkKind::Command { command, args } => Box::new(CommandSink::new(command, args.clone())),
+        SinkKind::Log { path } => Box::new(LogFileSink::new(path)),
+    };
+    SinkRoute::new(sink, config.levels.clone())
+}
+
+/// Native notifications for the platform we are running on
+pub fn desktop_sink() -> Box<dyn NotificationSink> {
+    if cfg!(target_os = "macos") {
+        Box::new(OsascriptSink)
+    } else {
+        Box::new(FreedesktopSink)
+    }
+}
+
+fn level_name(level: AlertLevel) -> &'static str {
+    match level {
+        AlertLevel::Info => "info",
+        AlertLevel::Warning => "warning",
+        AlertLevel::Critical => "critical",
+    }
+}
+
+/// JSON document sent to webhooks and written to log files
+pub fn notification_payload(notification: &Notification) -> serde_json::Value {
+    let timestamp = notification
+        .timestamp()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs_f64();
+    serde_json::json!({
+        "title": notification.title(),
+        "message": notification.message(),
+        "level": level_name(notification.level()),
+        "resolved": notification.is_resolved(),
+        "timestamp": timestamp,
+        "host": sysinfo::System::host_name().unwrap_or_default(),
+    })
+}
+
+async fn run_command(command: tokio::process::Command, program: &str) -> SinkResult {
+    run_command_with_input(command, program, None).await
+}
+
+/// Run `command`, writing `input` to its stdin when given
+async fn run_command_with_input(mut command: tokio::process::Command, program: &str, input: Option<&str>) -> SinkResult {
+    command
+        .stdin(if input.is_some() { Stdio::piped() } else { Stdio::null() })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let run = async {
+        let mut child = command.spawn()?;
+        if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+            stdin.write_all(input.as_bytes()).await?;
+        }
+        child.wait_with_output().await
+    };
+    let output = timeout(SINK_TIMEOUT, run)
+        .await
+        .map_err(|_| format!("{} timed out after {} seconds", program, SINK_TIMEOUT.as_secs()))?
+        .map_err(|e| format!("failed to run {}: {}", program, e))?;
+    if !output.status.success() {
+        return Err(format!("{} exited with {}: {}", program, output.status, String::from_utf8_lossy(&output.stderr).trim()).into());
+    }
+    Ok(())
+}
+
+pub struct OsascriptSink;
+
+impl NotificationSink for OsascriptSink {
+    fn name(&self) -> &'static str {
+        "osascript"
+    }
+
+    fn send<'a>(&'a self, notification: &'a Notification) -> SinkFuture<'a> {
+        Box::pin(async move { run_command(self.command(notification), "osascript").await })
+    }
+}
+
+impl OsascriptSink {
+    /// The `osascript` invocation for a notification
+    pub fn command(&self, notification: &Notification) -> tokio::process::Command {
+        let subtitle = match (notification.is_resolved(), notification.level()) {
+            (true, _) => "Resolved",
+            (false, AlertLevel::Info) => "Information",
+            (false, AlertLevel::Warning) => "Warning",
+            (false, AlertLevel::Critical) => "Critical Alert",
+        };
+
+        // Values travel as argv items rather than being spliced into the script
+        // source, so quotes or backslashes in sensor/process names can't break
+        // out of the string literal.
+        let mut command = tokio::process::Command::new("osascript");
+        command
+            .args(["-e", "on run argv"])
+            .args(["-e", "display notification (item 1 of argv) with title (item 2 of argv) subtitle (item 3 of argv)"])
+            .args(["-e", "end run"])
+            .arg("--")
+            .arg(notification.message())
+            .arg(notification.title())
+            .arg(subtitle);
+        command
+    }
+}
+
+pub struct FreedesktopSink;
+
+impl NotificationSink for FreedesktopSink {
+    fn name(&self) -> &'static str {
+        "freedesktop"
+    }
+
+    fn send<'a>(&'a self, notification: &'a Notification) -> SinkFuture<'a> {
+        Box::pin(async move {
+            let urgency = match (notification.is_resolved(), notification.level()) {
+                (true, _) | (false, AlertLevel::Info) => "low",
+                (false, AlertLevel::Warning) => "normal",
+                (false, AlertLevel::Critical) => "critical",
+            };
+            let title = if notification.is_resolved() {
+                format!("{} (resolved)", notification.title())
+            } else {
+                notification.title().to_string()
+            };
+
+            let mut command = tokio::process::Command::new("notify-send");
+            command
+                .arg("--app-name=System Alert")
+                .arg(format!("--urgency={}", urgency))
+                .arg("--")
+                .arg(title)
+                .arg(notification.message());
+            run_command(command, "notify-send").await
+        })
+    }
+}
+
+pub struct WebhookSink {
+    url: String,
+    headers: BTreeMap<String, String>,
+}
+
+impl WebhookSink {
+    pub fn new(url: &str, headers: BTreeMap<String, String>) -> Self {
+        Self {
+            url: url.to_string(),
+            headers,
+        }
+    }
+
+    /// Plain HTTP/1.1 POST; enough for local relays and test stand-ins
+    async fn post_http(&self, body: &str) -> SinkResult {
+        let rest = self.url.strip_prefix("http://").ok_or("webhook URL must start with http:// or https://")?;
+        let (authority, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], &rest[i..]),
+            None => (rest, "/"),
+        };
+        let address = connect_address(authority);
+
+        let mut request = format!(
+            "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+            path,
+            authority,
+            body.len()
+        );
+        for (name, value) in &self.headers {
+            if name.contains(['\r', '\n', ':']) || value.contains(['\r', '\n']) {
+                return Err(format!("invalid webhook header '{}'", name).into());
+            }
+            request.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        request.push_str("\r\n");
+        request.push_str(body);
+
+        let exchange = async {
+            let mut stream = tokio::net::TcpStream::connect(&address).await?;
+            stream.write_all(request.as_bytes()).await?;
+            let mut response = Vec::new();
+            stream.read_to_end(&mut response).await?;
+            Ok::<_, std::io::Error>(response)
+        };
+        let response = timeout(SINK_TIMEOUT, exchange)
+            .await
+            .map_err(|_| format!("webhook {} timed out", self.url))??;
+
+        let response = String::from_utf8_lossy(&response);
+        let status_line = response.lines().next().unwrap_or_default();
+        match status_line.split_whitespace().nth(1) {
+            Some(code) if code.starts_with('2') => Ok(()),
+            _ => Err(format!("webhook {} answered '{}'", self.url, status_line).into()),
+        }
+    }
+
+    /// HTTPS goes through curl
+    async fn post_https(&self, body: &str) -> SinkResult {
+        let (command, config) = self.curl(body)?;
+        run_command_with_input(command, "curl", Some(&config)).await
+    }
+
+    /// The `curl` invocation for an HTTPS webhook and the config it reads from stdin.
+    /// Custom headers (often tokens) go through the config so they never show up in `ps`.
+    pub fn curl(&self, body: &str) -> Result<(tokio::process::Command, String), String> {
+        let mut config = String::new();
+        for (name, value) in &self.headers {
+            if name.contains(['\r', '\n', ':']) || value.contains(['\r', '\n']) {
+                return Err(format!("invalid webhook header '{}'", name));
+            }
+            let header = format!("{}: {}", name, value).replace('\\', "\\\\").replace('"', "\\\"");
+            config.push_str(&format!("header = \"{}\"\n", header));
+        }
+
+        let mut command = tokio::process::Command::new("curl");
+        command
+            .args(["--silent", "--show-error", "--fail", "--max-time", "5", "--request", "POST"])
+            .args(["--header", "Content-Type: application/json"])
+            .args(["--config", "-"]);
+        command.arg("--data-raw").arg(body).arg("--").arg(&self.url);
+        Ok((command, config))
+    }
+}
+
+/// `host:port` to connect to, adding port 80 when the URL has none. IPv6 literals
+/// such as `[::1]:8080` keep their brackets, so only a colon after `]` is a port.
+pub fn connect_address(authority: &str) -> String {
+    let has_port = match authority.rfind(']') {
+        Some(end) => authority[end + 1..].starts_with(':'),
+        None => authority.contains(':'),
+    };
+    if has_port {
+        authority.to_string()
+    } else {
+        format!("{}:80", authority)
+    }
+}
+
+impl NotificationSink for WebhookSink {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    fn send<'a>(&'a self, notification: &'a Notification) -> SinkFuture<'a> {
+        Box::pin(async move {
+            let body = notification_payload(notification).to_string();
+            if self.url.starts_with("https://") {
+                self.post_https(&body).await
+            } else {
+                self.post_http(&body).await
+            }
+        })
+    }
+}
+
+pub struct CommandSink {
+    command: String,
+    args: Vec<String>,
+}
+
+impl CommandSink {
+    pub fn new(command: &str, args: Vec<String>) -> Self {
+        Self {
+            command: command.to_string(),
+            args,
+        }
+    }
+}
+
+impl NotificationSink for CommandSink {
+    fn name(&self) -> &'static str {
+        "command"
+    }
+
+    fn send<'a>(&'a self, notification: &'a Notification) -> SinkFuture<'a> {
+        Box::pin(async move {
+            let timestamp = notification
+                .timestamp()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            let mut command = tokio::process::Command::new(&self.command);
+            command
+                .args(&self.args)
+                .env("SYSALERT_TITLE", notification.title())
+                .env("SYSALERT_MESSAGE", notification.message())
+                .env("SYSALERT_LEVEL", level_name(notification.level()))
+                .env("SYSALERT_RESOLVED", if notification.is_resolved() { "1" } else { "0" })
+                .env("SYSALERT_TIMESTAMP", timestamp.to_string());
+            run_command(command, &self.command).await
+        })
+    }
+}
+
+pub struct LogFileSink {
+    path: PathBuf,
+}
+
+impl LogFileSink {
+    pub fn new(path: &str) -> Self {
+        Self { path: PathBuf::from(path) }
+    }
+}
+
+impl NotificationSink for LogFileSink {
+    fn name(&self) -> &'static str {
+        "log"
+    }
+
+    fn send<'a>(&'a self, notification: &'a Notification) -> SinkFuture<'a> {
+        Box::pin(async move {
+            let mut line = notification_payload(notification).to_string();
+            line.push('\n');
+            let mut file = tokio::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)
+                .await?;
+            file.write_all(line.as_bytes()).await?;
+            Ok(())
+        })
+    }
+}
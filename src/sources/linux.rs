@@ -0,0 +1,463 @@
+// Linux sources - procfs and sysfs
+//
+// Every path is resolved relative to a configurable root ("/" in production)
+// so the readers can be pointed at a fixture directory tree.
+
+use super::*;
+use crate::types::{ClusterKind, ClusterMetrics};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Battery state from `/sys/class/power_supply/*`.
+pub struct PowerSupplySource {
+    root: PathBuf,
+}
+
+/// Fans from `/sys/class/hwmon/*`, zones and cooling devices from `/sys/class/thermal/*`,
+/// throttle counters from `/sys/devices/system/cpu/cpu*/thermal_throttle`.
+pub struct SysfsThermalSource {
+    root: PathBuf,
+    throttle_count: Option<u64>,
+}
+
+/// Uptime and load averages from `/proc/uptime` and `/proc/loadavg`.
+pub struct ProcHealthSource {
+    root: PathBuf,
+}
+
+/// Per-device read/write bytes from `/proc/diskstats`.
+pub struct DiskstatsSource {
+    root: PathBuf,
+}
+
+impl PowerSupplySource {
+    pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        Self { root: root.as_ref().to_path_buf() }
+    }
+}
+
+impl SysfsThermalSource {
+    pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        Self { root: root.as_ref().to_path_buf(), throttle_count: None }
+    }
+}
+
+impl ProcHealthSource {
+    pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        Self { root: root.as_ref().to_path_buf() }
+    }
+}
+
+impl DiskstatsSource {
+    pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        Self { root: root.as_ref().to_path_buf() }
+    }
+}
+
+impl BatterySource for PowerSupplySource {
+    fn name(&self) -> &'static str {
+        "sysfs-power_supply"
+    }
+
+    fn sample(&mut self) -> SourceFuture<'_, SourceResult<BatteryInfo>> {
+        Box::pin(async { read_battery_info(&self.root) })
+    }
+}
+
+impl ThermalSource for SysfsThermalSource {
+    fn name(&self) -> &'static str {
+        "sysfs-thermal"
+    }
+
+    fn sample(&mut self) -> SourceFuture<'_, ThermalInfo> {
+        Box::pin(async {
+            let mut thermal_info = read_thermal_info(&self.root);
+            // The counters only ever grow, so throttling means they moved since the last sample
+            let count = read_throttle_count(&self.root);
+            if let (Some(previous), Some(current)) = (self.throttle_count, count) {
+                thermal_info.thermal_throttling |= current > previous;
+            }
+            self.throttle_count = count;
+            thermal_info
+        })
+    }
+}
+
+impl HealthSource for ProcHealthSource {
+    fn name(&self) -> &'static str {
+        "procfs"
+    }
+
+    fn sample(&mut self) -> SourceFuture<'_, SystemHealthInfo> {
+        Box::pin(async { read_system_health(&self.root) })
+    }
+}
+
+/// Fails only when there is no power_supply class at all; a machine without a battery reports defaults
+pub fn read_battery_info(root: &Path) -> SourceResult<BatteryInfo> {
+    let mut battery_info = BatteryInfo::default();
+    let mut found_battery = false;
+
+    let class = root.join("sys/class/power_supply");
+    if !class.is_dir() {
+        return Err(CollectorError::NotFound(class.display().to_string()));
+    }
+    for supply in list_dir(&class) {
+        let supply_type = read_string(&supply.join("type")).unwrap_or_default();
+
+        match supply_type.as_str() {
+            "Mains" | "USB" if read_number::<u32>(&supply.join("online")) == Some(1) => {
+                battery_info.is_plugged = true;
+            }
+            // Only the first battery is reported, like the macOS backend
+            "Battery" if !found_battery => {
+                fill_battery(&supply, &mut battery_info);
+                found_battery = true;
+            }
+            _ => {}
+        }
+    }
+
+    Ok(battery_info)
+}
+
+fn fill_battery(dir: &Path, battery_info: &mut BatteryInfo) {
+    if let Some(capacity) = read_number::<f32>(&dir.join("capacity")) {
+        battery_info.percentage = capacity;
+    }
+
+    let status = read_string(&dir.join("status")).unwrap_or_default();
+    battery_info.is_charging = status == "Charging";
+    if matches!(status.as_str(), "Charging" | "Full" | "Not charging") {
+        battery_info.is_plugged = true;
+    }
+
+    if let Some(cycles) = read_number::<u32>(&dir.join("cycle_count")) {
+        battery_info.cycle_count = cycles;
+    }
+
+    // sysfs reports micro-units: µV, µA, µW, µAh, µWh
+    let voltage_uv = read_number::<f64>(&dir.join("voltage_now"));
+    if let Some(uv) = voltage_uv {
+        battery_info.voltage = (uv / 1_000_000.0) as f32;
+    }
+
+    let current_ua = read_number::<f64>(&dir.join("current_now"));
+    let power_uw = read_number::<f64>(&dir.join("power_now"));
+    let amperage = match (current_ua, power_uw, voltage_uv) {
+        (Some(ua), _, _) => ua / 1_000_000.0,
+        (None, Some(uw), Some(uv)) if uv > 0.0 => uw / uv,
+        _ => 0.0,
+    };
+    // Match the macOS sign convention: negative while discharging
+    battery_info.amperage = if status == "Discharging" { -amperage.abs() } else { amperage.abs() } as f32;
+
+    if let Some(temp) = read_number::<f32>(&dir.join("temp")) {
+        battery_info.temperature = temp / 10.0; // tenths of °C
+    }
+
+    // Capacities are charge based (µAh) on most laptops, energy based (µWh) on others
+    let voltage_design = read_number::<f64>(&dir.join("voltage_min_design")).or(voltage_uv);
+    let (full, full_design, now) = if dir.join("charge_full").exists() {
+        (
+            read_number::<f64>(&dir.join("charge_full")),
+            read_number::<f64>(&dir.join("charge_full_design")),
+            read_number::<f64>(&dir.join("charge_now")),
+        )
+    } else {
+        let to_uah = |uwh: f64| voltage_design.filter(|v| *v > 0.0).map(|v| uwh / v * 1_000_000.0);
+        (
+            read_number::<f64>(&dir.join("energy_full")).and_then(to_uah),
+            read_number::<f64>(&dir.join("energy_full_design")).and_then(to_uah),
+            read_number::<f64>(&dir.join("energy_now")).and_then(to_uah),
+        )
+    };
+
+    if let Some(full) = full {
+        battery_info.current_capacity = (full / 1000.0) as u32;
+    }
+    if let Some(design) = full_design {
+        battery_info.design_capacity = (design / 1000.0) as u32;
+        if let (Some(full), true) = (full, design > 0.0) {
+            battery_info.health_percentage = (full / design * 100.0) as f32;
+        }
+    }
+
+    // Remaining time in seconds, preferring the kernel's own estimate
+    battery_info.time_remaining = if status != "Discharging" {
+        None
+    } else if let Some(seconds) = read_number::<u32>(&dir.join("time_to_empty_now")) {
+        Some(seconds)
+    } else {
+        match now {
+            Some(now) if amperage.abs() > 0.0 => Some((now / 1_000_000.0 / amperage.abs() * 3600.0) as u32),
+            _ => None,
+        }
+    };
+}
+
+pub fn read_thermal_info(root: &Path) -> ThermalInfo {
+    let mut thermal_info = ThermalInfo::default();
+
+    // Fan speeds: hwmon*/fan*_input in RPM
+    for hwmon in list_dir(&root.join("sys/class/hwmon")) {
+        for entry in list_dir(&hwmon) {
+            let file_name = entry.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+            if file_name.starts_with("fan") && file_name.ends_with("_input") {
+                if let Some(rpm) = read_number::<u32>(&entry) {
+                    thermal_info.fan_speeds.push(rpm);
+                }
+            }
+        }
+    }
+
+    // Thermal pressure: hottest zone relative to its critical trip point
+    let mut max_pressure = 0.0f64;
+    for zone in list_dir(&root.join("sys/class/thermal")) {
+        let name = zone.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        if name.starts_with("thermal_zone") {
+            let Some(temp) = read_number::<f64>(&zone.join("temp")) else {
+                continue;
+            };
+            if let Some(critical) = critical_trip_point(&zone) {
+                max_pressure = max_pressure.max(temp / critical * 100.0);
+            }
+        } else if name.starts_with("cooling_device") {
+            // An active processor cooling device means the kernel is throttling
+            let device_type = read_string(&zone.join("type")).unwrap_or_default();
+            let cur_state = read_number::<u32>(&zone.join("cur_state")).unwrap_or(0);
+            if cur_state > 0 && device_type.contains("Processor") {
+                thermal_info.thermal_throttling = true;
+            }
+        }
+    }
+
+    // Pressure is only a gauge; a warm zone far below its trip point is not throttling
+    thermal_info.thermal_pressure = max_pressure.clamp(0.0, 100.0) as u8;
+    thermal_info.heat_dissipation_rate = estimate_heat_dissipation(thermal_info.thermal_pressure);
+
+    thermal_info
+}
+
+/// Sum of the per-CPU core and package throttle counters, `None` where the kernel has none
+pub fn read_throttle_count(root: &Path) -> Option<u64> {
+    let counters: Vec<u64> = list_dir(&root.join("sys/devices/system/cpu"))
+        .iter()
+        .flat_map(|cpu| list_dir(&cpu.join("thermal_throttle")))
+        .filter(|entry| {
+            let file_name = entry.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+            file_name.ends_with("_throttle_count")
+        })
+        .filter_map(|entry| read_number::<u64>(&entry))
+        .collect();
+    (!counters.is_empty()).then(|| counters.iter().sum())
+}
+
+fn critical_trip_point(zone: &Path) -> Option<f64> {
+    (0..16).find_map(|i| {
+        let trip_type = read_string(&zone.join(format!("trip_point_{}_type", i)))?;
+        if trip_type == "critical" {
+            read_number::<f64>(&zone.join(format!("trip_point_{}_temp", i))).filter(|t| *t > 0.0)
+        } else {
+            None
+        }
+    })
+}
+
+pub fn read_system_health(root: &Path) -> SystemHealthInfo {
+    let mut health_info = SystemHealthInfo::default();
+
+    // "12345.67 54321.00" - seconds since boot, idle seconds
+    if let Some(uptime) = read_string(&root.join("proc/uptime")) {
+        if let Some(Ok(seconds)) = uptime.split_whitespace().next().map(str::parse::<f64>) {
+            health_info.uptime_seconds = seconds as u64;
+        }
+    }
+
+    // "0.52 0.58 0.59 1/467 12345"
+    if let Some(loadavg) = read_string(&root.join("proc/loadavg")) {
+        let loads: Vec<f64> = loadavg
+            .split_whitespace()
+            .take(3)
+            .filter_map(|s| s.parse().ok())
+            .collect();
+
+        if loads.len() >= 3 {
+            health_info.system_load_1min = loads[0];
+            health_info.system_load_5min = loads[1];
+            health_info.system_load_15min = loads[2];
+        }
+    }
+
+    apply_load_scores(&mut health_info);
+
+    health_info
+}
+
+impl DiskIoSource for DiskstatsSource {
+    fn name(&self) -> &'static str {
+        "procfs-diskstats"
+    }
+
+    fn sample(&mut self) -> SourceFuture<'_, SourceResult<Vec<DiskIoInfo>>> {
+        Box::pin(async { read_disk_io(&self.root) })
+    }
+}
+
+/// Whole block devices listed in `/sys/block`, skipping partitions, loop and RAM
+/// disks, and devices that never did any I/O
+pub fn read_disk_io(root: &Path) -> SourceResult<Vec<DiskIoInfo>> {
+    let path = root.join("proc/diskstats");
+    let content = fs::read_to_string(&path)
+        .map_err(|e| CollectorError::from_io(&path.display().to_string(), e))?;
+    let devices: Vec<String> = list_dir(&root.join("sys/block"))
+        .iter()
+        .filter_map(|path| path.file_name().map(|name| name.to_string_lossy().into_owned()))
+        .collect();
+
+    Ok(parse_diskstats(&content)
+        .into_iter()
+        // 没有 /sys/block 时无法区分分区，只能全部保留
+        .filter(|disk| devices.is_empty() || devices.contains(&disk.device))
+        .filter(|disk| !is_virtual_disk(&disk.device))
+        .filter(|disk| disk.read_bytes > 0 || disk.written_bytes > 0)
+        .collect())
+}
+
+/// Loop devices mirror I/O on the filesystem holding their image, and RAM disks
+/// never touch a disk; both are listed in `/sys/block` alongside real devices
+fn is_virtual_disk(device: &str) -> bool {
+    ["loop", "ram"].iter().any(|prefix| {
+        device
+            .strip_prefix(prefix)
+            .is_some_and(|number| !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()))
+    })
+}
+
+/// Parse `/proc/diskstats` lines:
+/// "   8       0 sda 1234 56 78900 1000 4321 65 98700 2000 0 3000 3000"
+/// - major, minor, name, reads, merged, sectors read, ms, writes, merged, sectors written, ...
+pub fn parse_diskstats(content: &str) -> Vec<DiskIoInfo> {
+    // diskstats always counts 512-byte sectors, whatever the device's block size
+    const SECTOR_SIZE: u64 = 512;
+
+    content
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 10 {
+                return None;
+            }
+            let sectors_read: u64 = fields[5].parse().ok()?;
+            let sectors_written: u64 = fields[9].parse().ok()?;
+            Some(DiskIoInfo {
+                device: fields[2].to_string(),
+                read_bytes: sectors_read * SECTOR_SIZE,
+                written_bytes: sectors_written * SECTOR_SIZE,
+                read_bytes_per_sec: 0.0,
+                write_bytes_per_sec: 0.0,
+            })
+        })
+        .collect()
+}
+
+/// CPU clusters from sysfs:
+/// - Intel hybrid parts list their cores in `/sys/devices/cpu_core/cpus` and `/sys/devices/cpu_atom/cpus`
+/// - big.LITTLE parts give each CPU a `cpu_capacity`, and `topology/cluster_id` splits equal-capacity cores further
+///
+/// CPUs that all look the same come back as one "CPU" cluster.
+pub fn read_cpu_topology(root: &Path) -> Vec<ClusterMetrics> {
+    let cpu_dir = root.join("sys/devices/system/cpu");
+    let mut cpus: Vec<usize> = list_dir(&cpu_dir)
+        .iter()
+        .filter_map(|path| path.file_name()?.to_str()?.strip_prefix("cpu")?.parse().ok())
+        .collect();
+    cpus.sort_unstable();
+    if cpus.is_empty() {
+        return Vec::new();
+    }
+
+    let atom = read_string(&root.join("sys/devices/cpu_atom/cpus"));
+    let core = read_string(&root.join("sys/devices/cpu_core/cpus"));
+    if let (Some(atom), Some(core)) = (atom, core) {
+        return vec![
+            topology_cluster("E-Cluster".to_string(), ClusterKind::Efficiency, parse_cpu_list(&atom)),
+            topology_cluster("P-Cluster".to_string(), ClusterKind::Performance, parse_cpu_list(&core)),
+        ];
+    }
+
+    // (capacity, cluster_id) -> cores, lowest capacity first
+    let mut groups: BTreeMap<(u32, i64), Vec<usize>> = BTreeMap::new();
+    for &cpu in &cpus {
+        let dir = cpu_dir.join(format!("cpu{}", cpu));
+        let capacity = read_number::<u32>(&dir.join("cpu_capacity")).unwrap_or(0);
+        let cluster_id = read_number::<i64>(&dir.join("topology/cluster_id")).unwrap_or(-1);
+        groups.entry((capacity, cluster_id)).or_default().push(cpu);
+    }
+    let lowest = groups.keys().map(|(capacity, _)| *capacity).min().unwrap_or(0);
+    let highest = groups.keys().map(|(capacity, _)| *capacity).max().unwrap_or(0);
+    // 容量都一样就不按 cluster_id 拆，服务器上那只是 L2 分组
+    if lowest == highest {
+        return vec![topology_cluster("CPU".to_string(), ClusterKind::Unknown, cpus)];
+    }
+
+    let e_count = groups.keys().filter(|(capacity, _)| *capacity == lowest).count();
+    let p_count = groups.len() - e_count;
+    let (mut e_index, mut p_index) = (0, 0);
+    groups
+        .into_iter()
+        .map(|((capacity, _), cores)| {
+            let (kind, letter, index, count) = if capacity == lowest {
+                e_index += 1;
+                (ClusterKind::Efficiency, 'E', e_index - 1, e_count)
+            } else {
+                p_index += 1;
+                (ClusterKind::Performance, 'P', p_index - 1, p_count)
+            };
+            topology_cluster(cluster_name(letter, index, count), kind, cores)
+        })
+        .collect()
+}
+
+/// "E-Cluster" when there is one cluster of a kind, "P0-Cluster", "P1-Cluster"... otherwise
+pub(crate) fn cluster_name(letter: char, index: usize, count: usize) -> String {
+    if count > 1 {
+        format!("{}{}-Cluster", letter, index)
+    } else {
+        format!("{}-Cluster", letter)
+    }
+}
+
+pub(crate) fn topology_cluster(name: String, kind: ClusterKind, cores: Vec<usize>) -> ClusterMetrics {
+    ClusterMetrics { name, kind, cores, ..ClusterMetrics::default() }
+}
+
+/// Expand a kernel CPU list such as "0-3,8-11"
+pub fn parse_cpu_list(list: &str) -> Vec<usize> {
+    list.trim()
+        .split(',')
+        .filter_map(|part| match part.split_once('-') {
+            Some((start, end)) => Some((start.trim().parse::<usize>().ok()?..=end.trim().parse().ok()?).collect()),
+            None => part.trim().parse().ok().map(|cpu| vec![cpu]),
+        })
+        .flatten()
+        .collect()
+}
+
+/// Sorted entries of a directory, or nothing if it doesn't exist
+pub(crate) fn list_dir(dir: &Path) -> Vec<PathBuf> {
+    let mut entries: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|rd| rd.flatten().map(|e| e.path()).collect())
+        .unwrap_or_default();
+    entries.sort();
+    entries
+}
+
+pub(crate) fn read_string(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+pub(crate) fn read_number<T: std::str::FromStr>(path: &Path) -> Option<T> {
+    read_string(path)?.parse().ok()
+}
@@ -0,0 +1,241 @@
+// macOS sources - powermetrics, sysctl, pmset, ioreg and system_profiler
+
+use super::*;
+use super::linux::{cluster_name, topology_cluster};
+use super::powermetrics::PowermetricsSample;
+use super::sampler::{self, PowermetricsSampler, SamplerCommand, SamplerState};
+use crate::battery_collector::FastBatteryCollector;
+use crate::types::{ClusterKind, ClusterMetrics};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+
+/// CPU cluster and power metrics from a long-running `powermetrics` (requires root).
+pub struct PowermetricsSource {
+    sampler: PowermetricsSampler,
+    stale_after: Duration,
+}
+
+impl PowermetricsSource {
+    /// Start powermetrics sampling every `interval`
+    pub fn new(interval: Duration) -> Self {
+        Self::with_command(SamplerCommand::powermetrics(interval), interval)
+    }
+
+    pub fn with_command(command: SamplerCommand, interval: Duration) -> Self {
+        Self {
+            sampler: PowermetricsSampler::spawn(command),
+            // 连续几个周期都没有新样本就当作超时
+            stale_after: (interval * 5).max(Duration::from_secs(5)),
+        }
+    }
+
+    /// Samples for the thermal source, which reads fans and pressure from the same child
+    pub fn subscribe(&self) -> watch::Receiver<SamplerState> {
+        self.sampler.subscribe()
+    }
+}
+
+impl CpuPowerSource for PowermetricsSource {
+    fn name(&self) -> &'static str {
+        "powermetrics"
+    }
+
+    fn sample(&mut self) -> SourceFuture<'_, SourceResult<CPUMetrics>> {
+        Box::pin(async {
+            let update = self.sampler.latest()?;
+            if update.received.elapsed() > self.stale_after {
+                return Err(CollectorError::Timeout { what: "powermetrics".to_string(), after: self.stale_after });
+            }
+            Ok(update.sample.cpu_metrics())
+        })
+    }
+}
+
+/// Thermal pressure from sysctl, fan speeds from the shared powermetrics sampler.
+pub struct MacThermalSource {
+    samples: watch::Receiver<SamplerState>,
+}
+
+impl MacThermalSource {
+    pub fn new(samples: watch::Receiver<SamplerState>) -> Self {
+        Self { samples }
+    }
+}
+
+impl ThermalSource for MacThermalSource {
+    fn name(&self) -> &'static str {
+        "sysctl+powermetrics"
+    }
+
+    fn sample(&mut self) -> SourceFuture<'_, ThermalInfo> {
+        let sample = sampler::latest(&self.samples, "powermetrics").ok().map(|update| update.sample);
+        Box::pin(collect_thermal_info(sample))
+    }
+}
+
+/// Uptime and load averages via sysctl.
+pub struct SysctlHealthSource;
+
+impl HealthSource for SysctlHealthSource {
+    fn name(&self) -> &'static str {
+        "sysctl"
+    }
+
+    fn sample(&mut self) -> SourceFuture<'_, SystemHealthInfo> {
+        Box::pin(collect_system_health())
+    }
+}
+
+impl BatterySource for FastBatteryCollector {
+    fn name(&self) -> &'static str {
+        "pmset+ioreg"
+    }
+
+    fn sample(&mut self) -> SourceFuture<'_, SourceResult<BatteryInfo>> {
+        Box::pin(self.get_battery_info())
+    }
+}
+
+async fn collect_thermal_info(sample: Option<Arc<PowermetricsSample>>) -> ThermalInfo {
+    let mut thermal_info = ThermalInfo::default();
+
+    // Get real thermal pressure from system
+    if let Ok(output) = tokio::process::Command::new("sysctl")
+        .arg("-n")
+        .arg("machdep.xcpm.cpu_thermal_level")
+        .output()
+        .await
+    {
+        if let Ok(thermal_str) = String::from_utf8(output.stdout) {
+            if let Ok(thermal_level) = thermal_str.trim().parse::<u8>() {
+                thermal_info.thermal_pressure = thermal_level * 10; // Convert to percentage
+            }
+        }
+    }
+
+    // Check for thermal throttling via CPU frequency scaling
+    thermal_info.thermal_throttling = thermal_info.thermal_pressure > 50;
+
+    if let Some(sample) = sample {
+        // Apple Silicon 没有 xcpm 这个 sysctl，改用 thermal 采样器给出的压力等级
+        if thermal_info.thermal_pressure == 0 {
+            thermal_info.thermal_pressure = match sample.thermal_pressure.as_deref() {
+                Some("Moderate") => 30,
+                Some("Heavy") => 60,
+                Some("Trapping") | Some("Sleeping") => 90,
+                _ => 0,
+            };
+            thermal_info.thermal_throttling = thermal_info.thermal_pressure > 50;
+        }
+        if !sample.fan_rpm.is_empty() {
+            thermal_info.fan_speeds = sample.fan_rpm.clone();
+        }
+    }
+
+    thermal_info.heat_dissipation_rate = estimate_heat_dissipation(thermal_info.thermal_pressure);
+
+    thermal_info
+}
+
+async fn collect_system_health() -> SystemHealthInfo {
+    // Get real system health data
+    let mut health_info = SystemHealthInfo::default();
+
+    // Get real uptime
+    if let Ok(output) = tokio::process::Command::new("sysctl")
+        .arg("-n")
+        .arg("kern.boottime")
+        .output()
+        .await
+    {
+        if let Ok(boottime_str) = String::from_utf8(output.stdout) {
+            // Parse boottime and calculate uptime
+            if let Some(timestamp_str) = boottime_str.split_whitespace().nth(3) {
+                if let Ok(boot_timestamp) = timestamp_str.trim_end_matches(',').parse::<i64>() {
+                    let now = std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .unwrap_or_default()
+                        .as_secs() as i64;
+                    health_info.uptime_seconds = (now - boot_timestamp) as u64;
+                }
+            }
+        }
+    }
+
+    // Get real load averages
+    if let Ok(output) = tokio::process::Command::new("sysctl")
+        .arg("-n")
+        .arg("vm.loadavg")
+        .output()
+        .await
+    {
+        if let Ok(loadavg_str) = String::from_utf8(output.stdout) {
+            // Parse "{ 2.66 2.75 2.97 }" format
+            let loads: Vec<f64> = loadavg_str
+                .trim()
+                .trim_start_matches('{')
+                .trim_end_matches('}')
+                .split_whitespace()
+                .filter_map(|s| s.parse().ok())
+                .collect();
+
+            if loads.len() >= 3 {
+                health_info.system_load_1min = loads[0];
+                health_info.system_load_5min = loads[1];
+                health_info.system_load_15min = loads[2];
+            }
+        }
+    }
+
+    apply_load_scores(&mut health_info);
+
+    health_info
+}
+
+/// CPU clusters from `sysctl hw`, or nothing on Macs without performance levels (Intel)
+pub fn read_perflevel_topology() -> Vec<ClusterMetrics> {
+    match std::process::Command::new("sysctl").arg("hw").output() {
+        Ok(output) => parse_perflevels(&String::from_utf8_lossy(&output.stdout)),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Build clusters from `hw.perflevelN.*` entries. Level 0 is the fastest and
+/// CPUs are numbered from the slowest level up, so the E-cores come first;
+/// `cpusperl2` splits a level into its L2 clusters (P0/P1 on M1 Pro/Max).
+pub fn parse_perflevels(sysctl_output: &str) -> Vec<ClusterMetrics> {
+    let mut levels: BTreeMap<usize, BTreeMap<&str, &str>> = BTreeMap::new();
+    for line in sysctl_output.lines() {
+        let Some((key, value)) = line.split_once(':') else { continue };
+        let Some(rest) = key.trim().strip_prefix("hw.perflevel") else { continue };
+        let Some((level, field)) = rest.split_once('.') else { continue };
+        if let Ok(level) = level.parse() {
+            levels.entry(level).or_default().insert(field, value.trim());
+        }
+    }
+
+    let mut clusters = Vec::new();
+    let mut next_cpu = 0;
+    for fields in levels.values().rev() {
+        let name = fields.get("name").copied().unwrap_or_default();
+        let cpus: usize = fields.get("logicalcpu").and_then(|v| v.parse().ok()).unwrap_or(0);
+        if cpus == 0 {
+            continue;
+        }
+        let per_l2 = match fields.get("cpusperl2").and_then(|v| v.parse::<usize>().ok()) {
+            Some(n) if n > 0 && n < cpus => n,
+            _ => cpus,
+        };
+        let kind = ClusterKind::from_name(name);
+        let letter = name.chars().next().unwrap_or('C');
+        let count = cpus.div_ceil(per_l2);
+        for index in 0..count {
+            let cores: Vec<usize> = (next_cpu..next_cpu + per_l2.min(cpus - index * per_l2)).collect();
+            next_cpu += cores.len();
+            clusters.push(topology_cluster(cluster_name(letter, index, count), kind, cores));
+        }
+    }
+    clusters
+}
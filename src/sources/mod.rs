@@ -0,0 +1,172 @@
+// Pluggable metric sources - one trait per platform-specific subsystem
+//
+// `DataCollector` only talks to these traits; the concrete backend is picked
+// once at startup by `MetricSources::detect()`. Cross-platform data (CPU usage,
+// memory, networks, filesystems, processes) still comes straight from sysinfo.
+
+pub mod linux;
+pub mod macos;
+pub mod plist;
+pub mod powermetrics;
+pub mod rapl;
+pub mod sampler;
+pub mod unsupported;
+
+pub use crate::error::CollectorError;
+use crate::types::{BatteryInfo, CPUMetrics, ClusterMetrics, DiskIoInfo, SystemHealthInfo, ThermalInfo};
+use std::future::Future;
+use std::pin::Pin;
+
+/// Boxed future returned by source methods so the traits stay object safe.
+pub type SourceFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+pub type SourceResult<T> = crate::error::CollectorResult<T>;
+
+/// CPU cluster activity and package/CPU/GPU/ANE power.
+pub trait CpuPowerSource: Send {
+    fn name(&self) -> &'static str;
+    fn sample(&mut self) -> SourceFuture<'_, SourceResult<CPUMetrics>>;
+}
+
+/// Fans, throttling and thermal pressure.
+pub trait ThermalSource: Send {
+    fn name(&self) -> &'static str;
+    fn sample(&mut self) -> SourceFuture<'_, ThermalInfo>;
+}
+
+/// Battery charge, health and adapter information.
+pub trait BatterySource: Send {
+    fn name(&self) -> &'static str;
+    fn sample(&mut self) -> SourceFuture<'_, SourceResult<BatteryInfo>>;
+}
+
+/// Uptime and load averages.
+pub trait HealthSource: Send {
+    fn name(&self) -> &'static str;
+    fn sample(&mut self) -> SourceFuture<'_, SystemHealthInfo>;
+}
+
+/// Cumulative read/write bytes per block device; rates are left at zero.
+pub trait DiskIoSource: Send {
+    fn name(&self) -> &'static str;
+    fn sample(&mut self) -> SourceFuture<'_, SourceResult<Vec<DiskIoInfo>>>;
+}
+
+pub struct MetricSources {
+    pub cpu_power: Box<dyn CpuPowerSource>,
+    pub thermal: Box<dyn ThermalSource>,
+    pub battery: Box<dyn BatterySource>,
+    pub health: Box<dyn HealthSource>,
+    pub disk_io: Box<dyn DiskIoSource>,
+    /// CPU clusters found at startup; their usage and frequency are filled in from sysinfo
+    /// whenever `cpu_power` doesn't report clusters itself
+    pub cpu_topology: Vec<ClusterMetrics>,
+}
+
+impl MetricSources {
+    /// Pick the best backend for the platform we are running on.
+    pub fn detect() -> Self {
+        let sources = if cfg!(target_os = "macos") {
+            Self::macos()
+        } else if cfg!(target_os = "linux") {
+            Self::linux("/")
+        } else {
+            Self::unsupported()
+        };
+
+        let topology: Vec<String> = sources
+            .cpu_topology
+            .iter()
+            .map(|c| format!("{}({})", c.name, c.cores.len()))
+            .collect();
+        log::info!(
+            "Metric sources: cpu_power={}, thermal={}, battery={}, health={}, disk_io={}, clusters=[{}]",
+            sources.cpu_power.name(),
+            sources.thermal.name(),
+            sources.battery.name(),
+            sources.health.name(),
+            sources.disk_io.name(),
+            topology.join(" "),
+        );
+
+        sources
+    }
+
+    /// macOS backend; starts the shared powermetrics sampler, so it needs a Tokio runtime
+    pub fn macos() -> Self {
+        let powermetrics = macos::PowermetricsSource::new(std::time::Duration::from_secs(1));
+        Self {
+            thermal: Box::new(macos::MacThermalSource::new(powermetrics.subscribe())),
+            cpu_power: Box::new(powermetrics),
+            battery: Box::new(crate::battery_collector::FastBatteryCollector::new()),
+            health: Box::new(macos::SysctlHealthSource),
+            // per-disk counters come from sysinfo's IOKit reader instead
+            disk_io: Box::new(unsupported::UnsupportedSource),
+            cpu_topology: macos::read_perflevel_topology(),
+        }
+    }
+
+    /// Linux backend reading procfs/sysfs below `root` ("/" on a live system).
+    pub fn linux<P: AsRef<std::path::Path>>(root: P) -> Self {
+        let root = root.as_ref();
+        let rapl = rapl::RaplSource::new(root);
+        let cpu_power: Box<dyn CpuPowerSource> = if rapl.is_available() {
+            Box::new(rapl)
+        } else {
+            Box::new(unsupported::UnsupportedSource)
+        };
+        Self {
+            cpu_power,
+            thermal: Box::new(linux::SysfsThermalSource::new(root)),
+            battery: Box::new(linux::PowerSupplySource::new(root)),
+            health: Box::new(linux::ProcHealthSource::new(root)),
+            disk_io: Box::new(linux::DiskstatsSource::new(root)),
+            cpu_topology: linux::read_cpu_topology(root),
+        }
+    }
+
+    pub fn unsupported() -> Self {
+        Self {
+            cpu_power: Box::new(unsupported::UnsupportedSource),
+            thermal: Box::new(unsupported::UnsupportedSource),
+            battery: Box::new(unsupported::UnsupportedSource),
+            health: Box::new(unsupported::UnsupportedSource),
+            disk_io: Box::new(unsupported::UnsupportedSource),
+            cpu_topology: Vec::new(),
+        }
+    }
+}
+
+impl Default for MetricSources {
+    fn default() -> Self {
+        Self::detect()
+    }
+}
+
+/// Estimate heat dissipation based on thermal pressure
+pub fn estimate_heat_dissipation(thermal_pressure: u8) -> f32 {
+    match thermal_pressure {
+        0..=20 => 5.0,
+        21..=40 => 10.0,
+        41..=60 => 15.0,
+        61..=80 => 20.0,
+        _ => 25.0,
+    }
+}
+
+/// Derive the power quality score and sleep/wake efficiency from load averages
+pub fn apply_load_scores(health_info: &mut SystemHealthInfo) {
+    let avg_load = (health_info.system_load_1min + health_info.system_load_5min + health_info.system_load_15min) / 3.0;
+    health_info.power_quality_score = if avg_load < 1.0 {
+        95
+    } else if avg_load < 2.0 {
+        85
+    } else if avg_load < 3.0 {
+        75
+    } else {
+        65
+    };
+
+    // Estimate sleep/wake efficiency (simplified)
+    health_info.sleep_wake_efficiency = if avg_load < 1.5 { 95.0 } else { 85.0 };
+}
@@ -0,0 +1,282 @@
+// Minimal reader for Apple's XML property lists
+//
+// Enough of the format for `powermetrics -f plist` and friends: dict, array,
+// string, integer, real, true/false, date and data. Binary plists are not handled.
+
+use super::*;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlistValue {
+    /// Keys in document order
+    Dict(Vec<(String, PlistValue)>),
+    Array(Vec<PlistValue>),
+    String(String),
+    Integer(i64),
+    Real(f64),
+    Bool(bool),
+    /// ISO 8601 timestamp, kept as written
+    Date(String),
+    /// Base64 payload, kept as written
+    Data(String),
+}
+
+impl PlistValue {
+    /// Look up `key` in a dict; `None` for missing keys and non-dicts
+    pub fn get(&self, key: &str) -> Option<&PlistValue> {
+        match self {
+            PlistValue::Dict(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// Integers and reals as a float
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            PlistValue::Integer(i) => Some(*i as f64),
+            PlistValue::Real(r) => Some(*r),
+            _ => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            PlistValue::Integer(i) => Some(*i),
+            PlistValue::Real(r) => Some(*r as i64),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            PlistValue::String(s) | PlistValue::Date(s) | PlistValue::Data(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            PlistValue::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[PlistValue]> {
+        match self {
+            PlistValue::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+}
+
+/// Parse one XML plist document; `source` names the producer in error messages
+pub fn parse(source: &str, xml: &str) -> SourceResult<PlistValue> {
+    let mut reader = Reader { source, xml, pos: 0 };
+    reader.skip_prolog();
+    let value = match reader.next_tag()? {
+        Tag::Open("plist") => {
+            let value = reader.value()?;
+            reader.expect_close("plist")?;
+            value
+        }
+        // 没有 <plist> 包裹的裸值也接受
+        tag => reader.value_from(tag)?,
+    };
+    reader.skip_misc();
+    if reader.pos < xml.len() {
+        return Err(reader.error());
+    }
+    Ok(value)
+}
+
+enum Tag<'a> {
+    Open(&'a str),
+    Empty(&'a str),
+    Close(&'a str),
+}
+
+struct Reader<'a> {
+    source: &'a str,
+    xml: &'a str,
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn rest(&self) -> &'a str {
+        &self.xml[self.pos..]
+    }
+
+    // 错误信息里带上出错位置附近的一小段原文
+    fn error(&self) -> CollectorError {
+        let near: String = self.rest().chars().take(40).collect();
+        CollectorError::parse(self.source, &near)
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// Skip whitespace, comments and processing instructions
+    fn skip_misc(&mut self) {
+        loop {
+            self.skip_whitespace();
+            let end = if self.rest().starts_with("<!--") {
+                self.rest().find("-->").map(|i| i + 3)
+            } else if self.rest().starts_with("<?") {
+                self.rest().find("?>").map(|i| i + 2)
+            } else {
+                return;
+            };
+            match end {
+                Some(end) => self.pos += end,
+                None => self.pos = self.xml.len(),
+            }
+        }
+    }
+
+    fn skip_prolog(&mut self) {
+        self.skip_misc();
+        if self.rest().starts_with("<!DOCTYPE") {
+            if let Some(end) = self.rest().find('>') {
+                self.pos += end + 1;
+            }
+        }
+        self.skip_misc();
+    }
+
+    fn next_tag(&mut self) -> SourceResult<Tag<'a>> {
+        self.skip_misc();
+        let rest = self.rest();
+        if !rest.starts_with('<') {
+            return Err(self.error());
+        }
+        let end = rest.find('>').ok_or_else(|| self.error())?;
+        let inner = &rest[1..end];
+        self.pos += end + 1;
+
+        if let Some(name) = inner.strip_prefix('/') {
+            return Ok(Tag::Close(name.trim()));
+        }
+        let (inner, empty) = match inner.strip_suffix('/') {
+            Some(inner) => (inner, true),
+            None => (inner, false),
+        };
+        // 属性（比如 <plist version="1.0">）直接忽略
+        let name = inner.split_whitespace().next().unwrap_or("");
+        Ok(if empty { Tag::Empty(name) } else { Tag::Open(name) })
+    }
+
+    fn expect_close(&mut self, name: &str) -> SourceResult<()> {
+        let start = self.pos;
+        match self.next_tag()? {
+            Tag::Close(n) if n == name => Ok(()),
+            _ => {
+                self.pos = start;
+                Err(self.error())
+            }
+        }
+    }
+
+    fn value(&mut self) -> SourceResult<PlistValue> {
+        let tag = self.next_tag()?;
+        self.value_from(tag)
+    }
+
+    fn value_from(&mut self, tag: Tag<'a>) -> SourceResult<PlistValue> {
+        match tag {
+            Tag::Empty(name) => match name {
+                "true" => Ok(PlistValue::Bool(true)),
+                "false" => Ok(PlistValue::Bool(false)),
+                "dict" => Ok(PlistValue::Dict(Vec::new())),
+                "array" => Ok(PlistValue::Array(Vec::new())),
+                "string" => Ok(PlistValue::String(String::new())),
+                "data" => Ok(PlistValue::Data(String::new())),
+                _ => Err(self.error()),
+            },
+            Tag::Open("dict") => self.dict(),
+            Tag::Open("array") => self.array(),
+            Tag::Open(name) => {
+                let text = self.text(name)?;
+                match name {
+                    "string" => Ok(PlistValue::String(text)),
+                    "date" => Ok(PlistValue::Date(text.trim().to_string())),
+                    "data" => Ok(PlistValue::Data(text.split_whitespace().collect())),
+                    "integer" => text.trim().parse().map(PlistValue::Integer).map_err(|_| CollectorError::parse(self.source, &text)),
+                    "real" => text.trim().parse().map(PlistValue::Real).map_err(|_| CollectorError::parse(self.source, &text)),
+                    _ => Err(CollectorError::parse(self.source, &format!("<{}>", name))),
+                }
+            }
+            Tag::Close(_) => Err(self.error()),
+        }
+    }
+
+    fn dict(&mut self) -> SourceResult<PlistValue> {
+        let mut entries = Vec::new();
+        loop {
+            match self.next_tag()? {
+                Tag::Close("dict") => return Ok(PlistValue::Dict(entries)),
+                Tag::Open("key") => {
+                    let key = self.text("key")?;
+                    let value = self.value()?;
+                    entries.push((key, value));
+                }
+                Tag::Empty("key") => {
+                    let value = self.value()?;
+                    entries.push((String::new(), value));
+                }
+                _ => return Err(self.error()),
+            }
+        }
+    }
+
+    fn array(&mut self) -> SourceResult<PlistValue> {
+        let mut items = Vec::new();
+        loop {
+            match self.next_tag()? {
+                Tag::Close("array") => return Ok(PlistValue::Array(items)),
+                tag => items.push(self.value_from(tag)?),
+            }
+        }
+    }
+
+    /// Character data up to the closing `</name>`, with entities decoded
+    fn text(&mut self, name: &str) -> SourceResult<String> {
+        let rest = self.rest();
+        let end = rest.find('<').ok_or_else(|| self.error())?;
+        let text = decode_entities(&rest[..end]).ok_or_else(|| self.error())?;
+        self.pos += end;
+        self.expect_close(name)?;
+        Ok(text)
+    }
+}
+
+fn decode_entities(raw: &str) -> Option<String> {
+    if !raw.contains('&') {
+        return Some(raw.to_string());
+    }
+    let mut out = String::with_capacity(raw.len());
+    let mut rest = raw;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        let end = rest[start..].find(';')? + start;
+        let entity = &rest[start + 1..end];
+        let decoded = match entity {
+            "lt" => '<',
+            "gt" => '>',
+            "amp" => '&',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => {
+                let code = match entity.strip_prefix("#x").or_else(|| entity.strip_prefix("#X")) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                    None => entity.strip_prefix('#')?.parse().ok()?,
+                };
+                char::from_u32(code)?
+            }
+        };
+        out.push(decoded);
+        rest = &rest[end + 1..];
+    }
+    out.push_str(rest);
+    Some(out)
+}
@@ -0,0 +1,277 @@
+// Typed model of `powermetrics -f plist` samples
+//
+// powermetrics writes one plist document per sample, each terminated by a NUL
+// byte. Ratios are kept as powermetrics reports them (0.0-1.0); power is
+// converted from mW to W.
+
+use super::plist::{self, PlistValue};
+use super::*;
+use crate::types::{ClusterKind, ClusterMetrics};
+
+/// One P-state of a DVFS histogram: how much of the interval was spent at `freq_mhz`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DvfsState {
+    pub freq_mhz: u32,
+    pub used_ratio: f64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CoreSample {
+    pub cpu: usize,
+    pub freq_mhz: f64,
+    pub idle_ratio: f64,
+    pub down_ratio: f64,
+    pub dvfs: Vec<DvfsState>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ClusterSample {
+    /// As powermetrics names it: "E-Cluster", "P0-Cluster", ...
+    pub name: String,
+    pub freq_mhz: f64,
+    pub idle_ratio: f64,
+    pub down_ratio: f64,
+    pub dvfs: Vec<DvfsState>,
+    pub cpus: Vec<CoreSample>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GpuSample {
+    pub freq_mhz: f64,
+    pub idle_ratio: f64,
+    pub dvfs: Vec<DvfsState>,
+    pub power_w: f64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PowermetricsSample {
+    pub hw_model: Option<String>,
+    pub elapsed_ns: u64,
+    pub clusters: Vec<ClusterSample>,
+    pub gpu: Option<GpuSample>,
+    pub cpu_power_w: f64,
+    pub gpu_power_w: f64,
+    pub ane_power_w: f64,
+    /// CPU + GPU + ANE, as powermetrics' "Combined Power"
+    pub package_power_w: f64,
+    /// From the thermal sampler: "Nominal", "Moderate", "Heavy", "Trapping" or "Sleeping"
+    pub thermal_pressure: Option<String>,
+    /// From the smc sampler, which only Intel Macs support
+    pub fan_rpm: Vec<u32>,
+}
+
+impl CoreSample {
+    pub fn active_percent(&self) -> f64 {
+        active_percent(self.idle_ratio, self.down_ratio)
+    }
+}
+
+impl ClusterSample {
+    pub fn active_percent(&self) -> f64 {
+        active_percent(self.idle_ratio, self.down_ratio)
+    }
+
+    pub fn kind(&self) -> ClusterKind {
+        ClusterKind::from_name(&self.name)
+    }
+
+    /// The cluster as the UI shows it, cores taken from the per-CPU entries
+    pub fn metrics(&self) -> ClusterMetrics {
+        ClusterMetrics {
+            name: self.name.clone(),
+            kind: self.kind(),
+            active_percent: self.active_percent() as f32,
+            freq_mhz: self.freq_mhz.round() as u32,
+            cores: self.cpus.iter().map(|core| core.cpu).collect(),
+        }
+    }
+}
+
+impl GpuSample {
+    pub fn active_percent(&self) -> f64 {
+        active_percent(self.idle_ratio, 0.0)
+    }
+}
+
+impl PowermetricsSample {
+    pub fn cpu_metrics(&self) -> CPUMetrics {
+        CPUMetrics {
+            clusters: self.clusters.iter().map(ClusterSample::metrics).collect(),
+            cpu_w: self.cpu_power_w,
+            gpu_w: self.gpu_power_w,
+            ane_w: self.ane_power_w,
+            package_w: self.package_power_w,
+        }
+    }
+}
+
+fn active_percent(idle_ratio: f64, down_ratio: f64) -> f64 {
+    ((1.0 - idle_ratio - down_ratio) * 100.0).clamp(0.0, 100.0)
+}
+
+/// The plist documents in a chunk of powermetrics output, oldest first
+pub fn split_samples(output: &str) -> impl Iterator<Item = &str> {
+    output.split('\0').map(str::trim).filter(|doc| !doc.is_empty())
+}
+
+/// The most recent complete sample in `output`
+pub fn parse_latest(output: &str) -> SourceResult<PowermetricsSample> {
+    match split_samples(output).last() {
+        Some(doc) => parse_sample(doc),
+        None => Err(CollectorError::parse("powermetrics", "")),
+    }
+}
+
+/// Parse a single plist document written by `powermetrics -f plist`
+pub fn parse_sample(xml: &str) -> SourceResult<PowermetricsSample> {
+    let root = plist::parse("powermetrics", xml)?;
+    // Intel Mac 或者没开 cpu_power 采样器时没有 processor 这一节
+    let processor = root
+        .get("processor")
+        .ok_or_else(|| CollectorError::parse("powermetrics", "<key>processor</key> missing"))?;
+
+    let elapsed_ns = root.get("elapsed_ns").and_then(PlistValue::as_i64).unwrap_or(0).max(0) as u64;
+    let elapsed_s = elapsed_ns as f64 / 1e9;
+    let clusters = processor
+        .get("clusters")
+        .and_then(PlistValue::as_array)
+        .unwrap_or_default()
+        .iter()
+        .map(|cluster| parse_cluster(cluster, elapsed_s))
+        .collect();
+
+    let mut gpu = root.get("gpu").map(|gpu| parse_gpu(gpu, elapsed_s));
+    let cpu_power_w = power_w(processor, "cpu", elapsed_s);
+    let ane_power_w = power_w(processor, "ane", elapsed_s);
+    let mut gpu_power_w = power_w(processor, "gpu", elapsed_s);
+    if let Some(gpu) = gpu.as_mut() {
+        if gpu_power_w == 0.0 {
+            gpu_power_w = gpu.power_w;
+        }
+        gpu.power_w = gpu_power_w;
+    }
+    let package_power_w = match processor.get("combined_power").and_then(PlistValue::as_f64) {
+        Some(mw) => mw / 1000.0,
+        None => cpu_power_w + gpu_power_w + ane_power_w,
+    };
+
+    Ok(PowermetricsSample {
+        hw_model: root.get("hw_model").and_then(PlistValue::as_str).map(str::to_string),
+        elapsed_ns,
+        clusters,
+        gpu,
+        cpu_power_w,
+        gpu_power_w,
+        ane_power_w,
+        package_power_w,
+        thermal_pressure: root.get("thermal_pressure").and_then(PlistValue::as_str).map(str::to_string),
+        fan_rpm: fan_rpm(root.get("smc")),
+    })
+}
+
+// smc 里的 fan 可能是单个数值，也可能是每个风扇一项的数组
+fn fan_rpm(smc: Option<&PlistValue>) -> Vec<u32> {
+    match smc.and_then(|smc| smc.get("fan")) {
+        Some(PlistValue::Array(fans)) => fans.iter().filter_map(PlistValue::as_f64).map(|rpm| rpm.round() as u32).collect(),
+        Some(fan) => fan.as_f64().map(|rpm| vec![rpm.round() as u32]).unwrap_or_default(),
+        None => Vec::new(),
+    }
+}
+
+fn parse_cluster(cluster: &PlistValue, elapsed_s: f64) -> ClusterSample {
+    let dvfs = parse_dvfs(cluster, elapsed_s);
+    ClusterSample {
+        name: cluster.get("name").and_then(PlistValue::as_str).unwrap_or_default().to_string(),
+        freq_mhz: freq_mhz(cluster, &dvfs),
+        idle_ratio: ratio(cluster, "idle_ratio"),
+        down_ratio: ratio(cluster, "down_ratio"),
+        cpus: cluster
+            .get("cpus")
+            .and_then(PlistValue::as_array)
+            .unwrap_or_default()
+            .iter()
+            .map(|core| parse_core(core, elapsed_s))
+            .collect(),
+        dvfs,
+    }
+}
+
+fn parse_core(core: &PlistValue, elapsed_s: f64) -> CoreSample {
+    let dvfs = parse_dvfs(core, elapsed_s);
+    CoreSample {
+        cpu: core.get("cpu").and_then(PlistValue::as_i64).unwrap_or(0).max(0) as usize,
+        freq_mhz: freq_mhz(core, &dvfs),
+        idle_ratio: ratio(core, "idle_ratio"),
+        down_ratio: ratio(core, "down_ratio"),
+        dvfs,
+    }
+}
+
+fn parse_gpu(gpu: &PlistValue, elapsed_s: f64) -> GpuSample {
+    let dvfs = parse_dvfs(gpu, elapsed_s);
+    // gpu 这一节的 freq_hz 实际单位是 MHz，和 CPU 的不一样
+    let freq_mhz = gpu
+        .get("freq_hz")
+        .and_then(PlistValue::as_f64)
+        .unwrap_or_else(|| dvfs_average(&dvfs));
+    GpuSample {
+        freq_mhz,
+        idle_ratio: ratio(gpu, "idle_ratio"),
+        power_w: power_w(gpu, "gpu", elapsed_s),
+        dvfs,
+    }
+}
+
+/// `dvfm_states` entries; older releases only report `used_ns`, in which case
+/// the ratio is taken against the sample interval
+fn parse_dvfs(value: &PlistValue, elapsed_s: f64) -> Vec<DvfsState> {
+    let states = value.get("dvfm_states").and_then(PlistValue::as_array).unwrap_or_default();
+    states
+        .iter()
+        .map(|state| {
+            let used_ratio = match state.get("used_ratio").and_then(PlistValue::as_f64) {
+                Some(ratio) => ratio,
+                None if elapsed_s > 0.0 => {
+                    state.get("used_ns").and_then(PlistValue::as_f64).unwrap_or(0.0) / 1e9 / elapsed_s
+                }
+                None => 0.0,
+            };
+            DvfsState {
+                freq_mhz: state.get("freq").and_then(PlistValue::as_i64).unwrap_or(0).max(0) as u32,
+                used_ratio,
+            }
+        })
+        .collect()
+}
+
+fn freq_mhz(value: &PlistValue, dvfs: &[DvfsState]) -> f64 {
+    match value.get("freq_hz").and_then(PlistValue::as_f64) {
+        Some(hz) => hz / 1e6,
+        None => dvfs_average(dvfs),
+    }
+}
+
+// 直方图的加权平均频率（只算非空闲时间）
+fn dvfs_average(dvfs: &[DvfsState]) -> f64 {
+    let used: f64 = dvfs.iter().map(|s| s.used_ratio).sum();
+    if used <= 0.0 {
+        return 0.0;
+    }
+    dvfs.iter().map(|s| s.freq_mhz as f64 * s.used_ratio).sum::<f64>() / used
+}
+
+fn ratio(value: &PlistValue, key: &str) -> f64 {
+    value.get(key).and_then(PlistValue::as_f64).unwrap_or(0.0)
+}
+
+/// `<prefix>_power` in mW, or `<prefix>_energy` (mJ over the interval) when the
+/// power key is missing
+fn power_w(section: &PlistValue, prefix: &str, elapsed_s: f64) -> f64 {
+    if let Some(mw) = section.get(&format!("{}_power", prefix)).and_then(PlistValue::as_f64) {
+        return mw / 1000.0;
+    }
+    match section.get(&format!("{}_energy", prefix)).and_then(PlistValue::as_f64) {
+        Some(mj) if elapsed_s > 0.0 => mj / 1000.0 / elapsed_s,
+        _ => 0.0,
+    }
+}
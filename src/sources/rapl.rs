@@ -0,0 +1,153 @@
+// Linux RAPL power via the powercap interface
+//
+// `/sys/class/powercap/intel-rapl:*/energy_uj` are cumulative energy counters
+// (also exposed for AMD parts). Power is the counter delta divided by the time
+// between two samples, so the first sample only primes the counters.
+
+use super::linux::{list_dir, read_number, read_string};
+use super::*;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RaplDomain {
+    Package,
+    Core,
+    Uncore,
+}
+
+#[derive(Debug, Clone)]
+struct RaplZone {
+    path: PathBuf,
+    domain: RaplDomain,
+    max_energy_range_uj: u64,
+}
+
+pub struct RaplSource {
+    root: PathBuf,
+    zones: Vec<RaplZone>,
+    last_energy: HashMap<PathBuf, u64>,
+    last_sample: Option<Instant>,
+}
+
+impl RaplSource {
+    pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        let root = root.as_ref().to_path_buf();
+        let zones = discover_zones(&root);
+        Self {
+            root,
+            zones,
+            last_energy: HashMap::new(),
+            last_sample: None,
+        }
+    }
+
+    /// Whether any package domain was found below the root
+    pub fn is_available(&self) -> bool {
+        self.zones.iter().any(|z| z.domain == RaplDomain::Package)
+    }
+
+    /// Read every counter and convert the deltas since the previous call into watts.
+    pub fn sample_at(&mut self, now: Instant) -> SourceResult<CPUMetrics> {
+        if self.zones.is_empty() {
+            self.zones = discover_zones(&self.root);
+            if self.zones.is_empty() {
+                return Err(CollectorError::NotFound("RAPL domains under sys/class/powercap".to_string()));
+            }
+        }
+
+        let mut energy = HashMap::with_capacity(self.zones.len());
+        for zone in &self.zones {
+            // energy_uj is root-only on most kernels; a value that doesn't parse is a parse error
+            let path = zone.path.join("energy_uj");
+            let content = fs::read_to_string(&path)
+                .map_err(|e| CollectorError::from_io(&path.display().to_string(), e))?;
+            let uj = content
+                .trim()
+                .parse::<u64>()
+                .map_err(|_| CollectorError::parse(&path.display().to_string(), &content))?;
+            energy.insert(zone.path.clone(), uj);
+        }
+
+        let previous = self.last_sample.replace(now);
+        let last_energy = std::mem::replace(&mut self.last_energy, energy);
+
+        let elapsed = match previous {
+            Some(previous) => now.saturating_duration_since(previous).as_secs_f64(),
+            None => return Err(CollectorError::failed("RAPL", "counters primed, power available from the next sample")),
+        };
+        if elapsed <= 0.0 {
+            return Err(CollectorError::failed("RAPL", "sampled twice at the same instant"));
+        }
+
+        let mut metrics = CPUMetrics::default();
+        for zone in &self.zones {
+            let (Some(&before), Some(&after)) = (last_energy.get(&zone.path), self.last_energy.get(&zone.path)) else {
+                continue;
+            };
+            let watts = energy_delta_uj(before, after, zone.max_energy_range_uj) as f64 / 1_000_000.0 / elapsed;
+            match zone.domain {
+                RaplDomain::Package => metrics.package_w += watts,
+                RaplDomain::Core => metrics.cpu_w += watts,
+                RaplDomain::Uncore => metrics.gpu_w += watts,
+            }
+        }
+
+        Ok(metrics)
+    }
+}
+
+impl CpuPowerSource for RaplSource {
+    fn name(&self) -> &'static str {
+        "rapl"
+    }
+
+    fn sample(&mut self) -> SourceFuture<'_, SourceResult<CPUMetrics>> {
+        Box::pin(async { self.sample_at(Instant::now()) })
+    }
+}
+
+/// Counter delta, accounting for a single wrap; the counter runs `0..=max_energy_range_uj`
+fn energy_delta_uj(before: u64, after: u64, max_energy_range_uj: u64) -> u64 {
+    if after >= before {
+        after - before
+    } else if max_energy_range_uj > 0 && max_energy_range_uj >= before {
+        // 计数到 max 之后回到 0，所以回绕一次是 max + 1
+        max_energy_range_uj - before + after + 1
+    } else {
+        // Counter reset without a usable range - skip this interval
+        0
+    }
+}
+
+fn discover_zones(root: &Path) -> Vec<RaplZone> {
+    list_dir(&root.join("sys/class/powercap"))
+        .into_iter()
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with("intel-rapl:"))
+        })
+        .filter_map(|path| {
+            let name = read_string(&path.join("name"))?;
+            let domain = if name.starts_with("package") {
+                RaplDomain::Package
+            } else if name == "core" {
+                RaplDomain::Core
+            } else if name == "uncore" {
+                RaplDomain::Uncore
+            } else {
+                // dram and psys are not part of the CPU package figures
+                return None;
+            };
+            let max_energy_range_uj = read_number::<u64>(&path.join("max_energy_range_uj")).unwrap_or(0);
+            Some(RaplZone {
+                path,
+                domain,
+                max_energy_range_uj,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,180 @@
+// Long-running powermetrics sampler
+//
+// One `powermetrics -i <ms> -f plist` child streams samples for the whole
+// session instead of a new process per refresh. A background task splits its
+// stdout on the NUL byte that ends each plist document, parses the sample and
+// publishes it over a watch channel. A child that exits is restarted with
+// exponential backoff.
+
+use super::powermetrics::{parse_sample, PowermetricsSample};
+use super::*;
+use crate::error::exit_error;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
+use tokio::process::Command;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+/// How to start the sampling child and how long to wait between restarts
+#[derive(Debug, Clone)]
+pub struct SamplerCommand {
+    pub program: String,
+    pub args: Vec<String>,
+    pub min_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl SamplerCommand {
+    pub fn new(program: &str, args: &[&str]) -> Self {
+        Self {
+            program: program.to_string(),
+            args: args.iter().map(|a| a.to_string()).collect(),
+            min_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+        }
+    }
+
+    /// powermetrics with the samplers the CPU power and thermal sources read
+    pub fn powermetrics(interval: Duration) -> Self {
+        let interval_ms = interval.as_millis().max(100).to_string();
+        Self::new(
+            "powermetrics",
+            &["-i", &interval_ms, "-f", "plist", "--samplers", "cpu_power,gpu_power,thermal,smc"],
+        )
+    }
+
+    pub fn with_backoff(mut self, min: Duration, max: Duration) -> Self {
+        self.min_backoff = min;
+        self.max_backoff = max.max(min);
+        self
+    }
+}
+
+/// A parsed sample and when it arrived
+#[derive(Debug, Clone)]
+pub struct SamplerUpdate {
+    pub sample: Arc<PowermetricsSample>,
+    pub received: Instant,
+}
+
+/// `None` until the child first produces output or fails; afterwards the
+/// newest sample, or why the latest attempt to get one failed
+pub type SamplerState = Option<SourceResult<SamplerUpdate>>;
+
+pub struct PowermetricsSampler {
+    program: String,
+    state: watch::Receiver<SamplerState>,
+    task: JoinHandle<()>,
+}
+
+impl PowermetricsSampler {
+    /// Start the child and its reader task; must be called from inside a Tokio runtime
+    pub fn spawn(command: SamplerCommand) -> Self {
+        let (sender, state) = watch::channel(None);
+        let program = command.program.clone();
+        let task = tokio::spawn(run(command, sender));
+        Self { program, state, task }
+    }
+
+    /// A receiver for other sources that read the same samples
+    pub fn subscribe(&self) -> watch::Receiver<SamplerState> {
+        self.state.clone()
+    }
+
+    pub fn latest(&self) -> SourceResult<SamplerUpdate> {
+        latest(&self.state, &self.program)
+    }
+}
+
+impl Drop for PowermetricsSampler {
+    fn drop(&mut self) {
+        // 任务结束时子进程随之被 kill_on_drop 杀掉
+        self.task.abort();
+    }
+}
+
+/// The newest sample in `state`, or why there is none
+pub fn latest(state: &watch::Receiver<SamplerState>, program: &str) -> SourceResult<SamplerUpdate> {
+    match &*state.borrow() {
+        Some(result) => result.clone(),
+        None => Err(CollectorError::failed(program, "waiting for the first sample")),
+    }
+}
+
+async fn run(command: SamplerCommand, sender: watch::Sender<SamplerState>) {
+    let mut backoff = command.min_backoff;
+    loop {
+        let (produced, error) = stream_samples(&command, &sender).await;
+        // 出过数据说明不是启动即失败，退避时间从头算
+        if produced {
+            backoff = command.min_backoff;
+        }
+        log::warn!("{} sampler stopped ({}), restarting in {:?}", command.program, error, backoff);
+        sender.send_replace(Some(Err(error)));
+        tokio::time::sleep(backoff).await;
+        backoff = (backoff * 2).min(command.max_backoff);
+    }
+}
+
+/// Run the child until it exits. Returns whether it produced any sample and why it stopped.
+async fn stream_samples(command: &SamplerCommand, sender: &watch::Sender<SamplerState>) -> (bool, CollectorError) {
+    let program = command.program.as_str();
+    let spawned = Command::new(program)
+        .args(&command.args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn();
+    let mut child = match spawned {
+        Ok(child) => child,
+        Err(e) => return (false, CollectorError::from_io(program, e)),
+    };
+
+    // stderr 单独读，免得管道写满把子进程卡住
+    let stderr_task = child.stderr.take().map(|mut stderr| {
+        tokio::spawn(async move {
+            let mut text = String::new();
+            let _ = stderr.read_to_string(&mut text).await;
+            text
+        })
+    });
+
+    let mut produced = false;
+    if let Some(stdout) = child.stdout.take() {
+        let mut stdout = BufReader::new(stdout);
+        let mut document = Vec::new();
+        loop {
+            document.clear();
+            match stdout.read_until(b'\0', &mut document).await {
+                Ok(0) => break,
+                Ok(_) => {}
+                Err(e) => return (produced, CollectorError::from_io(program, e)),
+            }
+            let text = String::from_utf8_lossy(&document);
+            let text = text.trim_matches(|c: char| c == '\0' || c.is_whitespace());
+            if text.is_empty() {
+                continue;
+            }
+            let update = parse_sample(text).map(|sample| SamplerUpdate {
+                sample: Arc::new(sample),
+                received: Instant::now(),
+            });
+            produced |= update.is_ok();
+            sender.send_replace(Some(update));
+        }
+    }
+
+    let stderr = match stderr_task {
+        Some(task) => task.await.unwrap_or_default(),
+        None => String::new(),
+    };
+    let error = match child.wait().await {
+        Ok(status) if status.success() => CollectorError::failed(program, "exited"),
+        Ok(status) => exit_error(program, status, &stderr),
+        Err(e) => CollectorError::from_io(program, e),
+    };
+    (produced, error)
+}
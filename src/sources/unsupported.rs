@@ -0,0 +1,60 @@
+// Placeholder source for platforms without a dedicated backend
+
+use super::*;
+
+/// Reports empty thermal and health data; CPU power, battery and disk I/O fail
+/// as unsupported so the collector falls back to estimates and sysinfo's counters.
+pub struct UnsupportedSource;
+
+/// What every trait's `name()` returns for `UnsupportedSource`
+pub const NAME: &str = "unsupported";
+
+impl CpuPowerSource for UnsupportedSource {
+    fn name(&self) -> &'static str {
+        NAME
+    }
+
+    fn sample(&mut self) -> SourceFuture<'_, SourceResult<CPUMetrics>> {
+        Box::pin(async { Err(CollectorError::Unsupported("CPU power metrics".to_string())) })
+    }
+}
+
+impl ThermalSource for UnsupportedSource {
+    fn name(&self) -> &'static str {
+        NAME
+    }
+
+    fn sample(&mut self) -> SourceFuture<'_, ThermalInfo> {
+        Box::pin(async { ThermalInfo::default() })
+    }
+}
+
+impl BatterySource for UnsupportedSource {
+    fn name(&self) -> &'static str {
+        NAME
+    }
+
+    fn sample(&mut self) -> SourceFuture<'_, SourceResult<BatteryInfo>> {
+        Box::pin(async { Err(CollectorError::Unsupported("battery information".to_string())) })
+    }
+}
+
+impl HealthSource for UnsupportedSource {
+    fn name(&self) -> &'static str {
+        NAME
+    }
+
+    fn sample(&mut self) -> SourceFuture<'_, SystemHealthInfo> {
+        Box::pin(async { SystemHealthInfo::default() })
+    }
+}
+
+impl DiskIoSource for UnsupportedSource {
+    fn name(&self) -> &'static str {
+        NAME
+    }
+
+    fn sample(&mut self) -> SourceFuture<'_, SourceResult<Vec<DiskIoInfo>>> {
+        Box::pin(async { Err(CollectorError::Unsupported("per-device disk I/O".to_string())) })
+    }
+}
@@ -1,25 +1,40 @@
-use std::time::Instant;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::{Duration, SystemTime};
 use sysinfo::Pid;
 
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
 pub struct CPUMetrics {
-    pub e_cluster_active: i32,
-    pub p_cluster_active: i32,
-    pub e_cluster_freq_mhz: i32,
-    pub p_cluster_freq_mhz: i32,
+    /// One entry per CPU cluster, in the order the platform reports them
+    #[serde(default)]
+    pub clusters: Vec<ClusterMetrics>,
     pub cpu_w: f64,
     pub gpu_w: f64,
     pub ane_w: f64,
     pub package_w: f64,
 }
 
+impl CPUMetrics {
+    /// Average cluster frequency weighted by core count
+    pub fn average_freq_mhz(&self) -> f64 {
+        let cores: usize = self.clusters.iter().map(|c| c.cores.len().max(1)).sum();
+        if cores == 0 {
+            return 0.0;
+        }
+        self.clusters
+            .iter()
+            .map(|c| c.freq_mhz as f64 * c.cores.len().max(1) as f64)
+            .sum::<f64>()
+            / cores as f64
+    }
+}
+
 impl std::fmt::Display for CPUMetrics {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "E-Cluster Active: {}%\nP-Cluster Active: {}%\nE-Cluster Freq: {} MHz\nP-Cluster Freq: {} MHz\nCPU Power: {:.2}W\nGPU Power: {:.2}W\nANE Power: {:.2}W\nPackage Power: {:.2}W",
-        self.e_cluster_active,
-        self.p_cluster_active,
-        self.e_cluster_freq_mhz,
-        self.p_cluster_freq_mhz,
+        for cluster in &self.clusters {
+            writeln!(f, "{} Active: {:.0}%\n{} Freq: {} MHz", cluster.name, cluster.active_percent, cluster.name, cluster.freq_mhz)?;
+        }
+        write!(f, "CPU Power: {:.2}W\nGPU Power: {:.2}W\nANE Power: {:.2}W\nPackage Power: {:.2}W",
         self.cpu_w,
         self.gpu_w,
         self.ane_w,
@@ -28,7 +43,46 @@ impl std::fmt::Display for CPUMetrics {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClusterKind {
+    Efficiency,
+    Performance,
+    /// Homogeneous CPUs, or a cluster the platform didn't classify
+    #[default]
+    Unknown,
+}
+
+impl ClusterKind {
+    /// Classify by the first letter of names like "E-Cluster", "P1-Cluster", "Efficiency"
+    pub fn from_name(name: &str) -> Self {
+        match name.chars().next() {
+            Some('E') => ClusterKind::Efficiency,
+            Some('P') => ClusterKind::Performance,
+            _ => ClusterKind::Unknown,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ClusterKind::Efficiency => "efficiency",
+            ClusterKind::Performance => "performance",
+            ClusterKind::Unknown => "unknown",
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClusterMetrics {
+    pub name: String,
+    pub kind: ClusterKind,
+    pub active_percent: f32,
+    pub freq_mhz: u32,
+    /// Logical CPU numbers in this cluster
+    pub cores: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SystemInfo {
     pub name: String,
     pub kernel_version: String,
@@ -38,14 +92,14 @@ pub struct SystemInfo {
     pub cpu_brand: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CpuInfo {
     pub core_usages: Vec<f32>,
     pub average_usage: f32,
     pub power_metrics: CPUMetrics,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MemoryInfo {
     pub total_memory: u64,
     pub used_memory: u64,
@@ -55,40 +109,95 @@ pub struct MemoryInfo {
     pub usage_percentage: u16,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NetworkInterface {
     pub name: String,
     pub bytes_received: u64,
     pub bytes_transmitted: u64,
     pub packets_received: u64,
     pub packets_transmitted: u64,
+    // per-second rates since the previous sample; zero on an interface's first sample
+    #[serde(default)]
+    pub rx_bytes_per_sec: f64,
+    #[serde(default)]
+    pub tx_bytes_per_sec: f64,
+    #[serde(default)]
+    pub rx_packets_per_sec: f64,
+    #[serde(default)]
+    pub tx_packets_per_sec: f64,
+    #[serde(default)]
+    pub errors_received: u64,
+    #[serde(default)]
+    pub errors_transmitted: u64,
+    #[serde(default)]
+    pub mac_address: Option<String>,
+    // addresses with prefix length, e.g. "192.168.1.20/24"
+    #[serde(default)]
+    pub ip_addresses: Vec<String>,
+}
+
+/// A mounted filesystem
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskInfo {
+    pub name: String,
+    pub mount_point: String,
+    pub file_system: String,
+    pub total_space: u64,
+    pub available_space: u64,
+    pub usage_percentage: f32, // used space, %
+    pub is_removable: bool,
+    pub is_read_only: bool,
+}
+
+/// Read/write activity of a block device
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskIoInfo {
+    pub device: String,
+    pub read_bytes: u64, // cumulative
+    pub written_bytes: u64,
+    // per-second rates since the previous sample; zero on a device's first sample
+    pub read_bytes_per_sec: f64,
+    pub write_bytes_per_sec: f64,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TemperatureInfo {
     pub label: String,
     pub temperature: f32,
     pub critical_temperature: f32,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProcessInfo {
+    #[serde(with = "pid_serde")]
     pub pid: Pid,
     pub name: String,
     pub cpu_usage: f32,
     pub memory_usage: u64,
     pub disk_read_bytes: u64,
     pub disk_write_bytes: u64,
+    #[serde(default, with = "opt_pid_serde")]
+    pub parent_pid: Option<Pid>,
+    #[serde(default)]
+    pub user: Option<String>, // user name, or the numeric uid if it has no name
+    #[serde(default)]
+    pub command: Vec<String>,
+    #[serde(default)]
+    pub threads: Option<u32>, // not reported on every platform
+    #[serde(default)]
+    pub start_time: u64, // seconds since the Unix epoch
+    #[serde(default)]
+    pub status: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BatteryInfo {
     pub percentage: f32,
     pub is_charging: bool,
     pub is_plugged: bool,
     pub health_percentage: f32,
     pub cycle_count: u32,
-    pub time_remaining: Option<u32>, // minutes
+    pub time_remaining: Option<u32>, // seconds
     pub power_adapter_wattage: f32,
     pub current_capacity: u32, // mAh
     pub design_capacity: u32, // mAh
@@ -116,7 +225,7 @@ impl Default for BatteryInfo {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ThermalInfo {
     pub fan_speeds: Vec<u32>, // RPM
     pub thermal_throttling: bool,
@@ -135,7 +244,7 @@ impl Default for ThermalInfo {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PerformanceMetrics {
     pub instructions_per_watt: f64,
     pub performance_per_watt: f64,
@@ -154,7 +263,7 @@ impl Default for PerformanceMetrics {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SystemHealthInfo {
     pub uptime_seconds: u64,
     pub sleep_wake_efficiency: f32, // %
@@ -177,17 +286,123 @@ impl Default for SystemHealthInfo {
     }
 }
 
-#[derive(Debug, Clone)]
+/// A subsystem whose data is missing or estimated, and why
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CollectorIssue {
+    pub subsystem: String, // "cpu_power", "battery"
+    pub source: String,    // the metric source that failed, e.g. "powermetrics"
+    pub kind: String,      // "not found", "permission denied", "timeout", ...
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SystemData {
     pub system_info: SystemInfo,
     pub cpu_info: CpuInfo,
     pub memory_info: MemoryInfo,
     pub network_info: Vec<NetworkInterface>,
+    #[serde(default)]
+    pub disk_info: Vec<DiskInfo>,
+    #[serde(default)]
+    pub disk_io: Vec<DiskIoInfo>,
     pub temperature_info: Vec<TemperatureInfo>,
     pub process_info: Vec<ProcessInfo>,
     pub battery_info: BatteryInfo,
     pub thermal_info: ThermalInfo,
     pub performance_metrics: PerformanceMetrics,
     pub system_health: SystemHealthInfo,
-    pub timestamp: Instant,
-}
\ No newline at end of file
+    pub timestamp: SystemTime, // wall clock, so recorded sessions can be replayed
+    #[serde(default)]
+    pub collector_issues: Vec<CollectorIssue>,
+    /// Where each metric group came from, keyed by field name (`"cpu_info.power_metrics"`
+    /// for CPU power); groups that were never collected have no entry
+    #[serde(default)]
+    pub provenance: BTreeMap<String, Provenance>,
+}
+
+impl SystemData {
+    pub fn provenance(&self, group: &str) -> Option<&Provenance> {
+        self.provenance.get(group)
+    }
+
+    /// Whether `group` holds a recent measurement rather than an estimate, a cached value
+    /// from before its source failed, a stale value or nothing at all
+    pub fn is_measured(&self, group: &str) -> bool {
+        self.provenance(group)
+            .is_some_and(|p| p.origin == DataOrigin::Measured && !p.is_stale(self.timestamp))
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DataOrigin {
+    /// Read from the source on its latest run
+    #[default]
+    Measured,
+    /// Derived from other metrics because the source failed, e.g. power from CPU usage
+    Estimated,
+    /// Kept from an earlier run because the source failed since
+    Cached,
+}
+
+impl DataOrigin {
+    pub fn label(&self) -> &'static str {
+        match self {
+            DataOrigin::Measured => "measured",
+            DataOrigin::Estimated => "estimated",
+            DataOrigin::Cached => "cached",
+        }
+    }
+}
+
+/// How a metric group's values were obtained and when
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Provenance {
+    pub origin: DataOrigin,
+    /// The metric source, e.g. "powermetrics", "sysinfo", "usage-estimate"
+    pub source: String,
+    pub measured_at: SystemTime,
+    /// Values older than this are stale; a few of the group's collection intervals
+    pub max_age: Duration,
+}
+
+impl Provenance {
+    /// Age of the values at `now`, typically the sample's timestamp
+    pub fn age(&self, now: SystemTime) -> Duration {
+        now.duration_since(self.measured_at).unwrap_or_default()
+    }
+
+    pub fn is_stale(&self, now: SystemTime) -> bool {
+        self.age(now) > self.max_age
+    }
+}
+
+// sysinfo only implements Serialize for Pid, so store it as a plain number
+mod pid_serde {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use sysinfo::Pid;
+
+    pub fn serialize<S: Serializer>(pid: &Pid, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u32(pid.as_u32())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Pid, D::Error> {
+        u32::deserialize(deserializer).map(Pid::from_u32)
+    }
+}
+
+mod opt_pid_serde {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use sysinfo::Pid;
+
+    pub fn serialize<S: Serializer>(pid: &Option<Pid>, serializer: S) -> Result<S::Ok, S::Error> {
+        match pid {
+            Some(pid) => serializer.serialize_some(&pid.as_u32()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Pid>, D::Error> {
+        Option::<u32>::deserialize(deserializer).map(|pid| pid.map(Pid::from_u32))
+    }
+}
@@ -0,0 +1,194 @@
+// 历史数据图表：折线图、迷你图和趋势箭头
+use super::{TermFrame, UI};
+use crate::history::HistoryData;
+use tui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    symbols::Marker,
+    text::Span,
+    widgets::{Axis, Block, Borders, Chart, Dataset, GraphType, Sparkline},
+};
+
+/// One line of a history chart
+pub(super) struct Series<'a> {
+    pub name: &'a str,
+    pub values: Vec<f64>,
+    pub color: Color,
+}
+
+/// Arrow for a trend from `get_cpu_trend`/`get_memory_trend`; small changes count as flat
+pub(super) fn trend_arrow(trend: Option<f32>) -> &'static str {
+    match trend {
+        Some(delta) if delta >= 1.0 => "↑",
+        Some(delta) if delta <= -1.0 => "↓",
+        Some(_) => "→",
+        None => "",
+    }
+}
+
+/// Bytes per second with a binary unit
+pub(super) fn format_rate(bytes_per_sec: f64) -> String {
+    const UNITS: [&str; 4] = ["B/s", "KB/s", "MB/s", "GB/s"];
+    let mut value = bytes_per_sec.max(0.0);
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1}{}", value, UNITS[unit])
+}
+
+// 迷你图从左侧开始绘制，只保留能显示下的最新数据
+pub(super) fn latest(values: &[u64], area: Rect) -> &[u64] {
+    let width = area.width.saturating_sub(2) as usize;
+    &values[values.len().saturating_sub(width)..]
+}
+
+/// Axis label for a point `seconds` before the latest sample
+fn format_offset(seconds: u64) -> String {
+    if seconds == 0 {
+        "now".to_string()
+    } else if seconds >= 3600 && seconds.is_multiple_of(3600) {
+        format!("-{}h", seconds / 3600)
+    } else if seconds >= 60 && seconds.is_multiple_of(60) {
+        format!("-{}m", seconds / 60)
+    } else {
+        format!("-{}s", seconds)
+    }
+}
+
+/// Seconds spanned by a full history window
+fn window_seconds(capacity: usize, refresh_rate: u64) -> u64 {
+    capacity.saturating_sub(1) as u64 * refresh_rate.max(1)
+}
+
+// 最新的点在 x = 0，更早的点按刷新间隔向左排列
+fn chart_points(values: &[f64], refresh_rate: u64) -> Vec<(f64, f64)> {
+    let step = refresh_rate.max(1) as f64;
+    let last = values.len().saturating_sub(1);
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| (-((last - i) as f64) * step, *value))
+        .collect()
+}
+
+impl UI {
+    /// Line chart of one or more history series on a time axis derived from the refresh rate
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn draw_history_chart(
+        f: &mut TermFrame,
+        area: Rect,
+        title: &str,
+        series: &[Series],
+        y_max: Option<f64>,
+        format_value: fn(f64) -> String,
+        history: &HistoryData,
+        refresh_rate: u64,
+    ) {
+        let points: Vec<Vec<(f64, f64)>> = series
+            .iter()
+            .map(|s| chart_points(&s.values, refresh_rate))
+            .collect();
+
+        let datasets: Vec<Dataset> = series
+            .iter()
+            .zip(points.iter())
+            .map(|(s, data)| {
+                Dataset::default()
+                    .name(s.name)
+                    .marker(Marker::Braille)
+                    .graph_type(GraphType::Line)
+                    .style(Style::default().fg(s.color))
+                    .data(data)
+            })
+            .collect();
+
+        // 未指定上限时按数据自动缩放
+        let y_max = y_max.unwrap_or_else(|| {
+            let peak = series
+                .iter()
+                .flat_map(|s| s.values.iter().copied())
+                .fold(0.0, f64::max);
+            if peak > 0.0 { peak * 1.1 } else { 1.0 }
+        });
+
+        let window = window_seconds(history.capacity(), refresh_rate);
+        let x_labels = vec![
+            Span::raw(format_offset(window)),
+            Span::raw(format_offset(window / 2)),
+            Span::raw(format_offset(0)),
+        ];
+        let y_labels = vec![
+            Span::raw(format_value(0.0)),
+            Span::raw(format_value(y_max / 2.0)),
+            Span::raw(format_value(y_max)),
+        ];
+
+        let chart = Chart::new(datasets)
+            .block(Block::default().title(title.to_string()).borders(Borders::ALL))
+            .x_axis(Axis::default()
+                .style(Style::default().fg(Color::Gray))
+                .bounds([-(window as f64), 0.0])
+                .labels(x_labels))
+            .y_axis(Axis::default()
+                .style(Style::default().fg(Color::Gray))
+                .bounds([0.0, y_max])
+                .labels(y_labels));
+        f.render_widget(chart, area);
+    }
+
+    /// Row of sparklines for CPU, memory, network and temperature history
+    pub(super) fn draw_history_strip(
+        f: &mut TermFrame,
+        area: Rect,
+        history: &HistoryData,
+    ) {
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Ratio(1, 5); 5].as_ref())
+            .split(area);
+
+        let cpu: Vec<u64> = history.cpu_history.iter().map(|v| *v as u64).collect();
+        let memory: Vec<u64> = history.memory_history.iter().map(|v| *v as u64).collect();
+        let rx: Vec<u64> = history.network_rx_history.iter().map(|v| *v as u64).collect();
+        let tx: Vec<u64> = history.network_tx_history.iter().map(|v| *v as u64).collect();
+        let temperature: Vec<u64> = history.temperature_history.iter().map(|v| *v as u64).collect();
+
+        let cpu_title = format!(
+            "CPU {:.1}% {}",
+            history.cpu_history.back().copied().unwrap_or(0.0),
+            trend_arrow(history.get_cpu_trend())
+        );
+        let memory_title = format!(
+            "MEM {}% {}",
+            history.memory_history.back().copied().unwrap_or(0),
+            trend_arrow(history.get_memory_trend())
+        );
+        let rx_title = format!("↓ {}", format_rate(history.network_rx_history.back().copied().unwrap_or(0.0)));
+        let tx_title = format!("↑ {}", format_rate(history.network_tx_history.back().copied().unwrap_or(0.0)));
+        let temperature_title = match history.temperature_history.back() {
+            Some(temp) => format!("TEMP {:.1}°C", temp),
+            None => "TEMP n/a".to_string(),
+        };
+
+        let sparklines = [
+            (cpu_title, cpu, Some(100), Color::Cyan),
+            (memory_title, memory, Some(100), Color::Green),
+            (rx_title, rx, None, Color::Blue),
+            (tx_title, tx, None, Color::Magenta),
+            (temperature_title, temperature, Some(100), Color::Yellow),
+        ];
+
+        for ((title, values, max, color), area) in sparklines.iter().zip(chunks.iter()) {
+            let mut sparkline = Sparkline::default()
+                .block(Block::default().title(title.as_str()).borders(Borders::ALL))
+                .style(Style::default().fg(*color))
+                .data(latest(values, *area));
+            if let Some(max) = max {
+                sparkline = sparkline.max(*max);
+            }
+            f.render_widget(sparkline, *area);
+        }
+    }
+}
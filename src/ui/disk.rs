@@ -0,0 +1,129 @@
+// 磁盘视图 - 橙色主题：读写吞吐量图表、文件系统占用和逐个设备的读写速率
+use super::charts::{format_rate, Series};
+use super::views::{bar, format_bytes, split_chart};
+use super::{TermFrame, UI};
+use crate::{config::Config, history::HistoryData, types::*};
+use tui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Cell, Row, Table},
+};
+
+const DISK_COLOR: Color = Color::LightRed;
+
+impl UI {
+    pub(super) fn draw_disk_view(
+        f: &mut TermFrame,
+        area: Rect,
+        data: &SystemData,
+        history: &HistoryData,
+        config: &Config,
+    ) {
+        let (area, chart_area) = split_chart(area, area.height / 3, config);
+        if let Some(chart_area) = chart_area {
+            let series = [
+                Series { name: "Read", values: history.disk_read_history.iter().copied().collect(), color: Color::Blue },
+                Series { name: "Write", values: history.disk_write_history.iter().copied().collect(), color: DISK_COLOR },
+            ];
+            Self::draw_history_chart(
+                f, chart_area, "🟠 Disk Throughput", &series, None,
+                format_rate, history, config.refresh_rate,
+            );
+        }
+
+        // 设备表按设备数量取高度，其余留给文件系统
+        let io_height = (data.disk_io.len() as u16 + 3).min(area.height / 2);
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(io_height)].as_ref())
+            .split(area);
+
+        Self::draw_filesystems(f, chunks[0], &data.disk_info, config);
+        Self::draw_disk_io(f, chunks[1], &data.disk_io);
+    }
+
+    fn draw_filesystems(f: &mut TermFrame, area: Rect, disks: &[DiskInfo], config: &Config) {
+        let thresholds = &config.thresholds;
+        let rows: Vec<Row> = disks.iter().map(|disk| {
+            let used = disk.total_space.saturating_sub(disk.available_space);
+            let mut flags = Vec::new();
+            if disk.is_removable {
+                flags.push("removable");
+            }
+            if disk.is_read_only {
+                flags.push("ro");
+            }
+            // 只读镜像总是满的，不按阈值着色
+            let style = if disk.is_read_only {
+                Style::default().fg(Color::Gray)
+            } else if disk.usage_percentage >= thresholds.disk_critical {
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+            } else if disk.usage_percentage >= thresholds.disk_warning {
+                Style::default().fg(Color::Yellow)
+            } else {
+                Style::default().fg(DISK_COLOR)
+            };
+            Row::new(vec![
+                Cell::from(disk.mount_point.clone()),
+                Cell::from(disk.name.clone()),
+                Cell::from(disk.file_system.clone()),
+                Cell::from(format_bytes(disk.total_space)),
+                Cell::from(format_bytes(used)),
+                Cell::from(format_bytes(disk.available_space)),
+                Cell::from(format!("{} {:>3.0}%", bar(disk.usage_percentage, 10), disk.usage_percentage)),
+                Cell::from(flags.join(",")),
+            ])
+            .style(style)
+        }).collect();
+
+        let header = Row::new(vec!["MOUNT", "DEVICE", "FS", "SIZE", "USED", "AVAIL", "USE", "FLAGS"])
+            .style(Style::default().add_modifier(Modifier::BOLD));
+        let table = Table::new(rows)
+            .header(header)
+            .block(Block::default()
+                .title(format!(
+                    "🟠 Filesystems (warning {:.0}%, critical {:.0}%)",
+                    thresholds.disk_warning, thresholds.disk_critical
+                ))
+                .borders(Borders::ALL))
+            .style(Style::default().fg(DISK_COLOR))
+            .widths(&[
+                Constraint::Percentage(22),
+                Constraint::Percentage(16),
+                Constraint::Length(8),
+                Constraint::Length(8),
+                Constraint::Length(8),
+                Constraint::Length(8),
+                Constraint::Length(15),
+                Constraint::Min(0),
+            ]);
+        f.render_widget(table, area);
+    }
+
+    fn draw_disk_io(f: &mut TermFrame, area: Rect, devices: &[DiskIoInfo]) {
+        let rows: Vec<Row> = devices.iter().map(|device| {
+            Row::new(vec![
+                device.device.clone(),
+                format_rate(device.read_bytes_per_sec),
+                format_rate(device.write_bytes_per_sec),
+                format_bytes(device.read_bytes),
+                format_bytes(device.written_bytes),
+            ])
+        }).collect();
+
+        let header = Row::new(vec!["DEVICE", "READ/s", "WRITE/s", "READ", "WRITTEN"])
+            .style(Style::default().add_modifier(Modifier::BOLD));
+        let table = Table::new(rows)
+            .header(header)
+            .block(Block::default().title("🟠 Device I/O").borders(Borders::ALL))
+            .style(Style::default().fg(DISK_COLOR))
+            .widths(&[
+                Constraint::Percentage(30),
+                Constraint::Length(12),
+                Constraint::Length(12),
+                Constraint::Length(10),
+                Constraint::Length(10),
+            ]);
+        f.render_widget(table, area);
+    }
+}
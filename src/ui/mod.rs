@@ -1,16 +1,101 @@
-use crate::{config::Config, history::HistoryData, types::*};
+use crate::{config::Config, history::HistoryData, notification::AlertState, types::*};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::io;
-use termion::raw::IntoRawMode;
+use sysinfo::Pid;
+use termion::{event::Key, raw::IntoRawMode};
 use tui::{
     backend::TermionBackend,
     layout::{Constraint, Direction, Layout, Rect, Alignment},
-    style::{Color, Style},
-    widgets::{Block, Borders, Paragraph, Gauge},
+    style::{Color, Modifier, Style},
+    text::Spans,
+    widgets::{Block, Borders, Paragraph, Gauge, Tabs},
     Frame, Terminal,
 };
 
+mod charts;
+mod disk;
+mod network;
+mod power;
+mod process_actions;
+mod process_detail;
+mod process_table;
+mod process_tree;
+mod views;
+
+pub use process_table::{ProcessSortKey, ProcessTableState};
+pub use process_tree::{ProcessRow, ResourceUsage};
+
+use charts::{format_rate, trend_arrow};
+use views::provenance_tag;
+
+type TermFrame<'a> = Frame<'a, TermionBackend<termion::raw::RawTerminal<std::io::Stderr>>>;
+
+/// Views selectable from the tab bar of the full layout
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Tab {
+    #[default]
+    Overview,
+    Cpu,
+    Memory,
+    Processes,
+    Network,
+    Disk,
+    Power,
+    Thermal,
+    Alerts,
+}
+
+impl Tab {
+    pub const ALL: [Tab; 9] = [
+        Tab::Overview,
+        Tab::Cpu,
+        Tab::Memory,
+        Tab::Processes,
+        Tab::Network,
+        Tab::Disk,
+        Tab::Power,
+        Tab::Thermal,
+        Tab::Alerts,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Tab::Overview => "Overview",
+            Tab::Cpu => "CPU",
+            Tab::Memory => "Memory",
+            Tab::Processes => "Processes",
+            Tab::Network => "Network",
+            Tab::Disk => "Disk",
+            Tab::Power => "Power/Battery",
+            Tab::Thermal => "Thermal",
+            Tab::Alerts => "Alerts",
+        }
+    }
+
+    pub fn index(self) -> usize {
+        Self::ALL.iter().position(|tab| *tab == self).unwrap_or(0)
+    }
+
+    /// Tab at `index`, wrapping around past either end
+    pub fn from_index(index: usize) -> Self {
+        Self::ALL[index % Self::ALL.len()]
+    }
+
+    pub fn next(self) -> Self {
+        Self::from_index(self.index() + 1)
+    }
+
+    pub fn previous(self) -> Self {
+        Self::from_index(self.index() + Self::ALL.len() - 1)
+    }
+}
+
 pub struct UI {
     terminal: Terminal<TermionBackend<termion::raw::RawTerminal<std::io::Stderr>>>,
+    active_tab: Tab,
+    process_table: ProcessTableState,
 }
 
 impl UI {
@@ -21,6 +106,8 @@ impl UI {
         
         Ok(Self {
             terminal,
+            active_tab: Tab::default(),
+            process_table: ProcessTableState::default(),
         })
     }
 
@@ -79,8 +166,11 @@ impl UI {
         data: &SystemData,
         history: &HistoryData,
         config: &Config,
+        alerts: &[AlertState],
     ) -> Result<(), Box<dyn std::error::Error>> {
         let minimal_mode = config.minimal_mode;
+        let active_tab = self.active_tab;
+        let process_table = &mut self.process_table;
         let data_clone = data.clone();
         let history_clone = history.clone();
         
@@ -88,7 +178,7 @@ impl UI {
             if minimal_mode {
                 Self::draw_minimal_layout_static(f, &data_clone);
             } else {
-                Self::draw_full_layout_static(f, &data_clone, &history_clone);
+                Self::draw_full_layout_static(f, &data_clone, &history_clone, active_tab, config, alerts, process_table);
             }
         })?;
         Ok(())
@@ -103,34 +193,97 @@ impl UI {
 
         // CPU and Memory Gauges
         let cpu_gauge = Gauge::default()
-            .block(Block::default().title("CPU Usage").borders(Borders::ALL))
+            .block(Block::default().title(format!("CPU Usage{}", provenance_tag(data, "cpu_info"))).borders(Borders::ALL))
             .gauge_style(Style::default().fg(Color::Cyan))
             .percent(data.cpu_info.average_usage as u16)
             .label(format!("{:.1}%", data.cpu_info.average_usage));
         f.render_widget(cpu_gauge, chunks[0]);
 
         let memory_gauge = Gauge::default()
-            .block(Block::default().title("Memory Usage").borders(Borders::ALL))
+            .block(Block::default().title(format!("Memory Usage{}", provenance_tag(data, "memory_info"))).borders(Borders::ALL))
             .gauge_style(Style::default().fg(Color::Green))
             .percent(data.memory_info.usage_percentage)
             .label(format!("{}%", data.memory_info.usage_percentage));
         f.render_widget(memory_gauge, chunks[1]);
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn draw_full_layout_static(
-        f: &mut Frame<TermionBackend<termion::raw::RawTerminal<std::io::Stderr>>>,
+        f: &mut TermFrame,
         data: &SystemData,
-        _history: &HistoryData,
+        history: &HistoryData,
+        tab: Tab,
+        config: &Config,
+        alerts: &[AlertState],
+        process_table: &mut ProcessTableState,
     ) {
-        // 全新的4象限布局设计
-        let main_chunks = Layout::default()
+        let chunks = Layout::default()
             .direction(Direction::Vertical)
             .margin(1)
+            .constraints([
+                Constraint::Length(3), // 标签栏
+                Constraint::Min(0),    // 当前视图
+            ].as_ref())
+            .split(f.size());
+
+        Self::draw_tab_bar(f, chunks[0], tab);
+
+        let area = chunks[1];
+        match tab {
+            Tab::Overview => Self::draw_overview(f, area, data, history, config),
+            Tab::Cpu => Self::draw_cpu_view(f, area, data, history, config),
+            Tab::Memory => Self::draw_memory_view(f, area, data, history, config),
+            Tab::Processes => Self::draw_processes_view(f, area, data, history, config.refresh_rate, process_table),
+            Tab::Network => Self::draw_network_view(f, area, &data.network_info, history, config),
+            Tab::Disk => Self::draw_disk_view(f, area, data, history, config),
+            Tab::Power => Self::draw_power_view(f, area, data, history, config),
+            Tab::Thermal => Self::draw_thermal_view(f, area, data, history, config),
+            Tab::Alerts => Self::draw_alerts_view(f, area, data, config, alerts),
+        }
+    }
+
+    fn draw_tab_bar(f: &mut TermFrame, area: Rect, tab: Tab) {
+        let titles: Vec<Spans> = Tab::ALL
+            .iter()
+            .enumerate()
+            .map(|(i, tab)| Spans::from(format!("{} {}", i + 1, tab.title())))
+            .collect();
+
+        let tabs = Tabs::new(titles)
+            .block(Block::default().borders(Borders::ALL))
+            .select(tab.index())
+            .style(Style::default().fg(Color::Gray))
+            .highlight_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD));
+        f.render_widget(tabs, area);
+    }
+
+    // 全新的4象限布局设计
+    fn draw_overview(
+        f: &mut TermFrame,
+        area: Rect,
+        data: &SystemData,
+        history: &HistoryData,
+        config: &Config,
+    ) {
+        // 开启历史记录时在底部显示迷你图
+        let area = if config.display.show_history {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(0), Constraint::Length(5)].as_ref())
+                .split(area);
+            Self::draw_history_strip(f, chunks[1], history);
+            chunks[0]
+        } else {
+            area
+        };
+
+        let main_chunks = Layout::default()
+            .direction(Direction::Vertical)
             .constraints([
                 Constraint::Percentage(50), // 上半部分
                 Constraint::Percentage(50), // 下半部分
             ].as_ref())
-            .split(f.size());
+            .split(area);
 
         // 上半部分：CPU和功率信息
         let top_chunks = Layout::default()
@@ -150,10 +303,10 @@ impl UI {
             ].as_ref())
             .split(main_chunks[1]);
 
-        Self::draw_cpu_section(f, top_chunks[0], data);
+        Self::draw_cpu_section(f, top_chunks[0], data, history);
         Self::draw_power_section(f, top_chunks[1], data);
-        Self::draw_memory_temp_section(f, bottom_chunks[0], data);
-        Self::draw_process_network_section(f, bottom_chunks[1], data);
+        Self::draw_memory_temp_section(f, bottom_chunks[0], data, history);
+        Self::draw_process_network_section(f, bottom_chunks[1], data, config);
     }
 
     // 新的CPU专区 - 蓝色主题
@@ -161,29 +314,34 @@ impl UI {
         f: &mut Frame<TermionBackend<termion::raw::RawTerminal<std::io::Stderr>>>,
         area: Rect,
         data: &SystemData,
+        history: &HistoryData,
     ) {
+        let clusters = &data.cpu_info.power_metrics.clusters;
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
-                Constraint::Length(6),  // CPU总览
+                Constraint::Length(4 + clusters.len() as u16),  // CPU总览
                 Constraint::Min(0),     // 核心详情
             ].as_ref())
             .split(area);
 
         // CPU Overview Information
-        let cpu_overview = format!(
-            "🔵 CPU: {} ({})\nAverage Usage: {:.1}%\nE-Cluster: {}% @ {} MHz\nP-Cluster: {}% @ {} MHz",
+        let mut cpu_overview = format!(
+            "🔵 CPU: {} ({})\nAverage Usage: {:.1}% {}",
             data.system_info.cpu_brand,
             data.system_info.cpu_arch,
             data.cpu_info.average_usage,
-            data.cpu_info.power_metrics.e_cluster_active,
-            data.cpu_info.power_metrics.e_cluster_freq_mhz,
-            data.cpu_info.power_metrics.p_cluster_active,
-            data.cpu_info.power_metrics.p_cluster_freq_mhz
+            trend_arrow(history.get_cpu_trend()),
         );
+        for cluster in clusters {
+            cpu_overview.push_str(&format!(
+                "\n{}: {:.0}% @ {} MHz",
+                cluster.name, cluster.active_percent, cluster.freq_mhz
+            ));
+        }
 
         let cpu_block = Paragraph::new(cpu_overview)
-            .block(Block::default().title("🔵 CPU Information").borders(Borders::ALL))
+            .block(Block::default().title(format!("🔵 CPU Information{}", provenance_tag(data, "cpu_info"))).borders(Borders::ALL))
             .style(Style::default().fg(Color::Cyan));
         f.render_widget(cpu_block, chunks[0]);
 
@@ -298,6 +456,29 @@ impl UI {
             data.system_health.system_load_15min, data.system_health.sleep_wake_efficiency as u8
         );
 
+        // 数据缺失、估算、缓存或过期时标出来，并说明原因
+        let mut issue_lines: Vec<String> = [
+            ("battery", "battery_info"),
+            ("power", "cpu_info.power_metrics"),
+            ("thermal", "thermal_info"),
+            ("health", "system_health"),
+        ]
+        .iter()
+        .filter_map(|(label, group)| {
+            let tag = provenance_tag(data, group);
+            let source = data.provenance(group).map(|p| format!(" from {}", p.source)).unwrap_or_default();
+            (!tag.is_empty()).then(|| format!("ⓘ {}:{}{}", label, tag, source))
+        })
+        .collect();
+        issue_lines.extend(data.collector_issues.iter().map(|issue| {
+            format!("⚠ {} ({}): {}", issue.subsystem, issue.source, issue.message)
+        }));
+        let power_info = if issue_lines.is_empty() {
+            power_info
+        } else {
+            format!("{}\n{}", power_info, issue_lines.join("\n"))
+        };
+
         let power_block = Paragraph::new(power_info)
             .block(Block::default().title("🔴 Power Analytics").borders(Borders::ALL))
             .style(Style::default().fg(Color::Red));
@@ -309,6 +490,7 @@ impl UI {
         f: &mut Frame<TermionBackend<termion::raw::RawTerminal<std::io::Stderr>>>,
         area: Rect,
         data: &SystemData,
+        history: &HistoryData,
     ) {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
@@ -323,17 +505,18 @@ impl UI {
         let memory_bar = "█".repeat(memory_bar_length) + &"░".repeat(30 - memory_bar_length);
         
         let memory_info = format!(
-            "🟢 Memory: {:.1}GB / {:.1}GB ({}%)\n[{}]\nSwap: {:.1}GB / {:.1}GB",
+            "🟢 Memory: {:.1}GB / {:.1}GB ({}%) {}\n[{}]\nSwap: {:.1}GB / {:.1}GB",
             data.memory_info.used_memory as f64 / (1024.0 * 1024.0 * 1024.0),
             data.memory_info.total_memory as f64 / (1024.0 * 1024.0 * 1024.0),
             data.memory_info.usage_percentage,
+            trend_arrow(history.get_memory_trend()),
             memory_bar,
             data.memory_info.used_swap as f64 / (1024.0 * 1024.0 * 1024.0),
             data.memory_info.total_swap as f64 / (1024.0 * 1024.0 * 1024.0)
         );
 
         let memory_block = Paragraph::new(memory_info)
-            .block(Block::default().title("🟢 Memory").borders(Borders::ALL))
+            .block(Block::default().title(format!("🟢 Memory{}", provenance_tag(data, "memory_info"))).borders(Borders::ALL))
             .style(Style::default().fg(Color::Green));
         f.render_widget(memory_block, chunks[0]);
 
@@ -348,7 +531,7 @@ impl UI {
         let temp_info = format!("🟡 Temperature Monitor\n{}", temp_details.join("\n"));
 
         let temp_block = Paragraph::new(temp_info)
-            .block(Block::default().title("🟡 Temperature").borders(Borders::ALL))
+            .block(Block::default().title(format!("🟡 Temperature{}", provenance_tag(data, "temperature_info"))).borders(Borders::ALL))
             .style(Style::default().fg(Color::Yellow));
         f.render_widget(temp_block, chunks[1]);
     }
@@ -358,6 +541,7 @@ impl UI {
         f: &mut Frame<TermionBackend<termion::raw::RawTerminal<std::io::Stderr>>>,
         area: Rect,
         data: &SystemData,
+        config: &Config,
     ) {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
@@ -389,11 +573,19 @@ impl UI {
         f.render_widget(process_block, chunks[0]);
 
         // Network Information - Cyan Theme
-        let total_rx = data.network_info.iter().map(|n| n.bytes_received).sum::<u64>();
-        let total_tx = data.network_info.iter().map(|n| n.bytes_transmitted).sum::<u64>();
+        let filter = config.display.interface_filter();
+        let interfaces: Vec<&NetworkInterface> =
+            data.network_info.iter().filter(|n| filter.matches(&n.name)).collect();
+        let total_rx = interfaces.iter().map(|n| n.bytes_received).sum::<u64>();
+        let total_tx = interfaces.iter().map(|n| n.bytes_transmitted).sum::<u64>();
+
+        let rx_rate = interfaces.iter().map(|n| n.rx_bytes_per_sec).sum::<f64>();
+        let tx_rate = interfaces.iter().map(|n| n.tx_bytes_per_sec).sum::<f64>();
 
         let network_info = format!(
-            "🔵 Network Total\n↓ {:.1}GB  ↑ {:.1}GB",
+            "🔵 Network Total\n↓ {}  ↑ {}\n↓ {:.1}GB  ↑ {:.1}GB total",
+            format_rate(rx_rate),
+            format_rate(tx_rate),
             total_rx as f64 / (1024.0 * 1024.0 * 1024.0),
             total_tx as f64 / (1024.0 * 1024.0 * 1024.0)
         );
@@ -410,11 +602,42 @@ impl UI {
         Ok(())
     }
 
+    pub fn active_tab(&self) -> Tab {
+        self.active_tab
+    }
+
+    pub fn set_tab(&mut self, tab: Tab) {
+        self.active_tab = tab;
+    }
+
+    /// Pass a key to the active view, returning whether it was used
+    pub fn handle_key(&mut self, key: Key) -> bool {
+        match self.active_tab {
+            Tab::Processes => self.process_table.handle_key(key),
+            _ => false,
+        }
+    }
+
+    /// Turn off signals and renice in the process table, showing `reason` instead
+    pub fn disable_process_actions(&mut self, reason: &str) {
+        self.process_table.disable_actions(reason);
+    }
+
+    /// Processes pinned in the process table, whose history should always be kept
+    pub fn pinned_processes(&self) -> HashSet<Pid> {
+        self.process_table.pinned().clone()
+    }
+
+    /// Whether a text field or dialog takes every key, so keys must not be used as commands
+    pub fn captures_keys(&self) -> bool {
+        self.active_tab == Tab::Processes && self.process_table.captures_keys()
+    }
+
     pub fn next_tab(&mut self) {
-        // 新界面不需要tab切换
+        self.active_tab = self.active_tab.next();
     }
 
     pub fn previous_tab(&mut self) {
-        // 新界面不需要tab切换
+        self.active_tab = self.active_tab.previous();
     }
 }
\ No newline at end of file
@@ -0,0 +1,139 @@
+// 网络视图 - 青色主题：汇总吞吐量图表和逐个接口的详情
+use super::charts::{format_rate, latest, Series};
+use super::views::{format_bytes, split_chart};
+use super::{TermFrame, UI};
+use crate::{config::Config, history::HistoryData, types::NetworkInterface};
+use tui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Paragraph, Sparkline},
+};
+
+/// Rows taken by one interface panel, borders included
+const INTERFACE_HEIGHT: u16 = 5;
+
+impl UI {
+    pub(super) fn draw_network_view(
+        f: &mut TermFrame,
+        area: Rect,
+        interfaces: &[NetworkInterface],
+        history: &HistoryData,
+        config: &Config,
+    ) {
+        let (area, chart_area) = split_chart(area, area.height / 3, config);
+        if let Some(chart_area) = chart_area {
+            let series = [
+                Series { name: "↓ RX", values: history.network_rx_history.iter().copied().collect(), color: Color::Blue },
+                Series { name: "↑ TX", values: history.network_tx_history.iter().copied().collect(), color: Color::Magenta },
+            ];
+            Self::draw_history_chart(
+                f, chart_area, "🔵 Network Throughput", &series, None,
+                format_rate, history, config.refresh_rate,
+            );
+        }
+
+        let filter = config.display.interface_filter();
+        let shown: Vec<&NetworkInterface> = interfaces.iter().filter(|n| filter.matches(&n.name)).collect();
+        let hidden = interfaces.len() - shown.len();
+
+        // 放不下的接口不绘制，在标题里注明数量
+        let block = Block::default().borders(Borders::ALL);
+        let inner = block.inner(area);
+        let fits = (inner.height / INTERFACE_HEIGHT) as usize;
+        let mut title = format!("🔵 Network Interfaces ({} shown", shown.len().min(fits));
+        if shown.len() > fits {
+            title.push_str(&format!(", {} more don't fit", shown.len() - fits));
+        }
+        if hidden > 0 {
+            title.push_str(&format!(", {} filtered", hidden));
+        }
+        title.push(')');
+        f.render_widget(block.title(title).style(Style::default().fg(Color::Cyan)), area);
+
+        if shown.is_empty() {
+            let empty = Paragraph::new("No interfaces match interface_include / interface_exclude")
+                .style(Style::default().fg(Color::Gray));
+            f.render_widget(empty, inner);
+            return;
+        }
+
+        let mut constraints = vec![Constraint::Length(INTERFACE_HEIGHT); shown.len().min(fits)];
+        constraints.push(Constraint::Min(0));
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(constraints)
+            .split(inner);
+
+        for (interface, row) in shown.iter().zip(rows.iter()) {
+            Self::draw_interface(f, *row, interface, history);
+        }
+    }
+
+    fn draw_interface(f: &mut TermFrame, area: Rect, interface: &NetworkInterface, history: &HistoryData) {
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Percentage(50),
+                Constraint::Percentage(25),
+                Constraint::Percentage(25),
+            ].as_ref())
+            .split(area);
+
+        let title = match &interface.mac_address {
+            Some(mac) => format!("{}  {}", interface.name, mac),
+            None => interface.name.clone(),
+        };
+        let addresses = if interface.ip_addresses.is_empty() {
+            "no address".to_string()
+        } else {
+            interface.ip_addresses.join(", ")
+        };
+        let error_style = if interface.errors_received + interface.errors_transmitted > 0 {
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        let text = vec![
+            Spans::from(addresses),
+            Spans::from(vec![
+                Span::raw(format!(
+                    "pkt/s ↓ {:.0} ↑ {:.0}   ",
+                    interface.rx_packets_per_sec, interface.tx_packets_per_sec
+                )),
+                Span::styled(
+                    format!("errors in {} out {}", interface.errors_received, interface.errors_transmitted),
+                    error_style,
+                ),
+            ]),
+            Spans::from(format!(
+                "total ↓ {} ↑ {}",
+                format_bytes(interface.bytes_received),
+                format_bytes(interface.bytes_transmitted)
+            )),
+        ];
+        let info = Paragraph::new(text)
+            .block(Block::default().title(title).borders(Borders::ALL))
+            .style(Style::default().fg(Color::Cyan));
+        f.render_widget(info, chunks[0]);
+
+        let (rx, tx): (Vec<u64>, Vec<u64>) = match history.interface(&interface.name) {
+            Some(h) => (
+                h.rx_history.iter().map(|v| *v as u64).collect(),
+                h.tx_history.iter().map(|v| *v as u64).collect(),
+            ),
+            None => (Vec::new(), Vec::new()),
+        };
+        let sparklines = [
+            (format!("↓ {}", format_rate(interface.rx_bytes_per_sec)), &rx, Color::Blue, chunks[1]),
+            (format!("↑ {}", format_rate(interface.tx_bytes_per_sec)), &tx, Color::Magenta, chunks[2]),
+        ];
+        for (title, values, color, area) in sparklines {
+            let sparkline = Sparkline::default()
+                .block(Block::default().title(title).borders(Borders::ALL))
+                .style(Style::default().fg(color))
+                .data(latest(values, area));
+            f.render_widget(sparkline, area);
+        }
+    }
+}
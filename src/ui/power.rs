@@ -0,0 +1,127 @@
+// 电源视图 - 红色主题：功耗历史、电池状态与健康度、功耗分项
+use super::charts::Series;
+use super::views::{provenance_tag, split_chart};
+use super::{TermFrame, UI};
+use crate::{config::Config, history::HistoryData, types::*};
+use tui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    widgets::{Block, Borders, Gauge, Paragraph},
+};
+
+const POWER_COLOR: Color = Color::Red;
+
+fn format_watts(value: f64) -> String {
+    format!("{:.1}W", value)
+}
+
+/// "3h 25m", or "n/a" when the platform gives no estimate
+fn format_time_remaining(seconds: Option<u32>) -> String {
+    match seconds {
+        Some(seconds) => format!("{}h {:02}m", seconds / 3600, (seconds % 3600) / 60),
+        None => "n/a".to_string(),
+    }
+}
+
+fn battery_state(battery: &BatteryInfo) -> &'static str {
+    if battery.is_charging {
+        "Charging"
+    } else if battery.is_plugged {
+        "On AC power"
+    } else {
+        "Discharging"
+    }
+}
+
+impl UI {
+    pub(super) fn draw_power_view(
+        f: &mut TermFrame,
+        area: Rect,
+        data: &SystemData,
+        history: &HistoryData,
+        config: &Config,
+    ) {
+        let (area, chart_area) = split_chart(area, area.height / 3, config);
+        if let Some(chart_area) = chart_area {
+            // 充电和放电都按功率大小画，方向见电池面板
+            let series = [
+                Series { name: "Package", values: history.power_history.iter().copied().collect(), color: POWER_COLOR },
+                Series { name: "Battery", values: history.battery_power_history.iter().map(|w| w.abs()).collect(), color: Color::Green },
+            ];
+            Self::draw_history_chart(
+                f, chart_area, "🔴 Power Draw", &series, None,
+                format_watts, history, config.refresh_rate,
+            );
+        }
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // 电量
+                Constraint::Length(3), // 健康度
+                Constraint::Min(0),    // 电池明细和功耗分项
+            ].as_ref())
+            .split(area);
+
+        let battery = &data.battery_info;
+        let charge_gauge = Gauge::default()
+            .block(Block::default().title(format!("🔋 Battery{}", provenance_tag(data, "battery_info"))).borders(Borders::ALL))
+            .gauge_style(Style::default().fg(if battery.percentage <= 20.0 && !battery.is_plugged { Color::Red } else { Color::Green }))
+            .percent(battery.percentage.clamp(0.0, 100.0) as u16)
+            .label(format!(
+                "{:.0}% | {} | {} remaining",
+                battery.percentage,
+                battery_state(battery),
+                format_time_remaining(battery.time_remaining)
+            ));
+        f.render_widget(charge_gauge, chunks[0]);
+
+        let health_gauge = Gauge::default()
+            .block(Block::default().title("🔋 Health").borders(Borders::ALL))
+            .gauge_style(Style::default().fg(if battery.health_percentage < 80.0 { Color::Yellow } else { Color::Green }))
+            .percent(battery.health_percentage.clamp(0.0, 100.0) as u16)
+            .label(format!(
+                "{:.1}% | {} / {} mAh | {} cycles",
+                battery.health_percentage,
+                battery.current_capacity,
+                battery.design_capacity,
+                battery.cycle_count
+            ));
+        f.render_widget(health_gauge, chunks[1]);
+
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+            .split(chunks[2]);
+
+        let battery_power = battery.voltage * battery.amperage;
+        let details = format!(
+            "Voltage:     {:.2}V\nCurrent:     {:+.2}A\nPower:       {:+.1}W ({})\nTemperature: {:.1}°C\nAdapter:     {}",
+            battery.voltage,
+            battery.amperage,
+            battery_power,
+            if battery_power >= 0.0 { "in" } else { "out" },
+            battery.temperature,
+            if battery.is_plugged { format!("{:.0}W", battery.power_adapter_wattage) } else { "not connected".to_string() },
+        );
+        let details_block = Paragraph::new(details)
+            .block(Block::default().title("🔋 Battery Details").borders(Borders::ALL))
+            .style(Style::default().fg(Color::Green));
+        f.render_widget(details_block, columns[0]);
+
+        let metrics = &data.cpu_info.power_metrics;
+        let breakdown = format!(
+            "Package:  {:6.2}W\nCPU:      {:6.2}W\nGPU:      {:6.2}W\nANE:      {:6.2}W\nPerf/Watt: {:.1} | Workload: {}",
+            metrics.package_w,
+            metrics.cpu_w,
+            metrics.gpu_w,
+            metrics.ane_w,
+            data.performance_metrics.performance_per_watt,
+            data.performance_metrics.workload_type,
+        );
+        let breakdown_block = Paragraph::new(breakdown)
+            .block(Block::default().title(format!("⚡ Power Consumption{}", provenance_tag(data, "cpu_info.power_metrics"))).borders(Borders::ALL))
+            .style(Style::default().fg(POWER_COLOR));
+        f.render_widget(breakdown_block, columns[1]);
+    }
+}
@@ -0,0 +1,158 @@
+// 选中进程的操作菜单：发送信号和调整优先级，执行前需要确认
+use super::{TermFrame, UI};
+use crate::process_control::{ProcessAction, MAX_NICE, MIN_NICE};
+use sysinfo::Pid;
+use termion::event::Key;
+use tui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+};
+
+/// Entries of the action menu; `None` asks for a nice value first
+const MENU: [(&str, Option<ProcessAction>); 5] = [
+    ("SIGTERM  terminate gracefully", Some(ProcessAction::Terminate)),
+    ("SIGKILL  kill immediately", Some(ProcessAction::Kill)),
+    ("SIGSTOP  pause", Some(ProcessAction::Stop)),
+    ("SIGCONT  resume", Some(ProcessAction::Continue)),
+    ("renice   change priority…", None),
+];
+
+/// The process an open dialog acts on
+#[derive(Debug, Clone)]
+pub struct ProcessTarget {
+    pub pid: Pid,
+    pub name: String,
+    /// Seconds since the Unix epoch; tells a reused PID apart from the selected process
+    pub start_time: u64,
+}
+
+#[derive(Debug, Clone)]
+pub enum ProcessDialog {
+    Menu { target: ProcessTarget, selected: usize },
+    NiceInput { target: ProcessTarget, value: String },
+    Confirm { target: ProcessTarget, action: ProcessAction },
+}
+
+/// What the table should do after the dialog handled a key
+pub enum DialogOutcome {
+    Open(ProcessDialog),
+    Closed,
+    /// Confirmed; the action should be applied to the target
+    Apply(ProcessTarget, ProcessAction),
+}
+
+impl ProcessDialog {
+    pub fn menu(target: ProcessTarget) -> Self {
+        ProcessDialog::Menu { target, selected: 0 }
+    }
+
+    pub fn handle_key(self, key: Key) -> DialogOutcome {
+        if key == Key::Esc {
+            return DialogOutcome::Closed;
+        }
+        match self {
+            ProcessDialog::Menu { target, selected } => match key {
+                Key::Up => DialogOutcome::Open(ProcessDialog::Menu {
+                    target,
+                    selected: selected.saturating_sub(1),
+                }),
+                Key::Down => DialogOutcome::Open(ProcessDialog::Menu {
+                    target,
+                    selected: (selected + 1).min(MENU.len() - 1),
+                }),
+                Key::Char('\n') => match MENU[selected].1 {
+                    Some(action) => DialogOutcome::Open(ProcessDialog::Confirm { target, action }),
+                    None => DialogOutcome::Open(ProcessDialog::NiceInput { target, value: String::new() }),
+                },
+                _ => DialogOutcome::Open(ProcessDialog::Menu { target, selected }),
+            },
+            ProcessDialog::NiceInput { target, mut value } => {
+                match key {
+                    Key::Char('\n') => {
+                        if let Ok(nice) = value.parse::<i32>() {
+                            if (MIN_NICE..=MAX_NICE).contains(&nice) {
+                                return DialogOutcome::Open(ProcessDialog::Confirm {
+                                    target,
+                                    action: ProcessAction::Renice(nice),
+                                });
+                            }
+                        }
+                    }
+                    Key::Backspace => {
+                        value.pop();
+                    }
+                    Key::Char(c @ ('0'..='9' | '-' | '+')) if value.len() < 3 => value.push(c),
+                    _ => {}
+                }
+                DialogOutcome::Open(ProcessDialog::NiceInput { target, value })
+            }
+            ProcessDialog::Confirm { target, action } => match key {
+                Key::Char('y') | Key::Char('Y') => DialogOutcome::Apply(target, action),
+                Key::Char('n') | Key::Char('N') => DialogOutcome::Closed,
+                _ => DialogOutcome::Open(ProcessDialog::Confirm { target, action }),
+            },
+        }
+    }
+}
+
+// 在区域中央取出固定大小的矩形
+fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
+}
+
+impl UI {
+    pub(super) fn draw_process_dialog(f: &mut TermFrame, area: Rect, dialog: &ProcessDialog) {
+        match dialog {
+            ProcessDialog::Menu { target, selected } => {
+                let popup = centered_rect(44, MENU.len() as u16 + 2, area);
+                let items: Vec<ListItem> = MENU.iter().map(|(label, _)| ListItem::new(*label)).collect();
+                let mut state = ListState::default();
+                state.select(Some(*selected));
+
+                let list = List::new(items)
+                    .block(Block::default()
+                        .title(format!("{} ({})", target.name, target.pid))
+                        .borders(Borders::ALL))
+                    .style(Style::default().fg(Color::White))
+                    .highlight_style(Style::default().bg(Color::Magenta).fg(Color::Black))
+                    .highlight_symbol("▶ ");
+                f.render_widget(Clear, popup);
+                f.render_stateful_widget(list, popup, &mut state);
+            }
+            ProcessDialog::NiceInput { target, value } => {
+                let popup = centered_rect(50, 5, area);
+                let text = vec![
+                    Spans::from(format!("New nice value for {} ({}):", target.name, target.pid)),
+                    Spans::from(Span::styled(format!("{}█", value), Style::default().add_modifier(Modifier::BOLD))),
+                    Spans::from(format!("{} (highest priority) … {} (lowest)", MIN_NICE, MAX_NICE)),
+                ];
+                let input = Paragraph::new(text)
+                    .block(Block::default().title("Renice").borders(Borders::ALL))
+                    .style(Style::default().fg(Color::White));
+                f.render_widget(Clear, popup);
+                f.render_widget(input, popup);
+            }
+            ProcessDialog::Confirm { target, action } => {
+                let popup = centered_rect(50, 4, area);
+                let text = vec![
+                    Spans::from(format!("{} {} ({})?", action.label(), target.name, target.pid)),
+                    Spans::from(Span::styled("y: confirm   n/Esc: cancel", Style::default().fg(Color::Gray))),
+                ];
+                let confirm = Paragraph::new(text)
+                    .block(Block::default().title("Confirm").borders(Borders::ALL))
+                    .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+                f.render_widget(Clear, popup);
+                f.render_widget(confirm, popup);
+            }
+        }
+    }
+}
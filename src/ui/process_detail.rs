@@ -0,0 +1,131 @@
+// 选中进程的详情面板：基本信息和 CPU/内存/磁盘迷你图
+use super::charts::{format_rate, latest};
+use super::views::format_bytes;
+use super::{TermFrame, UI};
+use crate::history::ProcessHistory;
+use crate::types::ProcessInfo;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    widgets::{Block, Borders, Paragraph, Sparkline, Wrap},
+};
+
+/// "3d 4h", "2h 15m", "42s"
+fn format_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    if secs >= 86400 {
+        format!("{}d {}h", secs / 86400, (secs % 86400) / 3600)
+    } else if secs >= 3600 {
+        format!("{}h {}m", secs / 3600, (secs % 3600) / 60)
+    } else if secs >= 60 {
+        format!("{}m {}s", secs / 60, secs % 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+impl UI {
+    pub(super) fn draw_process_detail(
+        f: &mut TermFrame,
+        area: Rect,
+        process: Option<&ProcessInfo>,
+        history: Option<&ProcessHistory>,
+        pinned: bool,
+        now: SystemTime,
+        refresh_rate: u64,
+    ) {
+        let process = match process {
+            Some(process) => process,
+            None => {
+                let empty = Paragraph::new("No process selected")
+                    .block(Block::default().title("Details").borders(Borders::ALL))
+                    .style(Style::default().fg(Color::Gray));
+                f.render_widget(empty, area);
+                return;
+            }
+        };
+
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(40), Constraint::Percentage(60)].as_ref())
+            .split(area);
+
+        let started = UNIX_EPOCH + Duration::from_secs(process.start_time);
+        let age = now.duration_since(started).map(format_age).unwrap_or_else(|_| "-".to_string());
+        let tracking = match (history, pinned) {
+            (_, true) => "pinned",
+            (Some(_), false) => "top process",
+            (None, false) => "not tracked (p to pin)",
+        };
+        let info = format!(
+            "PID {}  parent {}\nUser: {}  State: {}\nThreads: {}  Running for {}\nHistory: {}\n{}",
+            process.pid,
+            process.parent_pid.map_or_else(|| "-".to_string(), |pid| pid.to_string()),
+            process.user.as_deref().unwrap_or("-"),
+            process.status,
+            process.threads.map_or_else(|| "-".to_string(), |threads| threads.to_string()),
+            age,
+            tracking,
+            if process.command.is_empty() { process.name.clone() } else { process.command.join(" ") }
+        );
+        let info_block = Paragraph::new(info)
+            .block(Block::default().title(format!("🟣 {}", process.name)).borders(Borders::ALL))
+            .style(Style::default().fg(Color::Magenta))
+            .wrap(Wrap { trim: false });
+        f.render_widget(info_block, chunks[0]);
+
+        let graphs = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Ratio(1, 3); 3].as_ref())
+            .split(chunks[1]);
+
+        let history = match history {
+            Some(history) => history,
+            None => {
+                let untracked = Paragraph::new("Only the busiest processes and pinned ones keep history.\nPress p to pin this process.")
+                    .block(Block::default().title("History").borders(Borders::ALL))
+                    .style(Style::default().fg(Color::Gray));
+                f.render_widget(untracked, chunks[1]);
+                return;
+            }
+        };
+
+        let interval = refresh_rate.max(1) as f64;
+        let cpu: Vec<u64> = history.cpu_history.iter().map(|v| *v as u64).collect();
+        let memory: Vec<u64> = history.memory_history.iter().copied().collect();
+        let disk: Vec<u64> = history
+            .disk_read_history
+            .iter()
+            .zip(history.disk_write_history.iter())
+            .map(|(read, write)| read + write)
+            .collect();
+        let peak_cpu = history.cpu_history.iter().copied().fold(0.0, f32::max);
+
+        let cpu_title = format!("CPU {:.1}% (peak {:.1}%)", process.cpu_usage, peak_cpu);
+        let memory_title = format!("RSS {}", format_bytes(process.memory_usage));
+        let disk_title = format!(
+            "Disk R {} W {}",
+            format_rate(process.disk_read_bytes as f64 / interval),
+            format_rate(process.disk_write_bytes as f64 / interval)
+        );
+
+        // CPU 可能超过 100%（多线程），按实际峰值缩放
+        let cpu_max = (peak_cpu.ceil() as u64).max(100);
+        let sparklines = [
+            (cpu_title, &cpu, Some(cpu_max), Color::Cyan, graphs[0]),
+            (memory_title, &memory, None, Color::Green, graphs[1]),
+            (disk_title, &disk, None, Color::Yellow, graphs[2]),
+        ];
+        for (title, values, max, color, area) in sparklines {
+            let mut sparkline = Sparkline::default()
+                .block(Block::default().title(title).borders(Borders::ALL))
+                .style(Style::default().fg(color))
+                .data(latest(values, area));
+            if let Some(max) = max {
+                sparkline = sparkline.max(max);
+            }
+            f.render_widget(sparkline, area);
+        }
+    }
+}
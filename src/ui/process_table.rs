@@ -0,0 +1,493 @@
+// 进程表：排序、过滤、树形视图和跨刷新保持的选中项
+use super::process_actions::{DialogOutcome, ProcessDialog, ProcessTarget};
+use super::process_tree::{build_tree, with_ancestors, ProcessRow};
+use super::views::format_bytes;
+use super::{TermFrame, UI};
+use crate::history::HistoryData;
+use crate::process_control::{current_start_time, ProcessAction};
+use crate::types::{ProcessInfo, SystemData};
+use regex::{Regex, RegexBuilder};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use sysinfo::Pid;
+use termion::event::Key;
+use tui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState},
+};
+
+/// Column the process table is sorted by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessSortKey {
+    Pid,
+    User,
+    Name,
+    Cpu,
+    Memory,
+    DiskRead,
+    DiskWrite,
+}
+
+impl ProcessSortKey {
+    fn from_key(key: char) -> Option<Self> {
+        match key {
+            'P' => Some(Self::Pid),
+            'U' => Some(Self::User),
+            'N' => Some(Self::Name),
+            'C' => Some(Self::Cpu),
+            'M' => Some(Self::Memory),
+            'R' => Some(Self::DiskRead),
+            'W' => Some(Self::DiskWrite),
+            _ => None,
+        }
+    }
+
+    /// Usage columns compare the row's usage, which is the subtree total in tree mode
+    fn compare(self, a: &ProcessRow, b: &ProcessRow) -> Ordering {
+        match self {
+            Self::Pid => a.process.pid.cmp(&b.process.pid),
+            Self::User => a.process.user.cmp(&b.process.user),
+            Self::Name => a.process.name.to_lowercase().cmp(&b.process.name.to_lowercase()),
+            Self::Cpu => a.usage.cpu_usage.partial_cmp(&b.usage.cpu_usage).unwrap_or(Ordering::Equal),
+            Self::Memory => a.usage.memory_usage.cmp(&b.usage.memory_usage),
+            Self::DiskRead => a.usage.disk_read_bytes.cmp(&b.usage.disk_read_bytes),
+            Self::DiskWrite => a.usage.disk_write_bytes.cmp(&b.usage.disk_write_bytes),
+        }
+    }
+
+    /// Numeric columns start largest-first, PID, user and name smallest-first
+    fn default_descending(self) -> bool {
+        !matches!(self, Self::Pid | Self::User | Self::Name)
+    }
+}
+
+const COLUMNS: [(&str, Option<ProcessSortKey>); 10] = [
+    ("PID", Some(ProcessSortKey::Pid)),
+    ("USER", Some(ProcessSortKey::User)),
+    ("STATE", None),
+    ("THR", None),
+    ("CPU%", Some(ProcessSortKey::Cpu)),
+    ("MEM", Some(ProcessSortKey::Memory)),
+    ("READ", Some(ProcessSortKey::DiskRead)),
+    ("WRITE", Some(ProcessSortKey::DiskWrite)),
+    ("NAME", Some(ProcessSortKey::Name)),
+    ("COMMAND", None),
+];
+
+/// Sort order, filter and selection of the Processes view, kept across refreshes
+pub struct ProcessTableState {
+    sort_key: ProcessSortKey,
+    descending: bool,
+    filter: String,
+    filter_regex: Option<Regex>,
+    editing_filter: bool,
+    tree_mode: bool,
+    /// Tree nodes whose children are hidden
+    collapsed: HashSet<Pid>,
+    /// Processes whose history is kept even when they are not among the busiest
+    pinned: HashSet<Pid>,
+    show_detail: bool,
+    selected_pid: Option<Pid>,
+    selected_name: String,
+    selected_start_time: u64,
+    /// Start time of every process in the last drawn sample
+    start_times: HashMap<Pid, u64>,
+    /// Why signals and renice are unavailable, e.g. while replaying a recording
+    actions_disabled: Option<String>,
+    dialog: Option<ProcessDialog>,
+    /// Outcome of the last process action: Ok(message) or Err(message)
+    status: Option<Result<String, String>>,
+    /// PIDs in display order as of the last draw, used to move the selection
+    visible_pids: Vec<Pid>,
+    page_size: usize,
+    table_state: TableState,
+}
+
+impl Default for ProcessTableState {
+    fn default() -> Self {
+        Self {
+            sort_key: ProcessSortKey::Cpu,
+            descending: true,
+            filter: String::new(),
+            filter_regex: None,
+            editing_filter: false,
+            tree_mode: false,
+            collapsed: HashSet::new(),
+            pinned: HashSet::new(),
+            show_detail: false,
+            selected_pid: None,
+            selected_name: String::new(),
+            selected_start_time: 0,
+            start_times: HashMap::new(),
+            actions_disabled: None,
+            dialog: None,
+            status: None,
+            visible_pids: Vec::new(),
+            page_size: 10,
+            table_state: TableState::default(),
+        }
+    }
+}
+
+impl ProcessTableState {
+    pub fn is_editing_filter(&self) -> bool {
+        self.editing_filter
+    }
+
+    /// Whether the filter or an action dialog is taking all key presses
+    pub fn captures_keys(&self) -> bool {
+        self.editing_filter || self.dialog.is_some()
+    }
+
+    pub fn selected_pid(&self) -> Option<Pid> {
+        self.selected_pid
+    }
+
+    pub fn pinned(&self) -> &HashSet<Pid> {
+        &self.pinned
+    }
+
+    /// Turn the action menu off, showing `reason` instead
+    pub fn disable_actions(&mut self, reason: &str) {
+        self.actions_disabled = Some(reason.to_string());
+        self.dialog = None;
+    }
+
+    /// Handle a key for the process table, returning whether it was used
+    pub fn handle_key(&mut self, key: Key) -> bool {
+        if let Some(dialog) = self.dialog.take() {
+            match dialog.handle_key(key) {
+                DialogOutcome::Open(dialog) => self.dialog = Some(dialog),
+                DialogOutcome::Closed => {}
+                DialogOutcome::Apply(target, action) => self.apply_action(target, action),
+            }
+            return true;
+        }
+
+        if self.editing_filter {
+            match key {
+                Key::Char('\n') => self.editing_filter = false,
+                Key::Esc => {
+                    self.editing_filter = false;
+                    self.set_filter(String::new());
+                }
+                Key::Backspace => {
+                    let mut filter = self.filter.clone();
+                    filter.pop();
+                    self.set_filter(filter);
+                }
+                Key::Char(c) if !c.is_control() => {
+                    let mut filter = self.filter.clone();
+                    filter.push(c);
+                    self.set_filter(filter);
+                }
+                Key::Up | Key::Down | Key::PageUp | Key::PageDown => return self.move_selection(key),
+                _ => return false,
+            }
+            return true;
+        }
+
+        match key {
+            Key::Char('/') => self.editing_filter = true,
+            Key::Char('t') => self.tree_mode = !self.tree_mode,
+            Key::Char('d') => self.show_detail = !self.show_detail,
+            Key::Char('p') => match self.selected_pid {
+                Some(pid) => {
+                    if !self.pinned.remove(&pid) {
+                        self.pinned.insert(pid);
+                    }
+                }
+                None => return false,
+            },
+            Key::Char(' ') if self.tree_mode => match self.selected_pid {
+                Some(pid) => {
+                    if !self.collapsed.remove(&pid) {
+                        self.collapsed.insert(pid);
+                    }
+                }
+                None => return false,
+            },
+            Key::Char('\n') | Key::Char('k') => match self.selected_pid {
+                Some(_) if self.actions_disabled.is_some() => {
+                    let reason = self.actions_disabled.as_deref().unwrap_or_default();
+                    self.status = Some(Err(format!("Process actions are disabled: {}", reason)));
+                }
+                Some(pid) => {
+                    self.status = None;
+                    self.dialog = Some(ProcessDialog::menu(ProcessTarget {
+                        pid,
+                        name: self.selected_name.clone(),
+                        start_time: self.selected_start_time,
+                    }));
+                }
+                None => return false,
+            },
+            Key::Esc if self.status.is_some() => self.status = None,
+            Key::Esc if !self.filter.is_empty() => self.set_filter(String::new()),
+            Key::Char(c) => match ProcessSortKey::from_key(c) {
+                Some(sort_key) => self.sort_by(sort_key),
+                None => return false,
+            },
+            _ => return self.move_selection(key),
+        }
+        true
+    }
+
+    fn apply_action(&mut self, target: ProcessTarget, action: ProcessAction) {
+        // 对话框打开期间 PID 可能已被新进程复用，执行前重新读取启动时间
+        let refusal = match current_start_time(target.pid) {
+            None => Some("the process has exited"),
+            Some(start_time) if start_time != target.start_time => Some("the PID now belongs to another process"),
+            Some(_) => None,
+        };
+        self.status = Some(match refusal {
+            Some(reason) => Err(format!("{} → {} ({}) refused: {}", action.label(), target.name, target.pid, reason)),
+            None => match action.apply(target.pid) {
+                Ok(()) => Ok(format!("{} → {} ({})", action.label(), target.name, target.pid)),
+                Err(e) => Err(format!("{} → {} ({}) failed: {}", action.label(), target.name, target.pid, e)),
+            },
+        });
+    }
+
+    /// Pressing the active column's key again reverses the order
+    pub fn sort_by(&mut self, sort_key: ProcessSortKey) {
+        if self.sort_key == sort_key {
+            self.descending = !self.descending;
+        } else {
+            self.sort_key = sort_key;
+            self.descending = sort_key.default_descending();
+        }
+    }
+
+    // 能编译成正则就按正则匹配，否则按子串匹配，均不区分大小写
+    fn set_filter(&mut self, filter: String) {
+        self.filter_regex = if filter.is_empty() {
+            None
+        } else {
+            RegexBuilder::new(&filter).case_insensitive(true).build().ok()
+        };
+        self.filter = filter;
+    }
+
+    fn matches_filter(&self, process: &ProcessInfo) -> bool {
+        if self.filter.is_empty() {
+            return true;
+        }
+        match &self.filter_regex {
+            Some(regex) => regex.is_match(&process.name),
+            None => process.name.to_lowercase().contains(&self.filter.to_lowercase()),
+        }
+    }
+
+    fn move_selection(&mut self, key: Key) -> bool {
+        if self.visible_pids.is_empty() {
+            return false;
+        }
+        let last = self.visible_pids.len() - 1;
+        let current = self
+            .selected_pid
+            .and_then(|pid| self.visible_pids.iter().position(|p| *p == pid));
+        let index = match (key, current) {
+            (Key::Up, Some(i)) => i.saturating_sub(1),
+            (Key::Down, Some(i)) => (i + 1).min(last),
+            (Key::PageUp, Some(i)) => i.saturating_sub(self.page_size),
+            (Key::PageDown, Some(i)) => (i + self.page_size).min(last),
+            (Key::Home, _) => 0,
+            (Key::End, _) => last,
+            (Key::Up | Key::Down | Key::PageUp | Key::PageDown, None) => 0,
+            _ => return false,
+        };
+        self.selected_pid = Some(self.visible_pids[index]);
+        true
+    }
+
+    pub fn is_tree_mode(&self) -> bool {
+        self.tree_mode
+    }
+
+    fn compare_rows(&self, a: &ProcessRow, b: &ProcessRow) -> Ordering {
+        let order = self.sort_key.compare(a, b);
+        let order = if self.descending { order.reverse() } else { order };
+        // PID 作为次序键，避免相同值的行在刷新间跳动
+        order.then_with(|| a.process.pid.cmp(&b.process.pid))
+    }
+
+    /// Rows to display: filtered and sorted, or in tree mode the matches with
+    /// their ancestors, siblings sorted by subtree totals
+    pub fn rows<'a>(&self, processes: &'a [ProcessInfo]) -> Vec<ProcessRow<'a>> {
+        if self.tree_mode {
+            let included = with_ancestors(processes, |process| self.matches_filter(process));
+            return build_tree(&included, &self.collapsed, |a, b| self.compare_rows(a, b));
+        }
+
+        let mut rows: Vec<ProcessRow> = processes
+            .iter()
+            .filter(|process| self.matches_filter(process))
+            .map(ProcessRow::flat)
+            .collect();
+        rows.sort_by(|a, b| self.compare_rows(a, b));
+        rows
+    }
+
+    /// Keep the selection on the same PID; if it disappeared, stay at the same row
+    fn sync_selection(&mut self, rows: &[ProcessRow]) {
+        let previous_index = self.table_state.selected();
+        self.visible_pids = rows.iter().map(|row| row.process.pid).collect();
+        // 已退出进程的折叠状态不再需要
+        let visible: HashSet<Pid> = self.visible_pids.iter().copied().collect();
+        self.collapsed.retain(|pid| visible.contains(pid));
+
+        let index = match self.selected_pid.and_then(|pid| self.visible_pids.iter().position(|p| *p == pid)) {
+            Some(index) => Some(index),
+            None if rows.is_empty() => None,
+            None => Some(previous_index.unwrap_or(0).min(rows.len() - 1)),
+        };
+        self.selected_pid = index.map(|i| self.visible_pids[i]);
+        self.selected_name = index.map(|i| rows[i].process.name.clone()).unwrap_or_default();
+        self.selected_start_time = index.map(|i| rows[i].process.start_time).unwrap_or_default();
+        self.table_state.select(index);
+    }
+}
+
+impl UI {
+    // 进程视图 - 紫色主题
+    pub(super) fn draw_processes_view(
+        f: &mut TermFrame,
+        area: Rect,
+        data: &SystemData,
+        history: &HistoryData,
+        refresh_rate: u64,
+        state: &mut ProcessTableState,
+    ) {
+        let detail_height = if state.show_detail { 12 } else { 0 };
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(0),
+                Constraint::Length(1),
+                Constraint::Length(detail_height),
+            ].as_ref())
+            .split(area);
+
+        state.start_times = data.process_info.iter().map(|p| (p.pid, p.start_time)).collect();
+        state.pinned.retain(|pid| state.start_times.contains_key(pid));
+
+        let rows = state.rows(&data.process_info);
+        state.sync_selection(&rows);
+        // 边框和表头各占行
+        state.page_size = chunks[0].height.saturating_sub(4).max(1) as usize;
+
+        let header = Row::new(COLUMNS.iter().map(|(title, key)| {
+            let title = if *key == Some(state.sort_key) {
+                format!("{}{}", title, if state.descending { "▼" } else { "▲" })
+            } else {
+                title.to_string()
+            };
+            Cell::from(title)
+        }))
+        .style(Style::default().fg(Color::White).add_modifier(Modifier::BOLD))
+        .bottom_margin(1);
+
+        let table_rows: Vec<Row> = rows.iter().map(|row| {
+            let process = row.process;
+            // 树形模式下，有子进程的节点显示展开/折叠标记
+            let marker = match (row.has_children, row.collapsed) {
+                (true, true) => "▸ ",
+                (true, false) => "▾ ",
+                (false, _) => "",
+            };
+            let pin = if state.pinned.contains(&process.pid) { "📌" } else { "" };
+            let mut name = format!("{}{}{}{}", row.tree_prefix, marker, pin, process.name);
+            if row.hidden_descendants > 0 {
+                name.push_str(&format!(" [+{}]", row.hidden_descendants));
+            }
+            let command = if process.command.is_empty() {
+                format!("[{}]", process.name)
+            } else {
+                process.command.join(" ")
+            };
+            Row::new(vec![
+                Cell::from(process.pid.to_string()),
+                Cell::from(process.user.clone().unwrap_or_else(|| "-".to_string())),
+                Cell::from(process.status.clone()),
+                Cell::from(process.threads.map_or_else(|| "-".to_string(), |threads| threads.to_string())),
+                Cell::from(format!("{:.1}", row.usage.cpu_usage)),
+                Cell::from(format_bytes(row.usage.memory_usage)),
+                Cell::from(format_bytes(row.usage.disk_read_bytes)),
+                Cell::from(format_bytes(row.usage.disk_write_bytes)),
+                Cell::from(name),
+                Cell::from(command),
+            ])
+        }).collect();
+
+        let mode = if state.tree_mode { "tree, subtree totals" } else { "list" };
+        let title = if state.filter.is_empty() {
+            format!("🟣 Processes ({}) [{}]", data.process_info.len(), mode)
+        } else {
+            format!("🟣 Processes ({}/{}) [{}] filter: {}", rows.len(), data.process_info.len(), mode, state.filter)
+        };
+
+        let widths = [
+            Constraint::Length(7),
+            Constraint::Length(10),
+            Constraint::Length(9),
+            Constraint::Length(4),
+            Constraint::Length(6),
+            Constraint::Length(8),
+            Constraint::Length(8),
+            Constraint::Length(8),
+            Constraint::Min(24),
+            Constraint::Min(20),
+        ];
+        let table = Table::new(table_rows)
+            .header(header)
+            .block(Block::default().title(title).borders(Borders::ALL))
+            .style(Style::default().fg(Color::Magenta))
+            .highlight_style(Style::default().bg(Color::Magenta).fg(Color::Black))
+            .highlight_symbol("▶ ")
+            .widths(&widths);
+        f.render_stateful_widget(table, chunks[0], &mut state.table_state);
+
+        let footer = match &state.status {
+            _ if state.editing_filter => Paragraph::new(format!(
+                "Filter (name or regex): {}█   Enter: keep  Esc: clear",
+                state.filter
+            ))
+            .style(Style::default().fg(Color::Gray)),
+            Some(Ok(message)) => Paragraph::new(format!("✓ {}", message)).style(Style::default().fg(Color::Green)),
+            Some(Err(message)) => Paragraph::new(format!("✗ {}", message)).style(Style::default().fg(Color::Red)),
+            None => {
+                let actions = match &state.actions_disabled {
+                    Some(reason) => format!("actions off ({})", reason),
+                    None => "Enter/k: actions".to_string(),
+                };
+                Paragraph::new(format!(
+                    "↑↓: select  {}  P/U/N/C/M/R/W: sort  /: filter  t: tree  Space: fold  d: details  p: pin",
+                    actions
+                ))
+                .style(Style::default().fg(Color::Gray))
+            }
+        };
+        f.render_widget(footer, chunks[1]);
+
+        if state.show_detail {
+            let selected = state
+                .selected_pid
+                .and_then(|pid| data.process_info.iter().find(|p| p.pid == pid));
+            Self::draw_process_detail(
+                f,
+                chunks[2],
+                selected,
+                selected.and_then(|p| history.process(p.pid)),
+                selected.is_some_and(|p| state.pinned.contains(&p.pid)),
+                data.timestamp,
+                refresh_rate,
+            );
+        }
+
+        if let Some(dialog) = &state.dialog {
+            Self::draw_process_dialog(f, chunks[0], dialog);
+        }
+    }
+}
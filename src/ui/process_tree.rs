@@ -0,0 +1,215 @@
+// 进程树：按父进程分组，并汇总每棵子树的资源占用
+use crate::types::ProcessInfo;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use sysinfo::Pid;
+
+/// CPU, memory and disk figures of a process, or of a whole subtree
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ResourceUsage {
+    pub cpu_usage: f32,
+    pub memory_usage: u64,
+    pub disk_read_bytes: u64,
+    pub disk_write_bytes: u64,
+}
+
+impl ResourceUsage {
+    pub fn of(process: &ProcessInfo) -> Self {
+        Self {
+            cpu_usage: process.cpu_usage,
+            memory_usage: process.memory_usage,
+            disk_read_bytes: process.disk_read_bytes,
+            disk_write_bytes: process.disk_write_bytes,
+        }
+    }
+
+    fn add(&mut self, other: &ResourceUsage) {
+        self.cpu_usage += other.cpu_usage;
+        self.memory_usage += other.memory_usage;
+        self.disk_read_bytes += other.disk_read_bytes;
+        self.disk_write_bytes += other.disk_write_bytes;
+    }
+}
+
+/// A line of the process table, in flat or tree layout
+#[derive(Debug, Clone)]
+pub struct ProcessRow<'a> {
+    pub process: &'a ProcessInfo,
+    /// The process's own usage in flat mode, its whole subtree's in tree mode
+    pub usage: ResourceUsage,
+    /// Box-drawing prefix placing the row in the tree (empty in flat mode)
+    pub tree_prefix: String,
+    pub has_children: bool,
+    pub collapsed: bool,
+    /// Processes hidden below this row because it is collapsed
+    pub hidden_descendants: usize,
+}
+
+impl<'a> ProcessRow<'a> {
+    pub fn flat(process: &'a ProcessInfo) -> Self {
+        Self {
+            process,
+            usage: ResourceUsage::of(process),
+            tree_prefix: String::new(),
+            has_children: false,
+            collapsed: false,
+            hidden_descendants: 0,
+        }
+    }
+}
+
+struct Forest<'a> {
+    by_pid: HashMap<Pid, &'a ProcessInfo>,
+    children: HashMap<Pid, Vec<Pid>>,
+    roots: Vec<Pid>,
+    usage: HashMap<Pid, ResourceUsage>,
+    sizes: HashMap<Pid, usize>,
+}
+
+impl<'a> Forest<'a> {
+    fn new(processes: &[&'a ProcessInfo]) -> Self {
+        let by_pid: HashMap<Pid, &ProcessInfo> = processes.iter().map(|p| (p.pid, *p)).collect();
+        let mut children: HashMap<Pid, Vec<Pid>> = HashMap::new();
+        let mut roots = Vec::new();
+
+        for process in processes {
+            match process.parent_pid.filter(|parent| by_pid.contains_key(parent)) {
+                Some(parent) => children.entry(parent).or_default().push(process.pid),
+                None => roots.push(process.pid),
+            }
+        }
+
+        let mut forest = Self {
+            by_pid,
+            children,
+            roots,
+            usage: HashMap::new(),
+            sizes: HashMap::new(),
+        };
+
+        // 父进程链若成环（PID 复用时可能出现），环上的进程都不会出现在根下，将其提升为根
+        let mut reachable = HashSet::new();
+        for root in forest.roots.clone() {
+            forest.total(root, &mut reachable);
+        }
+        let mut orphans: Vec<Pid> = processes
+            .iter()
+            .map(|p| p.pid)
+            .filter(|pid| !reachable.contains(pid))
+            .collect();
+        orphans.sort();
+        for pid in orphans {
+            if !reachable.contains(&pid) {
+                forest.roots.push(pid);
+                forest.total(pid, &mut reachable);
+            }
+        }
+        forest
+    }
+
+    // 后序遍历，计算子树汇总和子树大小
+    fn total(&mut self, pid: Pid, visited: &mut HashSet<Pid>) -> (ResourceUsage, usize) {
+        if !visited.insert(pid) {
+            return (ResourceUsage::default(), 0);
+        }
+        let mut usage = ResourceUsage::of(self.by_pid[&pid]);
+        let mut size = 1;
+        for child in self.children.get(&pid).cloned().unwrap_or_default() {
+            let (child_usage, child_size) = self.total(child, visited);
+            usage.add(&child_usage);
+            size += child_size;
+        }
+        self.usage.insert(pid, usage);
+        self.sizes.insert(pid, size);
+        (usage, size)
+    }
+
+    fn row(&self, pid: Pid, tree_prefix: String, collapsed: &HashSet<Pid>) -> ProcessRow<'a> {
+        let has_children = self.children.get(&pid).is_some_and(|c| !c.is_empty());
+        let is_collapsed = has_children && collapsed.contains(&pid);
+        ProcessRow {
+            process: self.by_pid[&pid],
+            usage: self.usage.get(&pid).copied().unwrap_or_default(),
+            tree_prefix,
+            has_children,
+            collapsed: is_collapsed,
+            hidden_descendants: if is_collapsed { self.sizes[&pid] - 1 } else { 0 },
+        }
+    }
+}
+
+/// Lay `processes` out as a tree with subtree totals. Siblings are ordered by
+/// `compare`; children of PIDs in `collapsed` are left out.
+pub fn build_tree<'a>(
+    processes: &[&'a ProcessInfo],
+    collapsed: &HashSet<Pid>,
+    compare: impl Fn(&ProcessRow, &ProcessRow) -> Ordering,
+) -> Vec<ProcessRow<'a>> {
+    let forest = Forest::new(processes);
+    let mut rows = Vec::with_capacity(processes.len());
+    let mut emitted = HashSet::new();
+
+    let mut roots: Vec<ProcessRow> = forest
+        .roots
+        .iter()
+        .map(|pid| forest.row(*pid, String::new(), collapsed))
+        .collect();
+    roots.sort_by(&compare);
+
+    // 显式栈做深度优先遍历；`guides` 记录每层祖先是否还有后续兄弟
+    let mut stack: Vec<(ProcessRow, Vec<bool>)> = roots.into_iter().rev().map(|row| (row, Vec::new())).collect();
+    while let Some((row, guides)) = stack.pop() {
+        let pid = row.process.pid;
+        if !emitted.insert(pid) {
+            continue;
+        }
+        let expand = row.has_children && !row.collapsed;
+        rows.push(row);
+        if !expand {
+            continue;
+        }
+
+        let mut children: Vec<ProcessRow> = forest.children[&pid]
+            .iter()
+            .map(|child| forest.row(*child, String::new(), collapsed))
+            .collect();
+        children.sort_by(&compare);
+
+        let count = children.len();
+        for (i, mut child) in children.into_iter().enumerate().rev() {
+            let is_last = i + 1 == count;
+            let mut prefix: String = guides
+                .iter()
+                .map(|more| if *more { "│ " } else { "  " })
+                .collect();
+            prefix.push_str(if is_last { "└─" } else { "├─" });
+            child.tree_prefix = prefix;
+
+            let mut child_guides = guides.clone();
+            child_guides.push(!is_last);
+            stack.push((child, child_guides));
+        }
+    }
+    rows
+}
+
+/// Processes in `all` that match, plus their ancestors so matches keep their place in the tree
+pub fn with_ancestors(
+    all: &[ProcessInfo],
+    matches: impl Fn(&ProcessInfo) -> bool,
+) -> Vec<&ProcessInfo> {
+    let by_pid: HashMap<Pid, &ProcessInfo> = all.iter().map(|p| (p.pid, p)).collect();
+    let mut included: HashSet<Pid> = HashSet::new();
+
+    for process in all.iter().filter(|p| matches(p)) {
+        let mut current = Some(process);
+        while let Some(p) = current {
+            if !included.insert(p.pid) {
+                break;
+            }
+            current = p.parent_pid.and_then(|parent| by_pid.get(&parent).copied());
+        }
+    }
+
+    all.iter().filter(|p| included.contains(&p.pid)).collect()
+}
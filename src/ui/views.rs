@@ -0,0 +1,428 @@
+// 各个标签页的全屏视图
+use super::charts::{trend_arrow, Series};
+use super::{TermFrame, UI};
+use crate::{
+    config::Config,
+    history::HistoryData,
+    notification::{AlertLevel, AlertPhase, AlertState},
+    types::*,
+};
+use tui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    widgets::{Block, Borders, Gauge, Paragraph},
+};
+
+const GB: f64 = 1024.0 * 1024.0 * 1024.0;
+const MB: f64 = 1024.0 * 1024.0;
+
+/// Appended to a block title when its values aren't a recent measurement:
+/// " [estimated]", " [cached 45s]", " [stale 12s]" or " [no data]"
+pub(super) fn provenance_tag(data: &SystemData, group: &str) -> String {
+    let Some(provenance) = data.provenance(group) else {
+        return " [no data]".to_string();
+    };
+    let age = provenance.age(data.timestamp).as_secs();
+    match (provenance.origin, provenance.is_stale(data.timestamp)) {
+        (DataOrigin::Measured, false) => String::new(),
+        (DataOrigin::Measured, true) => format!(" [stale {}s]", age),
+        (DataOrigin::Estimated, _) => " [estimated]".to_string(),
+        (DataOrigin::Cached, _) => format!(" [cached {}s]", age),
+    }
+}
+
+/// Human readable byte count (B, KB, MB, GB)
+pub(super) fn format_bytes(bytes: u64) -> String {
+    let bytes = bytes as f64;
+    if bytes >= GB {
+        format!("{:.1}GB", bytes / GB)
+    } else if bytes >= MB {
+        format!("{:.1}MB", bytes / MB)
+    } else if bytes >= 1024.0 {
+        format!("{:.1}KB", bytes / 1024.0)
+    } else {
+        format!("{}B", bytes)
+    }
+}
+
+fn percent_of(part: u64, total: u64) -> u16 {
+    if total == 0 {
+        0
+    } else {
+        ((part as f64 / total as f64) * 100.0).min(100.0) as u16
+    }
+}
+
+pub(super) fn bar(percent: f32, width: usize) -> String {
+    let filled = ((percent.clamp(0.0, 100.0) / 100.0) * width as f32) as usize;
+    "█".repeat(filled) + &"░".repeat(width - filled)
+}
+
+fn format_percent(value: f64) -> String {
+    format!("{:.0}%", value)
+}
+
+fn format_celsius(value: f64) -> String {
+    format!("{:.0}°C", value)
+}
+
+/// Splits off the bottom `height` rows for a history chart when history is enabled
+pub(super) fn split_chart(area: Rect, height: u16, config: &Config) -> (Rect, Option<Rect>) {
+    if !config.display.show_history {
+        return (area, None);
+    }
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(height)].as_ref())
+        .split(area);
+    (chunks[0], Some(chunks[1]))
+}
+
+/// Compact core list: "0-3,8,10-11"
+pub(super) fn format_core_list(cores: &[usize]) -> String {
+    let mut ranges: Vec<String> = Vec::new();
+    let mut i = 0;
+    while i < cores.len() {
+        let start = cores[i];
+        while i + 1 < cores.len() && cores[i + 1] == cores[i] + 1 {
+            i += 1;
+        }
+        ranges.push(if cores[i] == start { start.to_string() } else { format!("{}-{}", start, cores[i]) });
+        i += 1;
+    }
+    ranges.join(",")
+}
+
+// 核心所属集群的简称（"E"、"P1"），只有一个集群时不显示
+fn cluster_tag(clusters: &[ClusterMetrics], core: usize) -> String {
+    if clusters.len() < 2 {
+        return String::new();
+    }
+    match clusters.iter().find(|c| c.cores.contains(&core)) {
+        Some(cluster) => format!(" {:<3}", cluster.name.trim_end_matches("-Cluster")),
+        None => "    ".to_string(),
+    }
+}
+
+impl UI {
+    // CPU视图 - 蓝色主题
+    pub(super) fn draw_cpu_view(
+        f: &mut TermFrame,
+        area: Rect,
+        data: &SystemData,
+        history: &HistoryData,
+        config: &Config,
+    ) {
+        let (area, chart_area) = split_chart(area, area.height / 3, config);
+        if let Some(chart_area) = chart_area {
+            let series = [Series {
+                name: "CPU",
+                values: history.cpu_history.iter().map(|v| *v as f64).collect(),
+                color: Color::Cyan,
+            }];
+            Self::draw_history_chart(
+                f, chart_area, "🔵 CPU History", &series, Some(100.0),
+                format_percent, history, config.refresh_rate,
+            );
+        }
+
+        let metrics = &data.cpu_info.power_metrics;
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // 平均使用率
+                Constraint::Length(5 + metrics.clusters.len() as u16), // 集群与功率
+                Constraint::Min(0),    // 核心详情
+            ].as_ref())
+            .split(area);
+
+        let usage = data.cpu_info.average_usage.clamp(0.0, 100.0);
+        let gauge = Gauge::default()
+            .block(Block::default().title(format!("🔵 Average CPU Usage{}", provenance_tag(data, "cpu_info"))).borders(Borders::ALL))
+            .gauge_style(Style::default().fg(Color::Cyan))
+            .percent(usage as u16)
+            .label(format!(
+                "{:.1}% {}",
+                data.cpu_info.average_usage,
+                trend_arrow(history.get_cpu_trend())
+            ));
+        f.render_widget(gauge, chunks[0]);
+
+        let mut lines = vec![format!(
+            "{} ({}), {} cores",
+            data.system_info.cpu_brand,
+            data.system_info.cpu_arch,
+            data.cpu_info.core_usages.len(),
+        )];
+        for cluster in &metrics.clusters {
+            lines.push(format!(
+                "{:<11} {:3.0}% @ {} MHz  cores {}",
+                format!("{}:", cluster.name),
+                cluster.active_percent,
+                cluster.freq_mhz,
+                format_core_list(&cluster.cores),
+            ));
+        }
+        lines.push(format!(
+            "CPU: {:.2}W | GPU: {:.2}W | ANE: {:.2}W | Package: {:.2}W",
+            metrics.cpu_w, metrics.gpu_w, metrics.ane_w, metrics.package_w,
+        ));
+        lines.push(format!("Workload: {}", data.performance_metrics.workload_type));
+        let clusters_block = Paragraph::new(lines.join("\n"))
+            .block(Block::default().title(format!("🔵 Clusters & Power{}", provenance_tag(data, "cpu_info.power_metrics"))).borders(Borders::ALL))
+            .style(Style::default().fg(Color::Cyan));
+        f.render_widget(clusters_block, chunks[1]);
+
+        // 核心较多时分两列显示
+        let rows = chunks[2].height.saturating_sub(2).max(1) as usize;
+        let columns = if data.cpu_info.core_usages.len() > rows { 2 } else { 1 };
+        let column_areas = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(vec![Constraint::Ratio(1, columns); columns as usize])
+            .split(chunks[2]);
+
+        let lines: Vec<String> = data.cpu_info.core_usages
+            .iter()
+            .enumerate()
+            .map(|(i, usage)| {
+                format!("Core {:2}{}: {:5.1}% [{}]", i, cluster_tag(&metrics.clusters, i), usage, bar(*usage, 30))
+            })
+            .collect();
+        let per_column = lines.len().div_ceil(columns as usize).max(1);
+
+        for (column, area) in lines.chunks(per_column).zip(column_areas.iter()) {
+            let cores_block = Paragraph::new(column.join("\n"))
+                .block(Block::default().title("CPU Cores").borders(Borders::ALL))
+                .style(Style::default().fg(Color::Blue));
+            f.render_widget(cores_block, *area);
+        }
+    }
+
+    // 内存视图 - 绿色主题
+    pub(super) fn draw_memory_view(
+        f: &mut TermFrame,
+        area: Rect,
+        data: &SystemData,
+        history: &HistoryData,
+        config: &Config,
+    ) {
+        let (area, chart_area) = split_chart(area, area.height / 3, config);
+        if let Some(chart_area) = chart_area {
+            let series = [Series {
+                name: "Memory",
+                values: history.memory_history.iter().map(|v| *v as f64).collect(),
+                color: Color::Green,
+            }];
+            Self::draw_history_chart(
+                f, chart_area, "🟢 Memory History", &series, Some(100.0),
+                format_percent, history, config.refresh_rate,
+            );
+        }
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // 内存
+                Constraint::Length(3), // 交换
+                Constraint::Length(5), // 明细
+                Constraint::Min(0),    // 内存占用最高的进程
+            ].as_ref())
+            .split(area);
+
+        let memory = &data.memory_info;
+        let memory_gauge = Gauge::default()
+            .block(Block::default().title(format!("🟢 Memory{}", provenance_tag(data, "memory_info"))).borders(Borders::ALL))
+            .gauge_style(Style::default().fg(Color::Green))
+            .percent(memory.usage_percentage.min(100))
+            .label(format!(
+                "{} / {} ({}%) {}",
+                format_bytes(memory.used_memory),
+                format_bytes(memory.total_memory),
+                memory.usage_percentage,
+                trend_arrow(history.get_memory_trend())
+            ));
+        f.render_widget(memory_gauge, chunks[0]);
+
+        let swap_percent = percent_of(memory.used_swap, memory.total_swap);
+        let swap_gauge = Gauge::default()
+            .block(Block::default().title("🟢 Swap").borders(Borders::ALL))
+            .gauge_style(Style::default().fg(Color::LightGreen))
+            .percent(swap_percent)
+            .label(format!(
+                "{} / {} ({}%)",
+                format_bytes(memory.used_swap),
+                format_bytes(memory.total_swap),
+                swap_percent
+            ));
+        f.render_widget(swap_gauge, chunks[1]);
+
+        let details = format!(
+            "Total:     {}\nUsed:      {}\nAvailable: {}",
+            format_bytes(memory.total_memory),
+            format_bytes(memory.used_memory),
+            format_bytes(memory.available_memory)
+        );
+        let details_block = Paragraph::new(details)
+            .block(Block::default().title("Breakdown").borders(Borders::ALL))
+            .style(Style::default().fg(Color::Green));
+        f.render_widget(details_block, chunks[2]);
+
+        let mut top_processes: Vec<&ProcessInfo> = data.process_info.iter().collect();
+        top_processes.sort_by_key(|process| std::cmp::Reverse(process.memory_usage));
+        top_processes.truncate(chunks[3].height.saturating_sub(3) as usize);
+
+        let process_lines: Vec<String> = top_processes.iter().map(|process| {
+            format!("{:>8} {:>9} {:>4}% {}",
+                process.pid,
+                format_bytes(process.memory_usage),
+                percent_of(process.memory_usage, memory.total_memory),
+                process.name
+            )
+        }).collect();
+
+        let process_info = format!("     PID       MEM  MEM% NAME\n{}", process_lines.join("\n"));
+        let process_block = Paragraph::new(process_info)
+            .block(Block::default().title("🟢 Top Processes by Memory").borders(Borders::ALL))
+            .style(Style::default().fg(Color::Green));
+        f.render_widget(process_block, chunks[3]);
+    }
+
+    // 温度视图 - 黄色主题
+    pub(super) fn draw_thermal_view(
+        f: &mut TermFrame,
+        area: Rect,
+        data: &SystemData,
+        history: &HistoryData,
+        config: &Config,
+    ) {
+        let (area, chart_area) = split_chart(area, area.height / 3, config);
+        if let Some(chart_area) = chart_area {
+            let series = [Series {
+                name: "Average",
+                values: history.temperature_history.iter().map(|v| *v as f64).collect(),
+                color: Color::Yellow,
+            }];
+            Self::draw_history_chart(
+                f, chart_area, "🟡 Average Temperature", &series, Some(100.0),
+                format_celsius, history, config.refresh_rate,
+            );
+        }
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // 热压力
+                Constraint::Min(0),    // 传感器
+                Constraint::Length(5), // 风扇
+            ].as_ref())
+            .split(area);
+
+        let thermal = &data.thermal_info;
+        let pressure_gauge = Gauge::default()
+            .block(Block::default().title(format!("🟡 Thermal Pressure{}", provenance_tag(data, "thermal_info"))).borders(Borders::ALL))
+            .gauge_style(Style::default().fg(if thermal.thermal_throttling { Color::Red } else { Color::Yellow }))
+            .percent(thermal.thermal_pressure.min(100) as u16)
+            .label(format!(
+                "{}% | Throttling: {}",
+                thermal.thermal_pressure,
+                if thermal.thermal_throttling { "YES" } else { "NO" }
+            ));
+        f.render_widget(pressure_gauge, chunks[0]);
+
+        let sensor_lines: Vec<String> = data.temperature_info.iter().map(|temp| {
+            let status = if temp.temperature > 80.0 { "🔥 HOT" }
+                        else if temp.temperature > 60.0 { "⚠️ WARM" }
+                        else { "✅ COOL" };
+            let limit = if temp.critical_temperature > 0.0 { temp.critical_temperature } else { 100.0 };
+            format!("{:<24} {:5.1}°C [{}] crit {:5.1}°C {}",
+                temp.label,
+                temp.temperature,
+                bar(temp.temperature / limit * 100.0, 20),
+                temp.critical_temperature,
+                status
+            )
+        }).collect();
+
+        let sensors = if sensor_lines.is_empty() {
+            "No temperature sensors reported".to_string()
+        } else {
+            sensor_lines.join("\n")
+        };
+        let sensors_block = Paragraph::new(sensors)
+            .block(Block::default().title(format!("🟡 Temperature Sensors{}", provenance_tag(data, "temperature_info"))).borders(Borders::ALL))
+            .style(Style::default().fg(Color::Yellow));
+        f.render_widget(sensors_block, chunks[1]);
+
+        let fans = if thermal.fan_speeds.is_empty() {
+            "No fans reported".to_string()
+        } else {
+            thermal.fan_speeds
+                .iter()
+                .enumerate()
+                .map(|(i, rpm)| format!("Fan {}: {} RPM", i, rpm))
+                .collect::<Vec<_>>()
+                .join(" | ")
+        };
+        let fans_info = format!(
+            "{}\nHeat dissipation: {:.1}W",
+            fans, thermal.heat_dissipation_rate
+        );
+        let fans_block = Paragraph::new(fans_info)
+            .block(Block::default().title(format!("🟡 Cooling{}", provenance_tag(data, "thermal_info"))).borders(Borders::ALL))
+            .style(Style::default().fg(Color::Yellow));
+        f.render_widget(fans_block, chunks[2]);
+    }
+
+    // 告警视图
+    pub(super) fn draw_alerts_view(
+        f: &mut TermFrame,
+        area: Rect,
+        data: &SystemData,
+        config: &Config,
+        alerts: &[AlertState],
+    ) {
+        let header = format!(
+            "Notifications: {} | Rules: {} | Cooldown: {}s\n",
+            if config.notifications.enabled { "enabled" } else { "disabled" },
+            config.alert_rules().len(),
+            config.notifications.cooldown_seconds
+        );
+
+        let alert_lines: Vec<String> = alerts.iter().map(|alert| {
+            let phase = match alert.phase {
+                AlertPhase::Pending => "PENDING",
+                AlertPhase::Firing => "FIRING",
+            };
+            let level = match alert.level {
+                AlertLevel::Info => "INFO",
+                AlertLevel::Warning => "WARNING",
+                AlertLevel::Critical => "CRITICAL",
+            };
+            // 按采样时间计算，回放时同样准确
+            let age = data.timestamp
+                .duration_since(alert.since)
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            format!("{:<8} {:<8} {:>6}s  {} [{}] {}",
+                phase, level, age, alert.name, alert.instance, alert.message)
+        }).collect();
+
+        let body = if alert_lines.is_empty() {
+            "✅ No active alerts".to_string()
+        } else {
+            format!("PHASE    LEVEL       AGE  ALERT\n{}", alert_lines.join("\n"))
+        };
+
+        let color = match alerts.iter().map(|alert| alert.level).max() {
+            Some(AlertLevel::Critical) => Color::Red,
+            Some(AlertLevel::Warning) => Color::Yellow,
+            _ => Color::Green,
+        };
+        let alerts_block = Paragraph::new(header + &body)
+            .block(Block::default()
+                .title(format!("🚨 Alerts ({})", alerts.len()))
+                .borders(Borders::ALL))
+            .style(Style::default().fg(color));
+        f.render_widget(alerts_block, area);
+    }
+}
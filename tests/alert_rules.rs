@@ -0,0 +1,125 @@
+// Alert rule building blocks: metric paths, comparators, thresholds and durations
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::time::Duration;
+use system_alert::alert_rules::{parse_duration, Comparator, MetricPath, RuleValue};
+
+const SAMPLE: &str = include_str!("fixtures/system_data.json");
+
+fn resolve(path: &str) -> Vec<(String, Value)> {
+    let sample: Value = serde_json::from_str(SAMPLE).unwrap();
+    MetricPath::parse(path)
+        .unwrap()
+        .resolve(&sample)
+        .into_iter()
+        .map(|metric| (metric.instance, metric.value.clone()))
+        .collect()
+}
+
+fn one(instance: &str, value: Value) -> Vec<(String, Value)> {
+    vec![(instance.to_string(), value)]
+}
+
+#[test]
+fn metric_paths_resolve_fields_and_indexes() {
+    assert_eq!(resolve("battery_info.percentage"), one("", json!(80.0)));
+    assert_eq!(resolve("cpu_info.core_usages[2]"), one("2", json!(60.0)));
+    assert_eq!(resolve("thermal_info.fan_speeds[0]"), one("0", json!(1200)));
+    assert_eq!(resolve("cpu_info.power_metrics.clusters[1].cores[0]"), one("P-Cluster/0", json!(2)));
+    // Missing fields and indexes past the end resolve to nothing
+    assert!(resolve("cpu_info.core_usages[9]").is_empty());
+    assert!(resolve("battery_info.no_such_field").is_empty());
+}
+
+#[test]
+fn metric_path_selectors_filter_array_elements() {
+    assert_eq!(resolve(r#"temperature_info[label="GPU"].temperature"#), one("GPU", json!(45.0)));
+    assert_eq!(resolve(r#"temperature_info[label!="GPU"].temperature"#), one("CPU die", json!(55.0)));
+    assert_eq!(resolve(r#"temperature_info[label=~"CPU.*"].temperature"#), one("CPU die", json!(55.0)));
+    // 正则要匹配整个值
+    assert!(resolve(r#"temperature_info[label=~"CPU"].temperature"#).is_empty());
+    assert_eq!(resolve(r#"temperature_info[label!~"CPU.*"].label"#), one("GPU", json!("GPU")));
+    assert_eq!(resolve("disk_info[is_read_only=false].usage_percentage"), one("/", json!(50.0)));
+    // Elements are labelled by label, mount point, name, device or pid, in that order
+    assert_eq!(resolve("process_info[pid=100].cpu_usage"), one("cargo", json!(25.0)));
+
+    let all = resolve("temperature_info[*].temperature");
+    assert_eq!(all, [("CPU die".to_string(), json!(55.0)), ("GPU".to_string(), json!(45.0))]);
+}
+
+#[test]
+fn invalid_metric_paths_are_rejected() {
+    for path in ["", "cpu_info..average_usage", "cpu_info.average usage", "temperature_info[", "temperature_info[label]",
+        "temperature_info[la-bel=\"x\"]", r#"temperature_info[label=~"("]"#, "cpu_info[0]x"]
+    {
+        assert!(MetricPath::parse(path).is_err(), "'{}' parsed", path);
+    }
+    assert_eq!(MetricPath::parse(" battery_info.percentage ").unwrap().to_string(), "battery_info.percentage");
+}
+
+#[test]
+fn comparators_compare_numbers_booleans_and_text() {
+    let number = RuleValue::Number(80.0);
+    assert!(Comparator::Greater.compare(&json!(80.5), &number));
+    assert!(!Comparator::Greater.compare(&json!(80), &number));
+    assert!(Comparator::GreaterOrEqual.compare(&json!(80), &number));
+    assert!(Comparator::Less.compare(&json!(79.9), &number));
+    assert!(Comparator::LessOrEqual.compare(&json!(80), &number));
+    assert!(Comparator::Equal.compare(&json!(80), &number));
+    assert!(Comparator::NotEqual.compare(&json!(81), &number));
+
+    // Booleans compare as 1/0 on either side
+    assert!(Comparator::Equal.compare(&json!(true), &RuleValue::Bool(true)));
+    assert!(Comparator::Greater.compare(&json!(true), &RuleValue::Number(0.5)));
+    assert!(Comparator::Equal.compare(&json!(0), &RuleValue::Bool(false)));
+
+    let text = RuleValue::Text("mixed".to_string());
+    assert!(Comparator::Equal.compare(&json!("mixed"), &text));
+    assert!(Comparator::NotEqual.compare(&json!("idle"), &text));
+    // Ordering text, or comparing across types, never matches
+    assert!(!Comparator::Greater.compare(&json!("mixed"), &text));
+    assert!(!Comparator::Equal.compare(&json!(1), &text));
+    assert!(!Comparator::Equal.compare(&json!("80"), &number));
+    assert!(!Comparator::NotEqual.compare(&Value::Null, &number));
+}
+
+#[test]
+fn rule_values_deserialize_untagged() {
+    #[derive(Deserialize)]
+    struct Rule {
+        op: Comparator,
+        threshold: RuleValue,
+    }
+
+    let parse = |toml_source: &str| toml::from_str::<Rule>(toml_source).unwrap();
+    let rule = parse("op = \">=\"\nthreshold = 80");
+    assert_eq!((rule.op, rule.threshold), (Comparator::GreaterOrEqual, RuleValue::Number(80.0)));
+    assert_eq!(parse("op = \"<\"\nthreshold = 12.5").threshold, RuleValue::Number(12.5));
+    assert_eq!(parse("op = \"==\"\nthreshold = true").threshold, RuleValue::Bool(true));
+    assert_eq!(parse("op = \"!=\"\nthreshold = \"idle\"").threshold, RuleValue::Text("idle".to_string()));
+    assert!(toml::from_str::<Rule>("op = \"=>\"\nthreshold = 1").is_err());
+
+    let json: Vec<RuleValue> = serde_json::from_str(r#"[false, 3, "hot"]"#).unwrap();
+    assert_eq!(json, [RuleValue::Bool(false), RuleValue::Number(3.0), RuleValue::Text("hot".to_string())]);
+}
+
+#[test]
+fn durations_parse_with_units() {
+    assert_eq!(parse_duration("500ms"), Ok(Duration::from_millis(500)));
+    assert_eq!(parse_duration("30s"), Ok(Duration::from_secs(30)));
+    assert_eq!(parse_duration("30"), Ok(Duration::from_secs(30)));
+    assert_eq!(parse_duration(" 1.5m "), Ok(Duration::from_secs(90)));
+    assert_eq!(parse_duration("5 m"), Ok(Duration::from_secs(300)));
+    assert_eq!(parse_duration("2h"), Ok(Duration::from_secs(7200)));
+}
+
+#[test]
+fn invalid_durations_are_errors() {
+    for input in ["", "s", "abc", "5d", "-5s", "1.2.3s", "1e400h"] {
+        assert!(parse_duration(input).is_err(), "'{}' parsed", input);
+    }
+    // Too large for a Duration: an error rather than a panic
+    let huge = format!("1{}h", "0".repeat(400));
+    assert!(parse_duration(&huge).unwrap_err().contains("out of range"));
+}
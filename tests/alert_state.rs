@@ -0,0 +1,169 @@
+// Alert state machine driven by synthetic SystemData sequences
+
+use serde::Deserialize;
+use std::time::{Duration, UNIX_EPOCH};
+use system_alert::alert_rules::AlertRule;
+use system_alert::notification::{AlertLevel, AlertPhase, Notification, NotificationManager};
+use system_alert::types::SystemData;
+
+const SAMPLE: &str = include_str!("fixtures/system_data.json");
+
+// CPU usage tiers: warning above 80 (resolves below 70), critical above 95 (back to warning below 90)
+const CPU_RULES: &str = r#"
+[[alerts]]
+name = "cpu"
+metric = "cpu_info.average_usage"
+op = ">"
+threshold = 80
+recovery = 70
+level = "warning"
+for = "10s"
+
+[[alerts]]
+name = "cpu"
+metric = "cpu_info.average_usage"
+op = ">"
+threshold = 95
+recovery = 90
+level = "critical"
+for = "10s"
+"#;
+
+#[derive(Deserialize)]
+struct Rules {
+    alerts: Vec<AlertRule>,
+}
+
+fn manager(rules: &str, cooldown_seconds: u64) -> NotificationManager {
+    let mut manager = NotificationManager::new(true, cooldown_seconds);
+    manager.set_rules(toml::from_str::<Rules>(rules).unwrap().alerts);
+    manager
+}
+
+/// A sample taken `secs` seconds into the sequence with the given CPU usage
+fn at(secs: u64, cpu_usage: f32) -> SystemData {
+    let mut data: SystemData = serde_json::from_str(SAMPLE).unwrap();
+    data.timestamp = UNIX_EPOCH + Duration::from_secs(1_700_000_000 + secs);
+    data.cpu_info.average_usage = cpu_usage;
+    data
+}
+
+fn step(manager: &mut NotificationManager, secs: u64, cpu_usage: f32) -> Vec<Notification> {
+    manager.evaluate(&at(secs, cpu_usage)).unwrap()
+}
+
+fn phase(manager: &NotificationManager) -> Option<(AlertPhase, AlertLevel)> {
+    manager.active_alerts().first().map(|alert| (alert.phase, alert.level))
+}
+
+#[test]
+fn pending_alert_fires_once_it_held_for_its_duration() {
+    let mut manager = manager(CPU_RULES, 3600);
+
+    assert!(step(&mut manager, 0, 85.0).is_empty());
+    assert_eq!(phase(&manager), Some((AlertPhase::Pending, AlertLevel::Warning)));
+    assert!(step(&mut manager, 5, 85.0).is_empty());
+    assert_eq!(phase(&manager), Some((AlertPhase::Pending, AlertLevel::Warning)));
+
+    let fired = step(&mut manager, 10, 85.0);
+    assert_eq!(fired.len(), 1);
+    assert_eq!((fired[0].level(), fired[0].is_resolved()), (AlertLevel::Warning, false));
+    assert_eq!(fired[0].message(), "cpu_info.average_usage is 85.0 (> 80)");
+    assert_eq!(phase(&manager), Some((AlertPhase::Firing, AlertLevel::Warning)));
+}
+
+#[test]
+fn pending_alert_clears_when_the_value_drops_before_its_duration() {
+    let mut manager = manager(CPU_RULES, 3600);
+
+    assert!(step(&mut manager, 0, 85.0).is_empty());
+    assert!(step(&mut manager, 5, 50.0).is_empty());
+    assert_eq!(phase(&manager), None);
+
+    // 条件重新成立后 `for` 计时从头开始
+    assert!(step(&mut manager, 10, 85.0).is_empty());
+    assert!(step(&mut manager, 15, 85.0).is_empty());
+    assert_eq!(phase(&manager), Some((AlertPhase::Pending, AlertLevel::Warning)));
+    assert_eq!(step(&mut manager, 20, 85.0).len(), 1);
+}
+
+#[test]
+fn firing_alert_resolves_only_below_its_recovery_threshold() {
+    let mut manager = manager(CPU_RULES, 3600);
+    step(&mut manager, 0, 85.0);
+    assert_eq!(step(&mut manager, 10, 85.0).len(), 1);
+
+    // Below the threshold but above recovery keeps firing, silently
+    assert!(step(&mut manager, 20, 75.0).is_empty());
+    assert_eq!(phase(&manager), Some((AlertPhase::Firing, AlertLevel::Warning)));
+
+    let resolved = step(&mut manager, 30, 65.0);
+    assert_eq!(resolved.len(), 1);
+    assert_eq!((resolved[0].level(), resolved[0].is_resolved()), (AlertLevel::Warning, true));
+    assert_eq!(resolved[0].message(), "cpu_info.average_usage is back to 65.0");
+    assert_eq!(phase(&manager), None);
+}
+
+#[test]
+fn warning_escalates_to_critical_and_back() {
+    let mut manager = manager(CPU_RULES, 3600);
+    step(&mut manager, 0, 85.0);
+    assert_eq!(step(&mut manager, 10, 85.0)[0].level(), AlertLevel::Warning);
+
+    // The critical tier waits out its own `for` while the warning keeps firing
+    assert!(step(&mut manager, 20, 97.0).is_empty());
+    assert_eq!(phase(&manager), Some((AlertPhase::Firing, AlertLevel::Warning)));
+
+    let escalated = step(&mut manager, 30, 97.0);
+    assert_eq!(escalated.len(), 1);
+    assert_eq!((escalated[0].level(), escalated[0].is_resolved()), (AlertLevel::Critical, false));
+    assert_eq!(phase(&manager), Some((AlertPhase::Firing, AlertLevel::Critical)));
+    assert_eq!(manager.active_alerts().len(), 1);
+
+    // Below the critical recovery threshold but still above the warning one
+    let lowered = step(&mut manager, 40, 85.0);
+    assert_eq!(lowered.len(), 1);
+    assert_eq!((lowered[0].level(), lowered[0].is_resolved()), (AlertLevel::Warning, false));
+    assert_eq!(phase(&manager), Some((AlertPhase::Firing, AlertLevel::Warning)));
+}
+
+#[test]
+fn cooldown_suppresses_reminders_for_a_firing_alert() {
+    let rules = r#"
+        [[alerts]]
+        name = "cpu"
+        metric = "cpu_info.average_usage"
+        op = ">"
+        threshold = 80
+        level = "warning"
+    "#;
+    let mut manager = manager(rules, 60);
+
+    let sent: Vec<usize> = (0..=13).map(|i| step(&mut manager, i * 10, 85.0).len()).collect();
+    // Fires at 0s, then reminds at 60s and 120s
+    assert_eq!(sent, [1, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 1, 0]);
+}
+
+#[test]
+fn reloading_keeps_the_state_of_unchanged_rules() {
+    let mut manager = manager(CPU_RULES, 3600);
+    step(&mut manager, 0, 85.0);
+    assert_eq!(step(&mut manager, 10, 85.0).len(), 1);
+
+    assert!(manager.set_rules(toml::from_str::<Rules>(CPU_RULES).unwrap().alerts).is_empty());
+    assert_eq!(phase(&manager), Some((AlertPhase::Firing, AlertLevel::Warning)));
+    assert!(step(&mut manager, 20, 85.0).is_empty());
+}
+
+// 删除规则时会把 resolved 通知交给投递任务，所以需要运行时
+#[tokio::test]
+async fn reloading_resolves_firing_alerts_of_removed_rules() {
+    let mut manager = manager(CPU_RULES, 3600);
+    step(&mut manager, 0, 85.0);
+    step(&mut manager, 10, 85.0);
+
+    let resolved = manager.set_rules(Vec::new());
+    assert_eq!(resolved.len(), 1);
+    assert_eq!((resolved[0].level(), resolved[0].is_resolved()), (AlertLevel::Warning, true));
+    assert_eq!(phase(&manager), None);
+}
@@ -0,0 +1,125 @@
+// Collector tasks on independent schedules and the provenance they record,
+// driven by fake metric sources
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use system_alert::data_collector::{CollectorSchedule, DataCollector};
+use system_alert::sources::{BatterySource, CollectorError, MetricSources, SourceFuture, SourceResult};
+use system_alert::types::{BatteryInfo, DataOrigin, SystemData};
+
+/// Counts its samples and takes `delay` to produce each one, like a slow `system_profiler`.
+/// Every sample after the first `succeed` fails.
+struct FakeBattery {
+    samples: Arc<AtomicUsize>,
+    delay: Duration,
+    succeed: usize,
+}
+
+impl BatterySource for FakeBattery {
+    fn name(&self) -> &'static str {
+        "fake"
+    }
+
+    fn sample(&mut self) -> SourceFuture<'_, SourceResult<BatteryInfo>> {
+        Box::pin(async {
+            tokio::time::sleep(self.delay).await;
+            let n = self.samples.fetch_add(1, Ordering::SeqCst) + 1;
+            if n > self.succeed {
+                return Err(CollectorError::failed("fake", "unplugged"));
+            }
+            Ok(BatteryInfo { percentage: n as f32, ..BatteryInfo::default() })
+        })
+    }
+}
+
+fn start(delay: Duration, schedule: CollectorSchedule) -> (DataCollector, Arc<AtomicUsize>) {
+    start_failing_after(usize::MAX, delay, schedule)
+}
+
+fn start_failing_after(succeed: usize, delay: Duration, schedule: CollectorSchedule) -> (DataCollector, Arc<AtomicUsize>) {
+    let samples = Arc::new(AtomicUsize::new(0));
+    let sources = MetricSources {
+        battery: Box::new(FakeBattery { samples: samples.clone(), delay, succeed }),
+        ..MetricSources::unsupported()
+    };
+    (DataCollector::start(sources, schedule), samples)
+}
+
+async fn wait_for(collector: &DataCollector, ready: impl Fn(&SystemData) -> bool) -> SystemData {
+    for _ in 0..100 {
+        if let Some(data) = collector.snapshot().filter(|data| ready(data)) {
+            return data;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    panic!("collectors produced no matching snapshot within 5s");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn a_slow_source_does_not_hold_back_the_others() {
+    let (collector, samples) = start(Duration::from_secs(2), CollectorSchedule::default());
+
+    let data = wait_for(&collector, |data| data.provenance("disk_info").is_some()).await;
+    assert!(data.is_measured("cpu_info"));
+    assert!(data.is_measured("memory_info"));
+    assert!(data.provenance("battery_info").is_none());
+    assert_eq!(samples.load(Ordering::SeqCst), 0);
+
+    let data = wait_for(&collector, |data| data.provenance("battery_info").is_some()).await;
+    assert_eq!(data.battery_info.percentage, 1.0);
+    assert!(data.provenance["battery_info"].measured_at > data.provenance["disk_info"].measured_at);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn failures_are_reported_per_subsystem() {
+    let (collector, _) = start(Duration::ZERO, CollectorSchedule::default());
+    let data = wait_for(&collector, |data| data.provenance("battery_info").is_some()).await;
+    // 没有功耗数据源时 CPU 功耗改用估算值，并记录原因
+    let subsystems: Vec<&str> = data.collector_issues.iter().map(|i| i.subsystem.as_str()).collect();
+    assert_eq!(subsystems, ["cpu_power"]);
+    assert_eq!(data.collector_issues[0].kind, "unsupported");
+
+    let power = &data.provenance["cpu_info.power_metrics"];
+    assert_eq!((power.origin, power.source.as_str()), (DataOrigin::Estimated, "usage-estimate"));
+    assert!(!data.is_measured("cpu_info.power_metrics"));
+    assert_eq!(data.provenance["performance_metrics"].origin, DataOrigin::Estimated);
+    assert!(data.is_measured("battery_info"));
+    assert_eq!(data.provenance["battery_info"].source, "fake");
+    // 不支持的数据源只给出空值，不记来源
+    assert!(data.provenance("thermal_info").is_none());
+    assert!(data.provenance("system_health").is_none());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn a_failing_source_leaves_its_last_values_marked_cached() {
+    let schedule = CollectorSchedule { battery: Duration::from_millis(100), ..CollectorSchedule::default() };
+    let (collector, _) = start_failing_after(1, Duration::ZERO, schedule);
+
+    let measured = wait_for(&collector, |data| data.provenance("battery_info").is_some()).await;
+    let cached = wait_for(&collector, |data| {
+        data.provenance("battery_info").is_some_and(|p| p.origin == DataOrigin::Cached)
+    })
+    .await;
+    assert_eq!(cached.battery_info.percentage, 1.0);
+    assert_eq!(cached.provenance["battery_info"].measured_at, measured.provenance["battery_info"].measured_at);
+    assert!(cached.collector_issues.iter().any(|i| i.subsystem == "battery"));
+
+    // 超过三个采集周期没有新数据就算过期
+    let stale = wait_for(&collector, |data| data.provenance["battery_info"].is_stale(data.timestamp)).await;
+    assert!(stale.provenance["battery_info"].age(stale.timestamp) > Duration::from_millis(300));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn schedule_changes_apply_to_running_tasks() {
+    let schedule = CollectorSchedule { battery: Duration::from_millis(50), ..CollectorSchedule::default() };
+    let (collector, samples) = start(Duration::ZERO, schedule.clone());
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert!(samples.load(Ordering::SeqCst) >= 4);
+
+    collector.set_schedule(CollectorSchedule { battery: Duration::from_secs(3600), ..schedule });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let settled = samples.load(Ordering::SeqCst);
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert_eq!(samples.load(Ordering::SeqCst), settled);
+}
@@ -0,0 +1,50 @@
+// Per-second rates from cumulative counters
+
+use std::time::{Duration, Instant};
+use system_alert::rates::CounterRates;
+
+#[test]
+fn the_first_sample_of_a_key_has_no_rate() {
+    let mut rates = CounterRates::new();
+    let now = Instant::now();
+    assert_eq!(rates.update(&"en0", &[1000, 10], now), None);
+    // 同一时刻再次采样也给不出速率
+    assert_eq!(rates.update(&"en0", &[2000, 20], now), None);
+}
+
+#[test]
+fn deltas_are_divided_by_the_elapsed_time() {
+    let mut rates = CounterRates::new();
+    let start = Instant::now();
+    rates.update(&"en0", &[1000, 10], start);
+    assert_eq!(rates.update(&"en0", &[3000, 14], start + Duration::from_secs(2)), Some(vec![1000.0, 2.0]));
+    assert_eq!(rates.update(&"en0", &[3500, 14], start + Duration::from_millis(2500)), Some(vec![1000.0, 0.0]));
+    // Other keys are tracked separately
+    assert_eq!(rates.update(&"en1", &[5, 5], start + Duration::from_secs(3)), None);
+}
+
+#[test]
+fn a_counter_reset_reports_zero_for_that_interval() {
+    let mut rates = CounterRates::new();
+    let start = Instant::now();
+    rates.update(&"sda", &[1_000_000, 500], start);
+    // Only the first counter went backwards
+    let rate = rates.update(&"sda", &[4096, 700], start + Duration::from_secs(1));
+    assert_eq!(rate, Some(vec![0.0, 200.0]));
+    // and counts normally again from the new value
+    let rate = rates.update(&"sda", &[8192, 700], start + Duration::from_secs(2));
+    assert_eq!(rate, Some(vec![4096.0, 0.0]));
+}
+
+#[test]
+fn a_removed_key_starts_over_when_it_reappears() {
+    let mut rates = CounterRates::new();
+    let start = Instant::now();
+    rates.update(&"wlan0".to_string(), &[1000], start);
+    rates.update(&"eth0".to_string(), &[1000], start);
+
+    rates.retain(|key| key != "wlan0");
+    assert_eq!(rates.update(&"wlan0".to_string(), &[5000], start + Duration::from_secs(1)), None);
+    assert_eq!(rates.update(&"eth0".to_string(), &[3000], start + Duration::from_secs(1)), Some(vec![2000.0]));
+    assert_eq!(rates.update(&"wlan0".to_string(), &[6000], start + Duration::from_secs(2)), Some(vec![1000.0]));
+}
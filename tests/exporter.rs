@@ -0,0 +1,105 @@
+// Prometheus exposition rendered from a fixed sample
+
+use std::time::Duration;
+use system_alert::exporter::{render_metrics, RenderOptions};
+use system_alert::types::{DataOrigin, Provenance, SystemData};
+
+const SAMPLE: &str = include_str!("fixtures/system_data.json");
+
+/// The fixture with CPU and memory measured just now, the battery cached from
+/// 20s ago, disk I/O stale and nothing recorded for the other groups
+fn sample() -> SystemData {
+    let mut data: SystemData = serde_json::from_str(SAMPLE).unwrap();
+    let provenance = |origin, source: &str, age_secs: u64, max_age_secs: u64| Provenance {
+        origin,
+        source: source.to_string(),
+        measured_at: data.timestamp - Duration::from_secs(age_secs),
+        max_age: Duration::from_secs(max_age_secs),
+    };
+    let groups = [
+        ("cpu_info", provenance(DataOrigin::Measured, "sysinfo", 0, 3)),
+        ("cpu_info.power_metrics", provenance(DataOrigin::Measured, "powermetrics", 1, 3)),
+        ("memory_info", provenance(DataOrigin::Measured, "sysinfo", 0, 3)),
+        ("battery_info", provenance(DataOrigin::Cached, "pmset+ioreg", 20, 90)),
+        ("disk_io", provenance(DataOrigin::Measured, "iostat", 60, 15)),
+    ];
+    data.provenance = groups.into_iter().map(|(group, p)| (group.to_string(), p)).collect();
+    data
+}
+
+fn lines(text: &str) -> Vec<&str> {
+    text.lines().collect()
+}
+
+#[test]
+fn renders_families_with_help_type_and_labels() {
+    let text = render_metrics(&sample(), RenderOptions::default());
+    let lines = lines(&text);
+
+    for expected in [
+        "# HELP sysalert_cpu_usage_percent Average CPU usage across all cores",
+        "# TYPE sysalert_cpu_usage_percent gauge",
+        "sysalert_cpu_usage_percent 55",
+        "sysalert_cpu_core_usage_percent{core=\"2\"} 60",
+        "sysalert_power_watts{domain=\"package\"} 3",
+        "sysalert_cpu_cluster_active_percent{cluster=\"P-Cluster\",kind=\"performance\"} 70",
+        "sysalert_memory_total_bytes 17179869184",
+        "# TYPE sysalert_network_received_bytes_total counter",
+        "sysalert_network_received_bytes_total{interface=\"en0\"} 1000",
+        "sysalert_filesystem_size_bytes{mountpoint=\"/\",device=\"disk3s1\",fstype=\"apfs\"} 500000000000",
+        "sysalert_disk_written_bytes_total{device=\"disk0\"} 8192",
+        "sysalert_temperature_celsius{sensor=\"CPU die\"} 55",
+        "sysalert_fan_speed_rpm{fan=\"0\"} 1200",
+        "sysalert_battery_charge_percent 80",
+        "sysalert_battery_time_remaining_seconds 300",
+        "sysalert_load_average{period=\"15m\"} 1",
+        "sysalert_data_age_seconds{group=\"battery_info\",source=\"pmset+ioreg\",origin=\"cached\"} 20",
+        "sysalert_data_age_seconds{group=\"cpu_info.power_metrics\",source=\"powermetrics\",origin=\"measured\"} 1",
+    ] {
+        assert!(lines.contains(&expected), "missing '{}'", expected);
+    }
+
+    // 每个指标族的 HELP/TYPE 只出现一次，且在它的样本之前
+    let mut declared = Vec::new();
+    for line in &lines {
+        if let Some(rest) = line.strip_prefix("# TYPE ") {
+            let name = rest.split_whitespace().next().unwrap();
+            assert!(!declared.contains(&name), "{} declared twice", name);
+            declared.push(name);
+        } else if !line.starts_with('#') {
+            let name = line.split(['{', ' ']).next().unwrap();
+            assert!(declared.contains(&name), "{} has no TYPE line before it", name);
+        }
+    }
+}
+
+#[test]
+fn escapes_label_values() {
+    let mut data = sample();
+    data.temperature_info[0].label = "Quote \"x\" \\ y\nz".to_string();
+    let text = render_metrics(&data, RenderOptions::default());
+    assert!(lines(&text).contains(&"sysalert_temperature_celsius{sensor=\"Quote \\\"x\\\" \\\\ y\\nz\"} 55"));
+}
+
+#[test]
+fn measured_only_leaves_out_estimated_cached_stale_and_unknown_groups() {
+    let data = sample();
+    let text = render_metrics(&data, RenderOptions { measured_only: true });
+
+    for kept in ["sysalert_cpu_usage_percent 55", "sysalert_power_watts{domain=\"cpu\"} 2.5", "sysalert_memory_usage_percent 50"] {
+        assert!(text.contains(kept), "missing '{}'", kept);
+    }
+    // Cached battery, stale disk I/O, and groups without provenance
+    for dropped in ["sysalert_battery_", "sysalert_disk_read_bytes_total", "sysalert_network_", "sysalert_temperature_celsius", "sysalert_load_average"] {
+        assert!(!text.contains(dropped), "'{}' exported", dropped);
+    }
+    // Their provenance is still there, so the gap is visible
+    assert!(text.contains("sysalert_data_age_seconds{group=\"disk_io\",source=\"iostat\",origin=\"measured\"} 60"));
+    assert!(text.contains("group=\"battery_info\""));
+
+    let mut estimated = data.clone();
+    estimated.provenance.get_mut("cpu_info.power_metrics").unwrap().origin = DataOrigin::Estimated;
+    let text = render_metrics(&estimated, RenderOptions { measured_only: true });
+    assert!(!text.contains("sysalert_power_watts"));
+    assert!(text.contains("sysalert_cpu_usage_percent"));
+}
@@ -0,0 +1,26 @@
+#!/bin/sh
+# Stand-in for `powermetrics -i <ms> -f plist`:
+#   fake_powermetrics.sh [--interval SECONDS] [--exit CODE] [--stderr MESSAGE] SAMPLE.plist...
+# Writes each sample file followed by a NUL byte, like powermetrics does per
+# sample, SECONDS apart (default 0.05). Then prints MESSAGE to stderr and
+# exits with CODE (default 0).
+interval=0.05
+code=0
+message=""
+while [ $# -gt 0 ]; do
+    case "$1" in
+        --interval) interval="$2"; shift 2 ;;
+        --exit) code="$2"; shift 2 ;;
+        --stderr) message="$2"; shift 2 ;;
+        *) break ;;
+    esac
+done
+for sample in "$@"; do
+    cat "$sample"
+    printf '\0'
+    sleep "$interval"
+done
+if [ -n "$message" ]; then
+    echo "$message" >&2
+fi
+exit "$code"
@@ -0,0 +1,21 @@
+// Key presses mapped to commands or passed on to the active view
+
+use system_alert::cli::{map_key, InputEvent};
+use termion::event::Key;
+
+#[test]
+fn keys_are_commands_outside_text_entry() {
+    assert!(matches!(map_key(Key::Char('q'), false), InputEvent::Quit));
+    assert!(matches!(map_key(Key::Char('3'), false), InputEvent::SelectTab(2)));
+    assert!(matches!(map_key(Key::Char('\t'), false), InputEvent::NextTab));
+    assert!(matches!(map_key(Key::Char('/'), false), InputEvent::Key(Key::Char('/'))));
+}
+
+#[test]
+fn text_entry_gets_every_key_but_ctrl_c() {
+    // 过滤框里输入的 q 和数字不能退出程序或切换标签页
+    for key in [Key::Char('q'), Key::Char('1'), Key::Char('n'), Key::Char('r'), Key::Left, Key::Char('\t')] {
+        assert!(matches!(map_key(key, true), InputEvent::Key(k) if k == key), "{:?}", key);
+    }
+    assert!(matches!(map_key(Key::Ctrl('c'), true), InputEvent::Quit));
+}
@@ -0,0 +1,368 @@
+// procfs/sysfs readers pointed at temporary fixture roots
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use system_alert::sources::linux::{
+    parse_cpu_list, parse_diskstats, read_cpu_topology, read_throttle_count, DiskstatsSource, PowerSupplySource, ProcHealthSource,
+    SysfsThermalSource,
+};
+use system_alert::sources::rapl::RaplSource;
+use system_alert::sources::{BatterySource, CollectorError, DiskIoSource, HealthSource, ThermalSource};
+use system_alert::types::{ClusterKind, ClusterMetrics};
+
+/// A scratch directory standing in for "/", removed again on drop
+struct FixtureRoot(PathBuf);
+
+impl FixtureRoot {
+    fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("system_alert-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        Self(dir)
+    }
+
+    fn write(&self, path: &str, content: &str) -> &Self {
+        let path = self.0.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, format!("{}\n", content)).unwrap();
+        self
+    }
+
+    fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for FixtureRoot {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+fn assert_close(actual: f32, expected: f32) {
+    assert!((actual - expected).abs() < 0.01, "{} != {}", actual, expected);
+}
+
+#[tokio::test]
+async fn reads_a_charge_based_battery() {
+    let root = FixtureRoot::new("charge");
+    root.write("sys/class/power_supply/AC/type", "Mains")
+        .write("sys/class/power_supply/AC/online", "0")
+        .write("sys/class/power_supply/BAT0/type", "Battery")
+        .write("sys/class/power_supply/BAT0/status", "Discharging")
+        .write("sys/class/power_supply/BAT0/capacity", "80")
+        .write("sys/class/power_supply/BAT0/cycle_count", "120")
+        .write("sys/class/power_supply/BAT0/voltage_now", "12000000")
+        .write("sys/class/power_supply/BAT0/current_now", "1500000")
+        .write("sys/class/power_supply/BAT0/charge_full", "4500000")
+        .write("sys/class/power_supply/BAT0/charge_full_design", "5000000")
+        .write("sys/class/power_supply/BAT0/charge_now", "3600000")
+        .write("sys/class/power_supply/BAT0/temp", "305");
+
+    let mut source = PowerSupplySource::new(root.path());
+    let battery = source.sample().await.unwrap();
+    assert_close(battery.percentage, 80.0);
+    assert!(!battery.is_plugged && !battery.is_charging);
+    assert_eq!(battery.cycle_count, 120);
+    assert_close(battery.voltage, 12.0);
+    // 放电时电流为负，和 macOS 一致
+    assert_close(battery.amperage, -1.5);
+    assert_close(battery.temperature, 30.5);
+    assert_eq!((battery.current_capacity, battery.design_capacity), (4500, 5000));
+    assert_close(battery.health_percentage, 90.0);
+    // 3.6 Ah at 1.5 A
+    assert_eq!(battery.time_remaining, Some(8640));
+}
+
+#[tokio::test]
+async fn converts_an_energy_based_battery_to_charge() {
+    let root = FixtureRoot::new("energy");
+    root.write("sys/class/power_supply/ADP1/type", "Mains")
+        .write("sys/class/power_supply/ADP1/online", "1")
+        .write("sys/class/power_supply/BAT1/type", "Battery")
+        .write("sys/class/power_supply/BAT1/status", "Charging")
+        .write("sys/class/power_supply/BAT1/capacity", "50")
+        .write("sys/class/power_supply/BAT1/voltage_now", "15000000")
+        .write("sys/class/power_supply/BAT1/voltage_min_design", "15000000")
+        .write("sys/class/power_supply/BAT1/power_now", "30000000")
+        .write("sys/class/power_supply/BAT1/energy_full", "60000000")
+        .write("sys/class/power_supply/BAT1/energy_full_design", "75000000")
+        .write("sys/class/power_supply/BAT1/energy_now", "30000000");
+
+    let battery = PowerSupplySource::new(root.path()).sample().await.unwrap();
+    assert!(battery.is_plugged && battery.is_charging);
+    // 60 Wh / 15 V = 4000 mAh, current from 30 W / 15 V
+    assert_eq!((battery.current_capacity, battery.design_capacity), (4000, 5000));
+    assert_close(battery.health_percentage, 80.0);
+    assert_close(battery.amperage, 2.0);
+    assert_eq!(battery.time_remaining, None);
+}
+
+#[tokio::test]
+async fn a_missing_power_supply_class_is_not_found() {
+    let root = FixtureRoot::new("no-power-supply");
+    let result = PowerSupplySource::new(root.path()).sample().await;
+    assert!(matches!(result, Err(CollectorError::NotFound(_))));
+}
+
+#[tokio::test]
+async fn reads_fans_and_thermal_zones() {
+    let root = FixtureRoot::new("thermal");
+    root.write("sys/class/hwmon/hwmon0/name", "thinkpad")
+        .write("sys/class/hwmon/hwmon0/fan1_input", "2100")
+        .write("sys/class/hwmon/hwmon0/fan2_input", "1800")
+        .write("sys/class/hwmon/hwmon0/temp1_input", "45000")
+        .write("sys/class/hwmon/hwmon1/name", "acpitz")
+        .write("sys/class/thermal/thermal_zone0/temp", "80000")
+        .write("sys/class/thermal/thermal_zone0/trip_point_0_type", "passive")
+        .write("sys/class/thermal/thermal_zone0/trip_point_0_temp", "90000")
+        .write("sys/class/thermal/thermal_zone0/trip_point_1_type", "critical")
+        .write("sys/class/thermal/thermal_zone0/trip_point_1_temp", "100000")
+        .write("sys/class/thermal/thermal_zone1/temp", "40000")
+        .write("sys/class/thermal/thermal_zone1/trip_point_0_type", "critical")
+        .write("sys/class/thermal/thermal_zone1/trip_point_0_temp", "100000")
+        .write("sys/class/thermal/cooling_device0/type", "Processor")
+        .write("sys/class/thermal/cooling_device0/cur_state", "0");
+
+    let thermal = SysfsThermalSource::new(root.path()).sample().await;
+    assert_eq!(thermal.fan_speeds, [2100, 1800]);
+    // 最热的 zone 对应其 critical 触发点：80 / 100
+    assert_eq!(thermal.thermal_pressure, 80);
+    assert!(!thermal.thermal_throttling);
+    assert_close(thermal.heat_dissipation_rate, 20.0);
+}
+
+#[tokio::test]
+async fn an_active_processor_cooling_device_means_throttling() {
+    let root = FixtureRoot::new("cooling");
+    root.write("sys/class/thermal/thermal_zone0/temp", "30000")
+        .write("sys/class/thermal/thermal_zone0/trip_point_0_type", "critical")
+        .write("sys/class/thermal/thermal_zone0/trip_point_0_temp", "100000")
+        .write("sys/class/thermal/cooling_device0/type", "Processor")
+        .write("sys/class/thermal/cooling_device0/cur_state", "2");
+
+    let thermal = SysfsThermalSource::new(root.path()).sample().await;
+    assert!(thermal.fan_speeds.is_empty());
+    assert_eq!(thermal.thermal_pressure, 30);
+    assert!(thermal.thermal_throttling);
+}
+
+#[tokio::test]
+async fn a_warm_zone_without_throttle_signals_is_not_throttling() {
+    let root = FixtureRoot::new("warm");
+    // 53 °C against a 105 °C trip point: ordinary load
+    root.write("sys/class/thermal/thermal_zone0/temp", "53000")
+        .write("sys/class/thermal/thermal_zone0/trip_point_0_type", "critical")
+        .write("sys/class/thermal/thermal_zone0/trip_point_0_temp", "105000")
+        .write("sys/class/thermal/cooling_device0/type", "Processor")
+        .write("sys/class/thermal/cooling_device0/cur_state", "0")
+        .write("sys/devices/system/cpu/cpu0/thermal_throttle/core_throttle_count", "7")
+        .write("sys/devices/system/cpu/cpu0/thermal_throttle/package_throttle_count", "3");
+
+    let mut source = SysfsThermalSource::new(root.path());
+    for _ in 0..2 {
+        let thermal = source.sample().await;
+        assert_eq!(thermal.thermal_pressure, 50);
+        assert!(!thermal.thermal_throttling);
+    }
+}
+
+#[tokio::test]
+async fn growing_throttle_counters_mean_throttling() {
+    let root = FixtureRoot::new("throttle-count");
+    root.write("sys/devices/system/cpu/cpu0/thermal_throttle/core_throttle_count", "7")
+        .write("sys/devices/system/cpu/cpu1/thermal_throttle/core_throttle_count", "2")
+        .write("sys/devices/system/cpu/cpu1/thermal_throttle/package_throttle_count", "3");
+    assert_eq!(read_throttle_count(root.path()), Some(12));
+
+    // 第一次采样只记下计数，之后计数增长才算降频
+    let mut source = SysfsThermalSource::new(root.path());
+    assert!(!source.sample().await.thermal_throttling);
+    root.write("sys/devices/system/cpu/cpu1/thermal_throttle/core_throttle_count", "5");
+    assert!(source.sample().await.thermal_throttling);
+    assert!(!source.sample().await.thermal_throttling);
+}
+
+#[tokio::test]
+async fn reads_uptime_and_load_averages() {
+    let root = FixtureRoot::new("health");
+    root.write("proc/uptime", "12345.67 54321.00")
+        .write("proc/loadavg", "1.52 1.58 1.59 1/467 12345");
+
+    let health = ProcHealthSource::new(root.path()).sample().await;
+    assert_eq!(health.uptime_seconds, 12345);
+    assert_eq!(
+        (health.system_load_1min, health.system_load_5min, health.system_load_15min),
+        (1.52, 1.58, 1.59)
+    );
+    assert_eq!(health.power_quality_score, 85);
+    assert_close(health.sleep_wake_efficiency, 85.0);
+
+    let empty = FixtureRoot::new("health-empty");
+    let health = ProcHealthSource::new(empty.path()).sample().await;
+    assert_eq!((health.uptime_seconds, health.system_load_1min), (0, 0.0));
+}
+
+fn rapl_root(name: &str, max_energy_range_uj: u64) -> FixtureRoot {
+    let root = FixtureRoot::new(name);
+    root.write("sys/class/powercap/intel-rapl:0/name", "package-0")
+        .write("sys/class/powercap/intel-rapl:0/max_energy_range_uj", &max_energy_range_uj.to_string())
+        .write("sys/class/powercap/intel-rapl:0:0/name", "core")
+        .write("sys/class/powercap/intel-rapl:0:0/max_energy_range_uj", &max_energy_range_uj.to_string())
+        .write("sys/class/powercap/intel-rapl:0:2/name", "dram");
+    root
+}
+
+fn set_energy(root: &FixtureRoot, package_uj: u64, core_uj: u64) {
+    root.write("sys/class/powercap/intel-rapl:0/energy_uj", &package_uj.to_string())
+        .write("sys/class/powercap/intel-rapl:0:0/energy_uj", &core_uj.to_string());
+}
+
+#[test]
+fn rapl_primes_on_the_first_sample_then_reports_watts() {
+    let root = rapl_root("rapl", 262_143_328_850);
+    set_energy(&root, 5_000_000, 1_000_000);
+    let mut source = RaplSource::new(root.path());
+    assert!(source.is_available());
+
+    let start = Instant::now();
+    match source.sample_at(start) {
+        Err(CollectorError::Failed { reason, .. }) => assert!(reason.contains("primed"), "{}", reason),
+        other => panic!("expected the priming error, got {:?}", other.map(|m| m.package_w)),
+    }
+
+    // 2 J package and 0.5 J core over two seconds
+    set_energy(&root, 7_000_000, 1_500_000);
+    let metrics = source.sample_at(start + Duration::from_secs(2)).unwrap();
+    assert!((metrics.package_w - 1.0).abs() < 1e-9, "{}", metrics.package_w);
+    assert!((metrics.cpu_w - 0.25).abs() < 1e-9, "{}", metrics.cpu_w);
+    assert_eq!(metrics.gpu_w, 0.0);
+}
+
+#[test]
+fn rapl_handles_a_counter_wrap_at_max_energy_range() {
+    let root = rapl_root("rapl-wrap", 1_000_000_000);
+    set_energy(&root, 999_500_000, 1_000_000_000);
+    let mut source = RaplSource::new(root.path());
+    let start = Instant::now();
+    assert!(source.sample_at(start).is_err());
+
+    // The counters run 0..=max, so a wrap adds max + 1: 0.5 J + 0.5 J, and exactly one step past max
+    set_energy(&root, 499_999, 0);
+    let metrics = source.sample_at(start + Duration::from_secs(1)).unwrap();
+    assert!((metrics.package_w - 1.0).abs() < 1e-9, "{}", metrics.package_w);
+    assert!((metrics.cpu_w - 0.000_001).abs() < 1e-12, "{}", metrics.cpu_w);
+}
+
+const DISKSTATS: &str = "\
+   7       0 loop0 120 0 2400 10 0 0 0 0 0 20 10 0 0 0 0
+   1       0 ram0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0
+   8       0 sda 1234 56 78900 1000 4321 65 98700 2000 0 3000 3000 0 0 0 0
+   8       1 sda1 1000 50 70000 900 4000 60 90000 1800 0 2700 2700 0 0 0 0
+   8      16 sdb 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0
+ 259       0 nvme0n1 500 0 2048 100 200 0 4096 50 0 150 150 0 0 0 0
+ 259       1 nvme0n1p1 400 0 1024 80 100 0 2048 40 0 120 120 0 0 0 0
+ 253       0 dm-0 300 0 1000 60 150 0 3000 30 0 90 90 0 0 0 0
+   8      32 short 1 2 3
+";
+
+#[test]
+fn parse_diskstats_converts_sectors_to_bytes() {
+    let disks = parse_diskstats(DISKSTATS);
+    let names: Vec<&str> = disks.iter().map(|d| d.device.as_str()).collect();
+    assert_eq!(names, ["loop0", "ram0", "sda", "sda1", "sdb", "nvme0n1", "nvme0n1p1", "dm-0"]);
+    let sda = &disks[2];
+    // diskstats 总是按 512 字节扇区计数
+    assert_eq!((sda.read_bytes, sda.written_bytes), (78900 * 512, 98700 * 512));
+    assert_eq!((sda.read_bytes_per_sec, sda.write_bytes_per_sec), (0.0, 0.0));
+}
+
+#[tokio::test]
+async fn disk_io_keeps_only_whole_physical_devices_with_activity() {
+    let root = FixtureRoot::new("diskstats");
+    root.write("proc/diskstats", DISKSTATS);
+    for device in ["loop0", "ram0", "sda", "sdb", "nvme0n1", "dm-0"] {
+        root.write(&format!("sys/block/{}/size", device), "1000");
+    }
+
+    let disks = DiskstatsSource::new(root.path()).sample().await.unwrap();
+    let names: Vec<&str> = disks.iter().map(|d| d.device.as_str()).collect();
+    assert_eq!(names, ["sda", "nvme0n1", "dm-0"]);
+
+    // Without /sys/block partitions can't be told apart, but loop and RAM disks still go
+    fs::remove_dir_all(root.path().join("sys")).unwrap();
+    let disks = DiskstatsSource::new(root.path()).sample().await.unwrap();
+    let names: Vec<&str> = disks.iter().map(|d| d.device.as_str()).collect();
+    assert_eq!(names, ["sda", "sda1", "nvme0n1", "nvme0n1p1", "dm-0"]);
+}
+
+/// `cpu0`..`cpu{count-1}` plus the non-CPU entries that live next to them
+fn cpu_root(name: &str, count: usize) -> FixtureRoot {
+    let root = FixtureRoot::new(name);
+    root.write("sys/devices/system/cpu/cpufreq/boost", "1")
+        .write("sys/devices/system/cpu/online", &format!("0-{}", count - 1));
+    for cpu in 0..count {
+        root.write(&format!("sys/devices/system/cpu/cpu{}/online", cpu), "1");
+    }
+    root
+}
+
+fn summary(clusters: &[ClusterMetrics]) -> Vec<(&str, ClusterKind, Vec<usize>)> {
+    clusters.iter().map(|c| (c.name.as_str(), c.kind, c.cores.clone())).collect()
+}
+
+#[test]
+fn intel_hybrid_cores_come_from_cpu_core_and_cpu_atom() {
+    let root = cpu_root("hybrid", 12);
+    root.write("sys/devices/cpu_core/cpus", "0-7")
+        .write("sys/devices/cpu_atom/cpus", "8-11");
+
+    assert_eq!(
+        summary(&read_cpu_topology(root.path())),
+        [
+            ("E-Cluster", ClusterKind::Efficiency, vec![8, 9, 10, 11]),
+            ("P-Cluster", ClusterKind::Performance, (0..8).collect()),
+        ]
+    );
+}
+
+#[test]
+fn mixed_cpu_capacity_separates_efficiency_and_performance_clusters() {
+    let root = cpu_root("capacity", 8);
+    // 小核 4 个，中核 3 个，大核 1 个（类似骁龙的 4+3+1）
+    for (cpu, capacity, cluster_id) in [(0, 446, 0), (1, 446, 0), (2, 446, 0), (3, 446, 0), (4, 871, 1), (5, 871, 1), (6, 871, 1), (7, 1024, 2)] {
+        root.write(&format!("sys/devices/system/cpu/cpu{}/cpu_capacity", cpu), &capacity.to_string())
+            .write(&format!("sys/devices/system/cpu/cpu{}/topology/cluster_id", cpu), &cluster_id.to_string());
+    }
+
+    assert_eq!(
+        summary(&read_cpu_topology(root.path())),
+        [
+            ("E-Cluster", ClusterKind::Efficiency, vec![0, 1, 2, 3]),
+            ("P0-Cluster", ClusterKind::Performance, vec![4, 5, 6]),
+            ("P1-Cluster", ClusterKind::Performance, vec![7]),
+        ]
+    );
+}
+
+#[test]
+fn uniform_cpus_form_a_single_cluster() {
+    let root = cpu_root("uniform", 4);
+    for cpu in 0..4 {
+        root.write(&format!("sys/devices/system/cpu/cpu{}/cpu_capacity", cpu), "1024")
+            .write(&format!("sys/devices/system/cpu/cpu{}/topology/cluster_id", cpu), &(cpu / 2).to_string());
+    }
+    assert_eq!(summary(&read_cpu_topology(root.path())), [("CPU", ClusterKind::Unknown, vec![0, 1, 2, 3])]);
+
+    let plain = cpu_root("plain", 2);
+    assert_eq!(summary(&read_cpu_topology(plain.path())), [("CPU", ClusterKind::Unknown, vec![0, 1])]);
+    assert!(read_cpu_topology(FixtureRoot::new("no-cpus").path()).is_empty());
+}
+
+#[test]
+fn cpu_lists_expand_ranges() {
+    assert_eq!(parse_cpu_list("0-3,8-9,12\n"), [0, 1, 2, 3, 8, 9, 12]);
+    assert_eq!(parse_cpu_list("5"), [5]);
+    assert!(parse_cpu_list("").is_empty());
+}
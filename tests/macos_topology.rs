@@ -0,0 +1,77 @@
+// CPU clusters from `sysctl hw` output; runs on any platform
+
+use system_alert::sources::macos::parse_perflevels;
+use system_alert::types::{ClusterKind, ClusterMetrics};
+
+// Trimmed `sysctl hw` from a MacBook Air (M1)
+const M1: &str = "\
+hw.ncpu: 8
+hw.nperflevels: 2
+hw.perflevel0.physicalcpu: 4
+hw.perflevel0.logicalcpu: 4
+hw.perflevel0.l2cachesize: 12582912
+hw.perflevel0.cpusperl2: 4
+hw.perflevel0.name: Performance
+hw.perflevel1.physicalcpu: 4
+hw.perflevel1.logicalcpu: 4
+hw.perflevel1.l2cachesize: 4194304
+hw.perflevel1.cpusperl2: 4
+hw.perflevel1.name: Efficiency
+hw.memsize: 17179869184
+";
+
+// MacBook Pro (M1 Pro, 10 cores): the performance level spans two L2 clusters
+const M1_PRO: &str = "\
+hw.ncpu: 10
+hw.nperflevels: 2
+hw.perflevel0.physicalcpu: 8
+hw.perflevel0.logicalcpu: 8
+hw.perflevel0.cpusperl2: 4
+hw.perflevel0.name: Performance
+hw.perflevel1.physicalcpu: 2
+hw.perflevel1.logicalcpu: 2
+hw.perflevel1.cpusperl2: 2
+hw.perflevel1.name: Efficiency
+";
+
+// Intel Macs have no performance levels
+const INTEL: &str = "\
+hw.ncpu: 12
+hw.physicalcpu: 6
+hw.logicalcpu: 12
+hw.l2cachesize: 262144
+hw.memsize: 34359738368
+";
+
+fn summary(clusters: &[ClusterMetrics]) -> Vec<(&str, ClusterKind, Vec<usize>)> {
+    clusters.iter().map(|c| (c.name.as_str(), c.kind, c.cores.clone())).collect()
+}
+
+#[test]
+fn m1_has_one_efficiency_and_one_performance_cluster() {
+    assert_eq!(
+        summary(&parse_perflevels(M1)),
+        [
+            ("E-Cluster", ClusterKind::Efficiency, vec![0, 1, 2, 3]),
+            ("P-Cluster", ClusterKind::Performance, vec![4, 5, 6, 7]),
+        ]
+    );
+}
+
+#[test]
+fn m1_pro_splits_the_performance_level_by_l2() {
+    assert_eq!(
+        summary(&parse_perflevels(M1_PRO)),
+        [
+            ("E-Cluster", ClusterKind::Efficiency, vec![0, 1]),
+            ("P0-Cluster", ClusterKind::Performance, vec![2, 3, 4, 5]),
+            ("P1-Cluster", ClusterKind::Performance, vec![6, 7, 8, 9]),
+        ]
+    );
+}
+
+#[test]
+fn intel_has_no_perflevel_clusters() {
+    assert!(parse_perflevels(INTEL).is_empty());
+    assert!(parse_perflevels("").is_empty());
+}
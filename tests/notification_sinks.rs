@@ -0,0 +1,216 @@
+// Notification sinks against local stand-ins
+
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::ffi::OsStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use system_alert::alert_rules::AlertRule;
+use system_alert::notification::{sanitize_text, AlertLevel, Notification, NotificationManager};
+use system_alert::sinks::{connect_address, NotificationSink, OsascriptSink, SinkFuture, SinkRoute, WebhookSink};
+use system_alert::types::SystemData;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+/// Accept one request, answer it with `status` and hand back the raw request
+async fn serve_once(status: &'static str) -> (String, JoinHandle<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/hooks/alert", listener.local_addr().unwrap());
+    let server = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut request = Vec::new();
+        let mut buffer = [0u8; 1024];
+        // 读完请求头，再按 Content-Length 读完请求体
+        loop {
+            let n = stream.read(&mut buffer).await.unwrap();
+            request.extend_from_slice(&buffer[..n]);
+            let text = String::from_utf8_lossy(&request);
+            if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                let length: usize = head
+                    .lines()
+                    .find_map(|line| line.strip_prefix("Content-Length: "))
+                    .and_then(|value| value.parse().ok())
+                    .unwrap_or(0);
+                if body.len() >= length {
+                    break;
+                }
+            }
+            if n == 0 {
+                break;
+            }
+        }
+        stream.write_all(format!("HTTP/1.1 {}\r\nContent-Length: 0\r\n\r\n", status).as_bytes()).await.unwrap();
+        String::from_utf8(request).unwrap()
+    });
+    (url, server)
+}
+
+fn headers(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+    pairs.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect()
+}
+
+#[tokio::test]
+async fn webhook_posts_json_with_custom_headers() {
+    let (url, server) = serve_once("204 No Content").await;
+    let sink = WebhookSink::new(&url, headers(&[("Authorization", "Bearer secret"), ("X-Team", "ops")]));
+    let notification = Notification::new("CPU Alert", "CPU usage is high: 91.0%", AlertLevel::Warning);
+    sink.send(&notification).await.unwrap();
+
+    let request = server.await.unwrap();
+    let (head, body) = request.split_once("\r\n\r\n").unwrap();
+    let mut lines = head.lines();
+    assert_eq!(lines.next(), Some("POST /hooks/alert HTTP/1.1"));
+    let header_lines: Vec<&str> = lines.collect();
+    for expected in ["Content-Type: application/json", "Authorization: Bearer secret", "X-Team: ops"] {
+        assert!(header_lines.contains(&expected), "missing '{}' in {:?}", expected, header_lines);
+    }
+    assert!(header_lines.contains(&format!("Content-Length: {}", body.len()).as_str()));
+
+    let payload: serde_json::Value = serde_json::from_str(body).unwrap();
+    assert_eq!(payload["title"], "CPU Alert");
+    assert_eq!(payload["message"], "CPU usage is high: 91.0%");
+    assert_eq!(payload["level"], "warning");
+    assert_eq!(payload["resolved"], false);
+    assert!(payload["timestamp"].as_f64().is_some_and(|t| t > 0.0));
+}
+
+#[tokio::test]
+async fn webhook_reports_a_non_2xx_status() {
+    let (url, server) = serve_once("500 Internal Server Error").await;
+    let sink = WebhookSink::new(&url, BTreeMap::new());
+    let error = sink
+        .send(&Notification::resolved("CPU Alert", "CPU usage is back to 40.0%", AlertLevel::Warning))
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("500 Internal Server Error"), "{}", error);
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn webhook_rejects_headers_with_line_breaks() {
+    let notification = Notification::new("CPU Alert", "high", AlertLevel::Critical);
+    for pairs in [[("X-Token", "abc\r\nX-Injected: 1")], [("X-Bad\nName", "value")]] {
+        // 在连接之前就拒绝，所以地址不必可用
+        let sink = WebhookSink::new("http://127.0.0.1:9/hook", headers(&pairs));
+        let error = sink.send(&notification).await.unwrap_err();
+        assert!(error.to_string().contains("invalid webhook header"), "{}", error);
+    }
+}
+
+/// A sink that takes `delay` to deliver and records the titles it got
+struct SlowSink {
+    delay: Duration,
+    delivered: Arc<Mutex<Vec<String>>>,
+}
+
+impl NotificationSink for SlowSink {
+    fn name(&self) -> &'static str {
+        "slow"
+    }
+
+    fn send<'a>(&'a self, notification: &'a Notification) -> SinkFuture<'a> {
+        Box::pin(async move {
+            tokio::time::sleep(self.delay).await;
+            self.delivered.lock().unwrap().push(notification.title().to_string());
+            Ok(())
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct Rules {
+    alerts: Vec<AlertRule>,
+}
+
+#[tokio::test]
+async fn slow_sinks_do_not_hold_up_the_caller() {
+    let rules: Rules = toml::from_str(
+        "[[alerts]]\nname = \"cpu\"\nmetric = \"cpu_info.average_usage\"\nop = \">\"\nthreshold = 50\nlevel = \"warning\"",
+    )
+    .unwrap();
+    let delivered = Arc::new(Mutex::new(Vec::new()));
+    let mut manager = NotificationManager::new(true, 3600);
+    manager.set_rules(rules.alerts);
+    manager.set_sinks(vec![SinkRoute::new(
+        Box::new(SlowSink { delay: Duration::from_millis(500), delivered: delivered.clone() }),
+        Vec::new(),
+    )]);
+
+    let data: SystemData = serde_json::from_str(include_str!("fixtures/system_data.json")).unwrap();
+    let started = Instant::now();
+    let notifications = manager.check_and_send_notifications(&data).unwrap();
+    assert_eq!(notifications.len(), 1);
+    assert!(started.elapsed() < Duration::from_millis(100), "{:?}", started.elapsed());
+
+    // 投递在后台任务里完成
+    tokio::time::sleep(Duration::from_millis(800)).await;
+    assert_eq!(*delivered.lock().unwrap(), [notifications[0].title().to_string()]);
+}
+
+#[test]
+fn https_webhook_headers_go_to_curl_on_stdin() {
+    let sink = WebhookSink::new(
+        "https://hooks.example.com/alert",
+        headers(&[("Authorization", "Bearer secret"), ("X-Note", r#"say "hi" \ bye"#)]),
+    );
+    let (command, config) = sink.curl("{}").unwrap();
+    let args: Vec<String> = command.as_std().get_args().map(|arg| arg.to_string_lossy().into_owned()).collect();
+
+    // 令牌不能出现在 argv 里，否则 ps 就能看到
+    assert!(args.iter().all(|arg| !arg.contains("secret")), "{:?}", args);
+    assert!(args.windows(2).any(|pair| pair == ["--config", "-"]));
+    assert_eq!(args.last().unwrap(), "https://hooks.example.com/alert");
+    assert_eq!(config, "header = \"Authorization: Bearer secret\"\nheader = \"X-Note: say \\\"hi\\\" \\\\ bye\"\n");
+
+    let sink = WebhookSink::new("https://hooks.example.com/alert", headers(&[("X-Token", "a\nb")]));
+    assert!(sink.curl("{}").is_err());
+}
+
+#[test]
+fn http_webhook_addresses_default_to_port_80() {
+    assert_eq!(connect_address("relay.local"), "relay.local:80");
+    assert_eq!(connect_address("relay.local:8080"), "relay.local:8080");
+    // IPv6 字面量里的冒号不是端口
+    assert_eq!(connect_address("[::1]"), "[::1]:80");
+    assert_eq!(connect_address("[fe80::1]:8080"), "[fe80::1]:8080");
+}
+
+#[test]
+fn sanitize_text_keeps_quotes_backslashes_and_unicode() {
+    let text = r#"Volume "Backup" at C:\data — 备份盘 🔥 'quoted'"#;
+    assert_eq!(sanitize_text(text), text);
+}
+
+#[test]
+fn sanitize_text_replaces_control_characters() {
+    assert_eq!(sanitize_text("CPU\u{7}die\nis\thot\u{1b}[31m"), "CPU die is hot [31m");
+    assert_eq!(sanitize_text("\r\nfan\0\r\n"), "fan");
+}
+
+#[test]
+fn sanitize_text_truncates_on_a_character_boundary() {
+    let short = "é".repeat(256);
+    assert_eq!(sanitize_text(&short), short);
+
+    for unit in ["é", "🔥", "a"] {
+        let clean = sanitize_text(&unit.repeat(300));
+        assert_eq!(clean.chars().count(), 256);
+        assert!(clean.ends_with('…'));
+        assert_eq!(clean.trim_end_matches('…'), unit.repeat(255));
+    }
+}
+
+#[test]
+fn osascript_passes_text_as_separate_arguments() {
+    let title = r#"Disk "Backup" \ 备份"#;
+    let message = r#"end tell" & do shell script "rm -rf ~" & " 🔥"#;
+    let command = OsascriptSink.command(&Notification::new(title, message, AlertLevel::Critical));
+    let args: Vec<&OsStr> = command.as_std().get_args().collect();
+
+    assert_eq!(command.as_std().get_program(), "osascript");
+    // 脚本本身固定不变，文本只出现在 "--" 之后的 argv 里
+    let script: Vec<&OsStr> = args.iter().take(6).copied().collect();
+    assert!(script.iter().all(|arg| !arg.to_string_lossy().contains("Backup")));
+    assert_eq!(args[6..], [OsStr::new("--"), OsStr::new(message), OsStr::new(title), OsStr::new("Critical Alert")]);
+}
@@ -0,0 +1,35 @@
+// Power draw history behind the Power/Battery view
+
+use system_alert::history::HistoryData;
+use system_alert::types::SystemData;
+
+const SAMPLE: &str = include_str!("fixtures/system_data.json");
+
+#[test]
+fn records_package_and_battery_power() {
+    let mut data: SystemData = serde_json::from_str(SAMPLE).unwrap();
+    let mut history = HistoryData::new(2);
+    history.update_from_system_data(&data);
+
+    // 接上电源开始充电
+    data.cpu_info.power_metrics.package_w = 7.5;
+    data.battery_info.amperage = 2.0;
+    history.update_from_system_data(&data);
+    history.update_from_system_data(&data);
+
+    assert_eq!(history.power_history, [7.5, 7.5]);
+    assert_eq!(history.battery_power_history, [25.0, 25.0]);
+
+    history.set_max_size(1);
+    assert_eq!(history.power_history, [7.5]);
+}
+
+#[test]
+fn discharging_is_negative_battery_power() {
+    let data: SystemData = serde_json::from_str(SAMPLE).unwrap();
+    let mut history = HistoryData::new(5);
+    history.update_from_system_data(&data);
+    assert_eq!(history.power_history, [3.0]);
+    // 12.5 V at -1 A
+    assert_eq!(history.battery_power_history, [-12.5]);
+}
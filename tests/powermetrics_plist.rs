@@ -0,0 +1,127 @@
+// powermetrics plist parsing against captured samples; runs on any platform
+
+use system_alert::sources::plist::{self, PlistValue};
+use system_alert::sources::powermetrics::{parse_latest, parse_sample, split_samples};
+use system_alert::sources::CollectorError;
+use system_alert::types::ClusterKind;
+
+const M1: &str = include_str!("fixtures/powermetrics/m1.plist");
+const M1_PRO: &str = include_str!("fixtures/powermetrics/m1_pro.plist");
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-6
+}
+
+#[test]
+fn m1_clusters_cores_and_power() {
+    let sample = parse_sample(M1).unwrap();
+    assert_eq!(sample.hw_model.as_deref(), Some("MacBookAir10,1"));
+    assert_eq!(sample.elapsed_ns, 1_004_512_042);
+
+    let names: Vec<&str> = sample.clusters.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, ["E-Cluster", "P-Cluster"]);
+    let e = &sample.clusters[0];
+    assert_eq!(e.kind(), ClusterKind::Efficiency);
+    assert!(close(e.freq_mhz, 1200.0));
+    assert!(close(e.active_percent(), 45.0));
+    assert_eq!(e.cpus.iter().map(|c| c.cpu).collect::<Vec<_>>(), [0, 1, 2, 3]);
+    assert_eq!(e.dvfs.len(), 5);
+    assert_eq!(e.dvfs[0].freq_mhz, 600);
+    assert!(close(e.dvfs[0].used_ratio, 0.15));
+
+    let p = &sample.clusters[1];
+    assert_eq!(p.kind(), ClusterKind::Performance);
+    assert!(close(p.active_percent(), 20.0));
+    assert!(close(p.cpus[0].freq_mhz, 2000.0));
+    assert!(close(p.cpus[0].down_ratio, 0.02));
+
+    let gpu = sample.gpu.as_ref().unwrap();
+    assert!(close(gpu.freq_mhz, 389.0));
+    assert!(close(gpu.active_percent(), 8.0));
+    assert!(close(gpu.power_w, 0.08725));
+
+    assert!(close(sample.cpu_power_w, 1.5235));
+    assert!(close(sample.gpu_power_w, 0.08725));
+    assert!(close(sample.ane_power_w, 0.0));
+    assert!(close(sample.package_power_w, 1.61075));
+
+    let metrics = sample.cpu_metrics();
+    let summary: Vec<(&str, f32, u32, Vec<usize>)> = metrics
+        .clusters
+        .iter()
+        .map(|c| (c.name.as_str(), c.active_percent.round(), c.freq_mhz, c.cores.clone()))
+        .collect();
+    assert_eq!(summary, [("E-Cluster", 45.0, 1200, vec![0, 1, 2, 3]), ("P-Cluster", 20.0, 2000, vec![4, 5, 6, 7])]);
+}
+
+// 旧版本只有 *_energy 和 used_ns，P1 簇也没有 freq_hz
+#[test]
+fn m1_pro_energy_only_sample() {
+    let sample = parse_sample(M1_PRO).unwrap();
+    let names: Vec<&str> = sample.clusters.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, ["E-Cluster", "P0-Cluster", "P1-Cluster"]);
+    let cores: Vec<usize> = sample.clusters.iter().map(|c| c.cpus.len()).collect();
+    assert_eq!(cores, [2, 4, 4]);
+
+    let p1 = &sample.clusters[2];
+    assert!(close(p1.dvfs[1].used_ratio, 0.05));
+    assert!(close(p1.freq_mhz, 1914.0));
+    assert!(close(p1.cpus[0].freq_mhz, 1914.0));
+
+    assert!(close(sample.cpu_power_w, 4.0));
+    assert!(close(sample.ane_power_w, 0.5));
+    assert!(close(sample.gpu_power_w, 1.0));
+    assert!(close(sample.package_power_w, 5.5));
+
+    let metrics = sample.cpu_metrics();
+    let kinds: Vec<ClusterKind> = metrics.clusters.iter().map(|c| c.kind).collect();
+    assert_eq!(kinds, [ClusterKind::Efficiency, ClusterKind::Performance, ClusterKind::Performance]);
+    let active: Vec<f32> = metrics.clusters.iter().map(|c| c.active_percent.round()).collect();
+    assert_eq!(active, [60.0, 40.0, 10.0]);
+    assert_eq!(metrics.clusters[2].freq_mhz, 1914);
+    assert_eq!(metrics.clusters[2].cores, [6, 7, 8, 9]);
+    assert!(close(metrics.average_freq_mhz(), (1500.0 * 2.0 + 2500.0 * 4.0 + 1914.0 * 4.0) / 10.0));
+}
+
+#[test]
+fn stream_of_samples_keeps_the_latest() {
+    let stream = format!("{}\0{}\0", M1, M1_PRO);
+    assert_eq!(split_samples(&stream).count(), 2);
+    let latest = parse_latest(&stream).unwrap();
+    assert_eq!(latest.hw_model.as_deref(), Some("MacBookPro18,3"));
+}
+
+#[test]
+fn plist_values_and_entities() {
+    let root = plist::parse("fixture", M1_PRO).unwrap();
+    assert_eq!(
+        root.get("kern_bootargs").and_then(PlistValue::as_str),
+        Some("debug=0x0 & amfi=<off>")
+    );
+    assert_eq!(root.get("is_delta").and_then(PlistValue::as_bool), Some(true));
+    assert_eq!(root.get("timestamp"), Some(&PlistValue::Date("2022-08-20T18:40:11Z".to_string())));
+
+    let value = plist::parse("inline", "<array><integer>-3</integer><real>1.5e3</real><string/><dict/></array>").unwrap();
+    assert_eq!(
+        value,
+        PlistValue::Array(vec![
+            PlistValue::Integer(-3),
+            PlistValue::Real(1500.0),
+            PlistValue::String(String::new()),
+            PlistValue::Dict(Vec::new()),
+        ])
+    );
+}
+
+#[test]
+fn bad_output_is_a_parse_error() {
+    // 文本格式的输出、截断的文档、没有 processor 的样本
+    let truncated = &M1[..M1.len() / 2];
+    let without_processor = "<plist version=\"1.0\"><dict><key>elapsed_ns</key><integer>1</integer></dict></plist>";
+    for output in ["*** Sampled system activity ***", truncated, without_processor, "", "\0\n"] {
+        match parse_latest(output) {
+            Err(CollectorError::Parse { source, .. }) => assert_eq!(source, "powermetrics"),
+            other => panic!("expected a parse error for {:?}, got {:?}", output, other),
+        }
+    }
+}
@@ -0,0 +1,77 @@
+// The streaming powermetrics sampler, driven by a fake child script
+
+use std::time::Duration;
+use system_alert::sources::sampler::{PowermetricsSampler, SamplerCommand, SamplerState};
+use system_alert::sources::CollectorError;
+use tokio::sync::watch;
+
+const FAKE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/powermetrics/fake_powermetrics.sh");
+const M1: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/powermetrics/m1.plist");
+const M1_PRO: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/powermetrics/m1_pro.plist");
+
+fn fake(args: &[&str]) -> SamplerCommand {
+    let mut all = vec![FAKE];
+    all.extend_from_slice(args);
+    SamplerCommand::new("sh", &all).with_backoff(Duration::from_millis(20), Duration::from_millis(100))
+}
+
+/// Wait for the next published state
+async fn next(state: &mut watch::Receiver<SamplerState>) -> SamplerState {
+    tokio::time::timeout(Duration::from_secs(5), state.changed())
+        .await
+        .expect("sampler published nothing within 5s")
+        .unwrap();
+    state.borrow_and_update().clone()
+}
+
+fn model(state: &SamplerState) -> Option<String> {
+    match state {
+        Some(Ok(update)) => update.sample.hw_model.clone(),
+        _ => None,
+    }
+}
+
+#[tokio::test]
+async fn publishes_each_sample_in_order() {
+    let sampler = PowermetricsSampler::spawn(fake(&["--interval", "0.3", M1, M1_PRO]));
+    let mut state = sampler.subscribe();
+    assert_eq!(model(&next(&mut state).await).as_deref(), Some("MacBookAir10,1"));
+    assert_eq!(model(&next(&mut state).await).as_deref(), Some("MacBookPro18,3"));
+    assert_eq!(sampler.latest().unwrap().sample.thermal_pressure.as_deref(), Some("Nominal"));
+}
+
+// 子进程退出后先报告原因，退避之后重启并继续出样本
+#[tokio::test]
+async fn restarts_the_child_after_it_exits() {
+    let command = fake(&["--interval", "0.3", "--exit", "3", "--stderr", "sampler crashed", M1])
+        .with_backoff(Duration::from_millis(300), Duration::from_millis(300));
+    let sampler = PowermetricsSampler::spawn(command);
+    let mut state = sampler.subscribe();
+    for _ in 0..2 {
+        assert!(model(&next(&mut state).await).is_some());
+        match next(&mut state).await {
+            Some(Err(CollectorError::Failed { reason, .. })) => assert_eq!(reason, "sampler crashed"),
+            other => panic!("expected the exit to be reported, got {:?}", other),
+        }
+    }
+}
+
+#[tokio::test]
+async fn reports_why_the_child_cannot_run() {
+    let sampler = PowermetricsSampler::spawn(fake(&["--exit", "1", "--stderr", "powermetrics must be invoked as the superuser"]));
+    let mut state = sampler.subscribe();
+    assert!(matches!(next(&mut state).await, Some(Err(CollectorError::PermissionDenied(_)))));
+
+    let missing = PowermetricsSampler::spawn(SamplerCommand::new("/nonexistent/powermetrics", &[]));
+    let mut state = missing.subscribe();
+    assert!(matches!(next(&mut state).await, Some(Err(CollectorError::NotFound(_)))));
+}
+
+#[tokio::test]
+async fn bad_documents_do_not_stop_the_stream() {
+    // 脚本本身不是 plist，正好当作坏样本
+    let sampler = PowermetricsSampler::spawn(fake(&["--interval", "0.3", FAKE, M1]));
+    let mut state = sampler.subscribe();
+    assert!(matches!(next(&mut state).await, Some(Err(CollectorError::Parse { .. }))));
+    assert_eq!(model(&next(&mut state).await).as_deref(), Some("MacBookAir10,1"));
+}
@@ -0,0 +1,22 @@
+// Start times read back just before acting on a PID
+
+use std::process::Command;
+use sysinfo::Pid;
+use system_alert::process_control::current_start_time;
+
+#[test]
+fn a_running_process_has_a_start_time() {
+    let own = Pid::from_u32(std::process::id());
+    let start_time = current_start_time(own).unwrap();
+    assert!(start_time > 0);
+    // 同一进程两次读取的启动时间一致
+    assert_eq!(current_start_time(own), Some(start_time));
+}
+
+#[test]
+fn an_exited_process_has_none() {
+    let mut child = Command::new("true").spawn().unwrap();
+    let pid = Pid::from_u32(child.id());
+    child.wait().unwrap();
+    assert_eq!(current_start_time(pid), None);
+}
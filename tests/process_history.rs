@@ -0,0 +1,101 @@
+// Per-process history: which processes are recorded and for how long
+
+use sysinfo::Pid;
+use system_alert::history::{HistoryData, PROCESS_GRACE_SAMPLES};
+use system_alert::types::{ProcessInfo, SystemData};
+
+const SAMPLE: &str = include_str!("fixtures/system_data.json");
+
+/// The fixture's process under another PID, with the given CPU usage
+fn process(pid: usize, cpu_usage: f32) -> ProcessInfo {
+    let data: SystemData = serde_json::from_str(SAMPLE).unwrap();
+    let mut process = data.process_info[0].clone();
+    process.pid = Pid::from(pid);
+    process.cpu_usage = cpu_usage;
+    process
+}
+
+/// History of the single busiest process, keeping `max_size` points
+fn top_one(max_size: usize) -> HistoryData {
+    let mut history = HistoryData::new(max_size);
+    history.set_tracked_processes(1);
+    history
+}
+
+fn cpu(history: &HistoryData, pid: usize) -> Option<Vec<f32>> {
+    history.process(Pid::from(pid)).map(|p| p.cpu_history.iter().copied().collect())
+}
+
+#[test]
+fn a_process_that_leaves_the_top_n_is_kept_for_a_grace_period() {
+    let mut history = top_one(3);
+    history.update_processes(&[process(1, 50.0), process(2, 10.0)]);
+    assert_eq!(cpu(&history, 2), None);
+
+    // 2 号进程挤掉 1 号后，1 号仍继续记录 PROCESS_GRACE_SAMPLES 个样本
+    for usage in 1..=PROCESS_GRACE_SAMPLES {
+        history.update_processes(&[process(1, usage as f32), process(2, 90.0)]);
+        assert!(cpu(&history, 1).is_some(), "dropped at {}", usage);
+    }
+
+    history.update_processes(&[process(1, 0.0), process(2, 90.0)]);
+    assert_eq!(cpu(&history, 1), None);
+    assert_eq!(cpu(&history, 2), Some(vec![90.0, 90.0, 90.0]));
+}
+
+#[test]
+fn the_grace_period_does_not_grow_with_the_history_size() {
+    let mut history = top_one(1000);
+    history.update_processes(&[process(1, 50.0), process(2, 10.0)]);
+    for _ in 0..=PROCESS_GRACE_SAMPLES {
+        history.update_processes(&[process(1, 5.0), process(2, 90.0)]);
+    }
+    assert_eq!(cpu(&history, 1), None);
+}
+
+#[test]
+fn returning_to_the_top_n_resets_the_grace_period() {
+    let mut history = top_one(2);
+    history.update_processes(&[process(1, 50.0), process(2, 10.0)]);
+    history.update_processes(&[process(1, 5.0), process(2, 90.0)]);
+    history.update_processes(&[process(1, 6.0), process(2, 90.0)]);
+    history.update_processes(&[process(1, 70.0), process(2, 10.0)]);
+
+    history.update_processes(&[process(1, 7.0), process(2, 90.0)]);
+    history.update_processes(&[process(1, 8.0), process(2, 90.0)]);
+    assert_eq!(cpu(&history, 1), Some(vec![7.0, 8.0]));
+}
+
+#[test]
+fn exited_and_restarted_processes_are_dropped_right_away() {
+    let mut history = top_one(5);
+    history.update_processes(&[process(1, 50.0), process(2, 10.0)]);
+    history.update_processes(&[process(1, 5.0), process(2, 90.0)]);
+    assert!(cpu(&history, 1).is_some());
+
+    // The PID now belongs to a process started later
+    let mut reused = process(1, 5.0);
+    reused.start_time += 60;
+    history.update_processes(&[reused.clone(), process(2, 90.0)]);
+    assert_eq!(cpu(&history, 1), None);
+
+    // A tracked PID that is reused starts over
+    reused.cpu_usage = 95.0;
+    history.update_processes(&[reused.clone(), process(2, 90.0)]);
+    reused.start_time += 60;
+    history.update_processes(&[reused, process(2, 90.0)]);
+    assert_eq!(cpu(&history, 1), Some(vec![95.0]));
+
+    history.update_processes(&[process(1, 99.0)]);
+    assert_eq!(cpu(&history, 2), None);
+}
+
+#[test]
+fn pinned_processes_are_recorded_outside_the_top_n() {
+    let mut history = top_one(2);
+    history.set_pinned([Pid::from(3)].into_iter().collect());
+    for _ in 0..4 {
+        history.update_processes(&[process(1, 50.0), process(3, 1.0)]);
+    }
+    assert_eq!(cpu(&history, 3), Some(vec![1.0, 1.0]));
+}
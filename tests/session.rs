@@ -0,0 +1,117 @@
+// Sample feeds: live refresh intervals and recorded sessions
+
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+use system_alert::data_collector::{CollectorSchedule, DataCollector};
+use system_alert::session::{SampleFeed, SessionRecorder, SessionReplayer};
+use system_alert::sources::MetricSources;
+use system_alert::types::SystemData;
+use tokio::time::Instant;
+
+const SAMPLE: &str = include_str!("fixtures/system_data.json");
+
+/// A session file in the temp directory, removed again on drop
+struct SessionFile(PathBuf);
+
+impl SessionFile {
+    fn new(name: &str) -> Self {
+        Self(std::env::temp_dir().join(format!("system_alert-{}-{}.jsonl", name, std::process::id())))
+    }
+}
+
+impl Drop for SessionFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+/// The fixture taken `secs` seconds in, tagged through its CPU usage
+fn sample(secs: u64, tag: f32) -> SystemData {
+    let mut data: SystemData = serde_json::from_str(SAMPLE).unwrap();
+    data.timestamp += Duration::from_secs(secs);
+    data.cpu_info.average_usage = tag;
+    data
+}
+
+fn record(file: &SessionFile, samples: &[SystemData]) {
+    let mut recorder = SessionRecorder::create(&file.0).unwrap();
+    for data in samples {
+        recorder.record(data).unwrap();
+    }
+}
+
+fn tag(data: Option<SystemData>) -> Option<f32> {
+    data.map(|data| data.cpu_info.average_usage)
+}
+
+#[tokio::test]
+async fn a_zero_refresh_rate_counts_as_one_second() {
+    let collector = DataCollector::start(MetricSources::unsupported(), CollectorSchedule::default());
+    let mut feed = SampleFeed::live(collector, 0, None);
+    feed.tick().await;
+    feed.set_refresh_rate(0);
+    feed.tick().await;
+
+    // 第二次 tick 要等满一秒，而不是立即返回
+    let started = Instant::now();
+    feed.tick().await;
+    assert!(started.elapsed() >= Duration::from_millis(900));
+}
+
+#[tokio::test]
+async fn replays_recorded_samples_in_order_at_scaled_speed() {
+    let file = SessionFile::new("round-trip");
+    let recorded = [sample(0, 1.0), sample(2, 2.0), sample(6, 3.0)];
+    record(&file, &recorded);
+
+    // 20x: the 2s and 4s gaps become 100ms and 200ms
+    let mut replayer = SessionReplayer::open(&file.0, 20.0).unwrap();
+    let mut gaps = Vec::new();
+    let mut tags = Vec::new();
+    for _ in 0..recorded.len() {
+        let started = Instant::now();
+        replayer.tick().await;
+        gaps.push(started.elapsed());
+        let data = replayer.next_sample().unwrap().unwrap();
+        assert_eq!(data.timestamp, recorded[tags.len()].timestamp);
+        tags.push(data.cpu_info.average_usage);
+    }
+    assert_eq!(tags, [1.0, 2.0, 3.0]);
+    assert!(gaps[0] < Duration::from_millis(50), "{:?}", gaps);
+    for (gap, expected) in gaps[1..].iter().zip([100, 200]) {
+        let expected = Duration::from_millis(expected);
+        assert!(*gap >= expected && *gap < expected * 4, "{:?}", gaps);
+    }
+
+    // 回放结束后 tick 不再返回
+    assert!(tokio::time::timeout(Duration::from_millis(300), replayer.tick()).await.is_err());
+    assert!(replayer.next_sample().unwrap().is_none());
+}
+
+#[tokio::test]
+async fn a_truncated_final_line_ends_the_replay() {
+    let file = SessionFile::new("truncated");
+    record(&file, &[sample(0, 1.0), sample(1, 2.0)]);
+    let partial = serde_json::to_string(&sample(2, 3.0)).unwrap();
+    let mut content = fs::read_to_string(&file.0).unwrap();
+    content.push_str(&partial[..partial.len() / 2]);
+    fs::write(&file.0, content).unwrap();
+
+    let mut replayer = SessionReplayer::open(&file.0, 1000.0).unwrap();
+    assert_eq!(tag(replayer.next_sample().unwrap()), Some(1.0));
+    replayer.tick().await;
+    assert_eq!(tag(replayer.next_sample().unwrap()), Some(2.0));
+    assert_eq!(tag(replayer.next_sample().unwrap()), None);
+}
+
+#[tokio::test]
+async fn a_corrupt_line_before_the_end_is_an_error() {
+    let file = SessionFile::new("corrupt");
+    let lines = [serde_json::to_string(&sample(0, 1.0)).unwrap(), "{\"oops\"".to_string(), serde_json::to_string(&sample(2, 3.0)).unwrap()];
+    fs::write(&file.0, lines.join("\n")).unwrap();
+
+    let mut replayer = SessionReplayer::open(&file.0, 1.0).unwrap();
+    let error = replayer.next_sample().unwrap_err();
+    assert!(error.to_string().contains("line 2"), "{}", error);
+}